@@ -0,0 +1,92 @@
+//! Awaiting asset loads with `Loader::load_async`.
+
+use std::{
+    str::from_utf8,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{sleep, spawn},
+    time::Duration,
+};
+
+use futures::{executor::block_on, join};
+use rayon::ThreadPoolBuilder;
+
+use amethyst_assets::*;
+use amethyst_core::ecs::prelude::VecStorage;
+use amethyst_error::Error;
+
+#[derive(Clone, Debug)]
+struct DummyAsset(String);
+
+impl Asset for DummyAsset {
+    const NAME: &'static str = "example::DummyAsset";
+    type Data = String;
+    type HandleStorage = VecStorage<Handle<DummyAsset>>;
+}
+
+#[derive(Clone, Debug)]
+struct DummyFormat;
+
+impl Format<String> for DummyFormat {
+    fn name(&self) -> &'static str {
+        "DUMMY"
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<String, Error> {
+        Ok(from_utf8(&bytes)?.to_owned())
+    }
+}
+
+fn main() {
+    let path = format!("{}/examples/assets", env!("CARGO_MANIFEST_DIR"));
+
+    let builder = ThreadPoolBuilder::new().num_threads(8);
+    let pool = Arc::new(builder.build().expect("Invalid config"));
+
+    let loader = Loader::new(&path, pool.clone());
+    let mut storage: AssetStorage<DummyAsset> = AssetStorage::new();
+
+    let a = loader.load_async("dummy/whatever.dum", DummyFormat, "", &storage);
+    let b = loader.load_async("dummy/another.dum", DummyFormat, "", &storage);
+    let c = loader.load_async("dummy/third.dum", DummyFormat, "", &storage);
+
+    // The futures only resolve once the storage has processed the assets,
+    // so await them on another thread while the "game loop" keeps running.
+    let done = Arc::new(AtomicBool::new(false));
+    let waiter = spawn({
+        let done = done.clone();
+        move || {
+            let handles = block_on(async { join!(a, b, c) });
+            done.store(true, Ordering::Release);
+            handles
+        }
+    });
+
+    // Game loop
+    let mut frame_number = 0;
+    while !done.load(Ordering::Acquire) {
+        frame_number += 1;
+
+        // Do per-frame stuff (display loading screen, ..)
+        sleep(Duration::from_millis(10));
+
+        storage.process(
+            |s| Ok(ProcessingState::Loaded(DummyAsset(s))),
+            frame_number,
+            &*pool,
+            None,
+        );
+    }
+
+    let (a, b, c) = waiter.join().expect("Waiting thread panicked");
+    for handle in &[
+        a.expect("Failed to load whatever.dum"),
+        b.expect("Failed to load another.dum"),
+        c.expect("Failed to load third.dum"),
+    ] {
+        let asset = storage.get(handle).expect("Asset is not in the storage");
+        println!("{:?}", asset);
+    }
+}
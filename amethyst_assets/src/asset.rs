@@ -1,7 +1,9 @@
-use crate::{storage::ProcessingState, FormatRegisteredData, Handle, Reload, SingleFile, Source};
+use crate::{
+    storage::ProcessingState, FormatRegisteredData, Handle, MapFormat, Reload, SingleFile, Source,
+};
 use amethyst_core::ecs::storage::UnprotectedStorage;
 use amethyst_error::{Error, ResultExt};
-use std::{fmt::Debug, ops::Deref, sync::Arc};
+use std::{fmt::Debug, io::Read, ops::Deref, sync::Arc};
 
 #[cfg(feature = "profiler")]
 use thread_profiler::profile_scope;
@@ -55,6 +57,24 @@ pub trait Format<D: 'static>: objekt::Clone + Debug + Send + Sync + 'static {
     /// A unique identifier for this format.
     fn name(&self) -> &'static str;
 
+    /// The file extensions (without the leading dot) this format is usually used with.
+    ///
+    /// This is used to pick files when loading whole directories with `Loader::load_dir`.
+    /// An empty list, which is the default, means the format doesn't have specific extensions.
+    fn extensions(&self) -> &[&'static str] {
+        &[]
+    }
+
+    /// Whether loads of the same asset with this format can share a handle, see
+    /// `Loader::load_from`.
+    ///
+    /// Formats are told apart by their type and `Debug` output, so formats with options
+    /// which don't show up in their `Debug` output, like closures, have to return `false`.
+    /// The default is `true`.
+    fn shareable(&self) -> bool {
+        true
+    }
+
     /// Produces asset data from given bytes.
     /// This method is a simplified version of `format`.
     /// This format assumes that the asset name is the full path and the asset is only
@@ -77,6 +97,8 @@ pub trait Format<D: 'static>: objekt::Clone + Debug + Send + Sync + 'static {
     /// to reload assets if necessary (for hot reloading).
     /// You should only create `Reload` when `create_reload` is `Some`.
     /// Also, the parameter is just a request, which means it's optional either way.
+    /// Formats which read more than one file can use a `MultiFileReader`, so that
+    /// changes to any of the files trigger a reload.
     fn import(
         &self,
         name: String,
@@ -89,9 +111,10 @@ pub trait Format<D: 'static>: objekt::Clone + Debug + Send + Sync + 'static {
             let (b, m) = source
                 .load_with_metadata(&name)
                 .with_context(|_| crate::error::Error::Source)?;
+            let reload = SingleFile::new(boxed_format, m, name, source);
             Ok(FormatValue {
                 data: self.import_simple(b)?,
-                reload: Some(Box::new(SingleFile::new(boxed_format, m, name, source))),
+                reload: Some(Box::new(reload)),
             })
         } else {
             let b = source
@@ -100,15 +123,46 @@ pub trait Format<D: 'static>: objekt::Clone + Debug + Send + Sync + 'static {
             Ok(FormatValue::data(self.import_simple(b)?))
         }
     }
+
+    /// Wraps this format, so `mapper` is applied to the data it imports, including
+    /// when the asset is hot reloaded. See `MapFormat`.
+    ///
+    /// ```rust,ignore
+    /// let format = ObjFormat.map(|mut mesh: MeshData| {
+    ///     mesh.scale(0.01);
+    ///     Ok(mesh)
+    /// });
+    /// ```
+    fn map<M>(self, mapper: M) -> MapFormat<Self, M>
+    where
+        Self: Sized,
+        M: Fn(D) -> Result<D, Error> + Clone + Send + Sync + 'static,
+    {
+        MapFormat::new(self, mapper)
+    }
 }
 
 objekt::clone_trait_object!(<D> Format<D>);
 
+/// A format which parses asset data while it is read from the source, instead of getting
+/// all of the bytes at once like a `Format` does.
+///
+/// This keeps the memory needed to load big assets (like long audio tracks or huge
+/// terrains) bounded. Streaming formats are used with `Loader::load_streaming`.
+pub trait StreamingFormat<D: 'static>: Send + Sync + 'static {
+    /// A unique identifier for this format.
+    fn name(&self) -> &'static str;
+
+    /// Produces asset data from the given reader, which streams the bytes of the asset.
+    fn import(&self, reader: Box<dyn Read + Send>) -> Result<D, Error>;
+}
+
 /// SerializableFormat is a marker trait which is required for Format types that are supposed
 /// to be serialized. This trait implies both `Serialize` and `Deserialize` implementation.
 ///
 /// **Note:** This trait should never be implemented manually.
-/// Use the `register_format` macro to register it correctly.
+/// Use the `register_format` macro to register it correctly, or `FormatRegistry` to
+/// register formats at runtime.
 /// See [FormatRegisteredData](trait.FormatRegisteredData.html) for the full example.
 pub trait SerializableFormat<D: FormatRegisteredData + 'static>:
     Format<D> + erased_serde::Serialize + 'static
@@ -123,6 +177,12 @@ impl<D: 'static> Format<D> for Box<dyn Format<D>> {
     fn name(&self) -> &'static str {
         self.deref().name()
     }
+    fn extensions(&self) -> &[&'static str] {
+        self.deref().extensions()
+    }
+    fn shareable(&self) -> bool {
+        self.deref().shareable()
+    }
     fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
         self.deref().import_simple(bytes)
     }
@@ -141,6 +201,12 @@ impl<D: 'static> Format<D> for Box<dyn SerializableFormat<D>> {
     fn name(&self) -> &'static str {
         self.deref().name()
     }
+    fn extensions(&self) -> &[&'static str] {
+        self.deref().extensions()
+    }
+    fn shareable(&self) -> bool {
+        self.deref().shareable()
+    }
     fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
         self.deref().import_simple(bytes)
     }
@@ -46,3 +46,119 @@ where
         self.map.clear();
     }
 }
+
+/// A cache of asset handles of type `A` keyed by arbitrary values, e.g. the
+/// parameters a procedural asset has been generated from.
+/// Like `Cache`, this stores `WeakHandle`s, so it doesn't keep the assets alive.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct WeakHandleCache<K, A> {
+    map: FnvHashMap<K, WeakHandle<A>>,
+    prune_at: usize,
+}
+
+impl<K, A> WeakHandleCache<K, A>
+where
+    K: Eq + Hash,
+{
+    /// Creates a new, empty `WeakHandleCache`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Retrieves an asset handle using a given `key`, if the asset is still alive.
+    pub fn get<Q>(&self, key: &Q) -> Option<Handle<A>>
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.map.get(key).and_then(WeakHandle::upgrade)
+    }
+
+    /// Retrieves an asset handle using a given `key`, creating it with `f` if
+    /// there is no cached handle or the cached asset isn't alive anymore.
+    ///
+    /// Dead handles are pruned from the cache once in a while.
+    pub fn get_or_insert_with<F>(&mut self, key: K, f: F) -> Handle<A>
+    where
+        F: FnOnce() -> Handle<A>,
+    {
+        if let Some(handle) = self.get(&key) {
+            return handle;
+        }
+
+        if self.map.len() >= self.prune_at {
+            self.clear_dead();
+            self.prune_at = (self.map.len() * 2).max(16);
+        }
+
+        let handle = f();
+        self.map.insert(key, handle.downgrade());
+
+        handle
+    }
+
+    /// Deletes all cached handles which are invalid.
+    pub fn clear_dead(&mut self) {
+        self.map.retain(|_, h| !h.is_dead());
+    }
+
+    /// Clears all values.
+    pub fn clear_all(&mut self) {
+        self.map.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use amethyst_core::ecs::prelude::VecStorage;
+
+    use super::WeakHandleCache;
+    use crate::{Asset, AssetStorage, Handle};
+
+    struct Mesh(u32);
+
+    impl Asset for Mesh {
+        const NAME: &'static str = "test::Mesh";
+        type Data = Self;
+        type HandleStorage = VecStorage<Handle<Mesh>>;
+    }
+
+    #[test]
+    fn handles_are_cached_while_they_are_alive() {
+        let mut storage = AssetStorage::new();
+        let mut cache = WeakHandleCache::new();
+
+        let handle = cache.get_or_insert_with(4, || storage.insert(Mesh(4)));
+        assert_eq!(Some(&handle), cache.get(&4).as_ref());
+        let again = cache.get_or_insert_with(4, || unreachable!("The handle is cached"));
+        assert_eq!(handle, again);
+        assert_eq!(4, storage.get(&again).unwrap().0);
+        assert!(cache.get(&5).is_none());
+
+        drop((handle, again));
+        assert!(cache.get(&4).is_none());
+        let replaced = cache.get_or_insert_with(4, || storage.insert(Mesh(40)));
+        assert_eq!(40, storage.get(&replaced).unwrap().0);
+    }
+
+    #[test]
+    fn dead_handles_are_cleaned_up() {
+        let mut storage = AssetStorage::new();
+        let mut cache = WeakHandleCache::new();
+
+        let kept = cache.get_or_insert_with(0, || storage.insert(Mesh(0)));
+        for size in 1..20 {
+            cache.get_or_insert_with(size, || storage.insert(Mesh(size)));
+        }
+        // Inserting beyond the first 16 entries pruned the dead ones.
+        assert!(cache.map.len() < 20);
+
+        cache.clear_dead();
+        assert_eq!(1, cache.map.len());
+        assert_eq!(Some(kept), cache.get(&0));
+
+        cache.clear_all();
+        assert!(cache.get(&0).is_none());
+    }
+}
@@ -6,13 +6,21 @@
 //! of asset data types and their formats, and embedding the format name into
 //! the serialization format itself.
 
-use crate::SerializableFormat;
+use crate::{Format, FormatValue, SerializableFormat, Source};
+use amethyst_error::{format_err, Error};
+use fnv::FnvHashMap;
+use parking_lot::RwLock;
 use serde::{
-    de::{self, DeserializeSeed, Expected, SeqAccess, Visitor},
+    de::{self, DeserializeOwned, DeserializeSeed, Expected, SeqAccess, Visitor},
     ser::{Serialize, SerializeTupleStruct, Serializer},
     Deserialize, Deserializer,
 };
-use std::{collections::BTreeMap, marker::PhantomData};
+use std::{
+    any::{Any, TypeId},
+    collections::BTreeMap,
+    marker::PhantomData,
+    sync::Arc,
+};
 
 /// A trait for all asset types that have their format types.
 /// Use this as a bound for asset data types when used inside boxed format types intended for deserialization.
@@ -81,7 +89,13 @@ impl<'de, 'a, T: ?Sized + 'static> Visitor<'de> for SeqLookupVisitor<'a, T> {
                 "non-unique tag of {}: {:?}",
                 self.expected, key
             ))),
-            None => Err(de::Error::unknown_variant(key, &self.registry.names)),
+            None => runtime_format(key).ok_or_else(|| {
+                de::Error::custom(format_args!(
+                    "unknown format `{}`, registered formats are: {}",
+                    key,
+                    format_names(self.registry).join(", ")
+                ))
+            }),
         }
     }
 }
@@ -168,6 +182,148 @@ impl<'de, D: FormatRegisteredData> Deserialize<'de> for Box<dyn SerializableForm
     }
 }
 
+type RuntimeFormats = FnvHashMap<TypeId, BTreeMap<&'static str, Box<dyn Any + Send + Sync>>>;
+
+lazy_static::lazy_static! {
+    // Formats registered with `FormatRegistry`, by the `TypeId` of `dyn SerializableFormat<D>`.
+    static ref RUNTIME_FORMATS: RwLock<RuntimeFormats> = Default::default();
+}
+
+/// Looks up a format registered with `FormatRegistry`.
+fn runtime_format<T: ?Sized + 'static>(name: &str) -> Option<DeserializeFn<T>> {
+    RUNTIME_FORMATS
+        .read()
+        .get(&TypeId::of::<T>())?
+        .get(name)?
+        .downcast_ref::<DeserializeFn<T>>()
+        .cloned()
+}
+
+/// Returns the names of all formats which can be deserialized as `T`, sorted.
+fn format_names<T: ?Sized + 'static>(registry: &Registry<T>) -> Vec<&'static str> {
+    let mut names = registry.names.clone();
+    if let Some(formats) = RUNTIME_FORMATS.read().get(&TypeId::of::<T>()) {
+        names.extend(formats.keys().cloned());
+    }
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// A resource for registering formats at runtime, so they can be referenced by name in
+/// prefabs and other deserialized data, like formats registered with `register_format`.
+///
+/// A format is deserialized from its name and its parameters, e.g. in RON:
+///
+/// ```ron
+/// File("terrain/island.png", ("Heightmap", (scale: 0.5)))
+/// ```
+///
+/// The format's `name` must return the name it has been registered with, and the asset
+/// data type must be registered with `register_format_type`. Registrations are process
+/// wide, as prefabs are deserialized on the loader's threads.
+///
+/// ```ignore
+/// world.write_resource::<FormatRegistry>()
+///     .register::<HeightmapData, HeightmapFormat>("Heightmap")?;
+/// ```
+#[derive(Debug, Default)]
+pub struct FormatRegistry {
+    _private: (),
+}
+
+impl FormatRegistry {
+    /// Creates a new format registry.
+    pub fn new() -> Self {
+        FormatRegistry::default()
+    }
+
+    /// Registers the format `F` for loading asset data `D` under the given name.
+    ///
+    /// Registering a name again replaces the format. Fails if the name is already used
+    /// by a format registered with `register_format`.
+    pub fn register<D, F>(&mut self, name: &'static str) -> Result<(), Error>
+    where
+        D: FormatRegisteredData,
+        F: Format<D> + Clone + Serialize + DeserializeOwned,
+    {
+        if D::registry().map.contains_key(name) {
+            return Err(format_err!(
+                "Format name {:?} has already been registered with `register_format`",
+                name
+            ));
+        }
+        let deserializer: DeserializeFn<dyn SerializableFormat<D>> = deserialize_runtime::<D, F>;
+        RUNTIME_FORMATS
+            .write()
+            .entry(TypeId::of::<dyn SerializableFormat<D>>())
+            .or_insert_with(BTreeMap::new)
+            .insert(name, Box::new(deserializer));
+
+        Ok(())
+    }
+
+    /// Returns the names of all formats which can be deserialized for asset data `D`,
+    /// including the ones registered with `register_format`.
+    pub fn names<D: FormatRegisteredData>(&self) -> Vec<&'static str> {
+        format_names(D::registry())
+    }
+}
+
+fn deserialize_runtime<D, F>(
+    deserializer: &mut dyn erased_serde::Deserializer<'_>,
+) -> erased_serde::Result<Box<dyn SerializableFormat<D>>>
+where
+    D: FormatRegisteredData,
+    F: Format<D> + Clone + Serialize + DeserializeOwned,
+{
+    let format = erased_serde::deserialize::<F>(deserializer)?;
+    Ok(Box::new(RuntimeFormat(format)))
+}
+
+/// Makes a format registered at runtime a `SerializableFormat`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+struct RuntimeFormat<F>(F);
+
+impl<D, F> Format<D> for RuntimeFormat<F>
+where
+    D: 'static,
+    F: Format<D> + Clone,
+{
+    fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        self.0.extensions()
+    }
+
+    fn shareable(&self) -> bool {
+        self.0.shareable()
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        self.0.import_simple(bytes)
+    }
+
+    fn import(
+        &self,
+        name: String,
+        source: Arc<dyn Source>,
+        create_reload: Option<Box<dyn Format<D>>>,
+    ) -> Result<FormatValue<D>, Error> {
+        self.0.import(name, source, create_reload)
+    }
+}
+
+impl<D, F> SerializableFormat<D> for RuntimeFormat<F>
+where
+    D: FormatRegisteredData,
+    F: Format<D> + Clone + Serialize,
+{
+}
+
 impl<D: FormatRegisteredData> dyn SerializableFormat<D> {
     // This code is called by `register_format` macro. Considered a private api otherwise.
     #[doc(hidden)]
@@ -321,4 +477,45 @@ mod tests {
             deserialized_prefab.test.import_simple(Vec::new()).unwrap()
         );
     }
+    #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+    struct ScaledFormat {
+        scale: u32,
+    }
+
+    impl Format<TestData> for ScaledFormat {
+        fn name(&self) -> &'static str {
+            "SCALED"
+        }
+
+        fn import_simple(&self, _bytes: Vec<u8>) -> Result<TestData, Error> {
+            Ok(TestData(self.scale.to_string()))
+        }
+    }
+
+    #[test]
+    fn formats_can_be_registered_at_runtime() {
+        let mut registry = FormatRegistry::new();
+        registry
+            .register::<TestData, ScaledFormat>("SCALED")
+            .unwrap();
+        assert!(registry.register::<TestData, ScaledFormat>("FOO").is_err());
+        assert_eq!(vec!["FOO", "SCALED"], registry.names::<TestData>());
+
+        let value = serde_json::json!({ "test": ["SCALED", { "scale": 2 }] });
+        let prefab: TestPrefab = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            TestData("2".to_owned()),
+            prefab.test.import_simple(Vec::new()).unwrap()
+        );
+        // Runtime formats serialize like the ones registered with `register_format`.
+        let value = serde_json::to_value(&prefab).unwrap();
+        assert_eq!(
+            serde_json::json!({ "test": ["SCALED", { "scale": 2 }] }),
+            value
+        );
+
+        let value = serde_json::json!({ "test": ["BAR", null] });
+        let e = serde_json::from_value::<TestPrefab>(value).unwrap_err();
+        assert!(e.to_string().contains("FOO, SCALED"), "{}", e);
+    }
 }
@@ -1,16 +1,263 @@
+use std::{fmt, time::Duration};
+
 use err_derive::Error;
 
+use amethyst_error::Error as AmethystError;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(display = "Failed to load asset with name {:?}", _0)]
     Asset(String),
+    #[error(display = "Asset name {:?} is not part of the manifest", _0)]
+    UnknownAssetName(String),
     #[error(display = "Failed to load bytes from source")]
     Source,
+    #[error(display = "No source with id {:?} has been added to the loader", _0)]
+    SourceNotFound(String),
     #[error(display = "Format {:?} could not load asset", _0)]
     Format(&'static str),
     #[error(display = "Asset was loaded but no handle to it was saved.")]
     UnusedHandle,
+    #[error(display = "Asset load was cancelled")]
+    Cancelled,
+    #[error(display = "Operation is not supported by the source")]
+    Unsupported,
+    #[error(display = "Asset load timed out after {:?} while {}", elapsed, stage)]
+    Timeout { elapsed: Duration, stage: LoadStage },
     #[error(display = "Some error has occurred")]
     #[doc(hidden)]
     __Nonexhaustive,
 }
+
+/// Where in the data of an asset decoding failed, see `DecodeError`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ErrorLocation {
+    /// A position in text data, both starting at 1.
+    LineColumn {
+        /// The line of the error.
+        line: usize,
+        /// The column of the error within its line.
+        column: usize,
+    },
+    /// A position in binary data, the number of bytes read before the error occurred.
+    Offset(u64),
+}
+
+impl ErrorLocation {
+    /// Returns the location of the first `DecodeError` among the causes of `error`.
+    pub fn find(error: &AmethystError) -> Option<ErrorLocation> {
+        error.causes().find_map(|cause| {
+            cause
+                .as_error()
+                .downcast_ref::<DecodeError>()
+                .and_then(|e| e.location)
+        })
+    }
+
+    /// Appends the location to `name`, as `name:line:column` for text data, which
+    /// editors and terminals usually recognize as a link.
+    pub fn describe(location: Option<ErrorLocation>, name: &str) -> String {
+        match location {
+            Some(ErrorLocation::LineColumn { line, column }) => {
+                format!("{}:{}:{}", name, line, column)
+            }
+            Some(ErrorLocation::Offset(offset)) => format!("{} (byte offset {})", name, offset),
+            None => name.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ErrorLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ErrorLocation::LineColumn { line, column } => {
+                write!(f, "line {}, column {}", line, column)
+            }
+            ErrorLocation::Offset(offset) => write!(f, "byte offset {}", offset),
+        }
+    }
+}
+
+/// Error of a format failing to decode the data of an asset, telling where the data is
+/// invalid if the parser knows it. The error of the parser is its source.
+///
+/// Use `ErrorLocation::find` to get the location from the errors passed to trackers.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DecodeError {
+    /// The name of the encoding, e.g. `"Ron"`.
+    pub encoding: &'static str,
+    /// Where decoding failed.
+    pub location: Option<ErrorLocation>,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed parsing {} data", self.encoding)?;
+        if let Some(location) = self.location {
+            write!(f, " at {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// The stage an asset load was in when it timed out, see `LoadOptions::timeout`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LoadStage {
+    /// The data of the asset has not been read from its source yet.
+    Reading,
+    /// The data has been read, but processing it did not finish, e.g. because
+    /// the asset kept returning `ProcessingState::Loading`.
+    Processing,
+}
+
+impl fmt::Display for LoadStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            LoadStage::Reading => f.write_str("reading from the source"),
+            LoadStage::Processing => f.write_str("processing"),
+        }
+    }
+}
+
+/// Why `AssetStorage::try_get` found no asset for a handle.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GetError {
+    /// The asset is still being loaded or waits to be added by `AssetStorage::process`.
+    Pending,
+    /// The asset failed to load, was cancelled or has been removed.
+    Unloaded,
+    /// The handle has been created by another storage. This is only detected in
+    /// debug builds, as release builds don't tag handles with their storage.
+    WrongStorage,
+}
+
+impl fmt::Display for GetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            GetError::Pending => f.write_str("Asset is still loading"),
+            GetError::Unloaded => f.write_str("Asset failed to load or has been removed"),
+            GetError::WrongStorage => f.write_str("Handle belongs to another asset storage"),
+        }
+    }
+}
+
+impl std::error::Error for GetError {}
+
+/// Why an asset failed to load, reported to `AssetStorage::on_processing_error`.
+///
+/// Converting it into an `amethyst_error::Error` gives the error which is passed to the
+/// `Tracker` of the load.
+#[derive(Debug)]
+pub enum ProcessingError {
+    /// Reading the asset or parsing it with its `Format` failed.
+    Format {
+        /// The handle id of the asset.
+        id: u32,
+        /// The name the asset was loaded with.
+        name: String,
+        /// The error returned while reading or parsing.
+        cause: AmethystError,
+    },
+    /// Processing the data of the asset into the asset failed, or panicked.
+    Processing {
+        /// The handle id of the asset.
+        id: u32,
+        /// The name the asset was loaded with.
+        name: String,
+        /// The error returned by processing.
+        cause: AmethystError,
+    },
+    /// The asset did not finish loading in time, see `LoadOptions::timeout`.
+    Timeout {
+        /// The handle id of the asset.
+        id: u32,
+        /// The name the asset was loaded with.
+        name: String,
+        /// How long ago the load was requested.
+        elapsed: Duration,
+        /// What the load was doing when it timed out.
+        stage: LoadStage,
+    },
+    /// The asset was loaded, but all handles to it had been dropped already.
+    UnusedHandle {
+        /// The handle id of the asset.
+        id: u32,
+        /// The name the asset was loaded with.
+        name: String,
+    },
+}
+
+impl ProcessingError {
+    /// The handle id of the asset which failed to load.
+    pub fn id(&self) -> u32 {
+        match *self {
+            ProcessingError::Format { id, .. }
+            | ProcessingError::Processing { id, .. }
+            | ProcessingError::Timeout { id, .. }
+            | ProcessingError::UnusedHandle { id, .. } => id,
+        }
+    }
+
+    /// The name of the asset which failed to load.
+    pub fn name(&self) -> &str {
+        match *self {
+            ProcessingError::Format { ref name, .. }
+            | ProcessingError::Processing { ref name, .. }
+            | ProcessingError::Timeout { ref name, .. }
+            | ProcessingError::UnusedHandle { ref name, .. } => name,
+        }
+    }
+}
+
+impl fmt::Display for ProcessingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ProcessingError::Format {
+                ref name,
+                ref cause,
+                ..
+            } => write!(f, "Failed to read asset with name {:?}: {}", name, cause),
+            ProcessingError::Processing {
+                ref name,
+                ref cause,
+                ..
+            } => write!(f, "Failed to process asset with name {:?}: {}", name, cause),
+            ProcessingError::Timeout {
+                ref name,
+                elapsed,
+                stage,
+                ..
+            } => write!(
+                f,
+                "Asset with name {:?} timed out after {:?} while {}",
+                name, elapsed, stage
+            ),
+            ProcessingError::UnusedHandle { ref name, .. } => write!(
+                f,
+                "Asset with name {:?} was loaded but no handle to it was saved",
+                name
+            ),
+        }
+    }
+}
+
+impl From<ProcessingError> for AmethystError {
+    fn from(e: ProcessingError) -> Self {
+        match e {
+            ProcessingError::Format { name, cause, .. }
+            | ProcessingError::Processing { name, cause, .. } => {
+                AmethystError::from(Error::Asset(name)).with_source(cause)
+            }
+            ProcessingError::Timeout {
+                name,
+                elapsed,
+                stage,
+                ..
+            } => AmethystError::from(Error::Asset(name))
+                .with_source(Error::Timeout { elapsed, stage }),
+            ProcessingError::UnusedHandle { .. } => AmethystError::from(Error::UnusedHandle),
+        }
+    }
+}
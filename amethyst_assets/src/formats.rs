@@ -1,6 +1,204 @@
-use crate::Format;
+use std::{any::Any, fmt, io::Read, marker::PhantomData, path::Path, sync::Arc};
+
+use crate::{
+    error::{DecodeError, ErrorLocation},
+    Format, FormatValue, Source,
+};
 use amethyst_error::{format_err, Error, ResultExt};
-use serde::{Deserialize, Serialize};
+use log::debug;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// A `Format` deserializing asset data of type `D` with serde, from bytes in the
+/// wire encoding `E` (e.g. `Ron` or `Bincode`).
+///
+/// ```rust,ignore
+/// let format = SerdeFormat::<LevelData, Bincode>::new();
+/// loader.load("level.bin", format, (), &storage);
+/// ```
+pub struct SerdeFormat<D, E> {
+    marker: PhantomData<fn() -> (D, E)>,
+}
+
+impl<D, E> SerdeFormat<D, E> {
+    /// Creates a new format.
+    pub fn new() -> Self {
+        SerdeFormat {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<D, E> Clone for SerdeFormat<D, E> {
+    fn clone(&self) -> Self {
+        SerdeFormat::new()
+    }
+}
+
+impl<D, E> Copy for SerdeFormat<D, E> {}
+
+impl<D, E> Default for SerdeFormat<D, E> {
+    fn default() -> Self {
+        SerdeFormat::new()
+    }
+}
+
+impl<D, E: Encoding> fmt::Debug for SerdeFormat<D, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SerdeFormat<{}>", E::NAME)
+    }
+}
+
+impl<D, E> Format<D> for SerdeFormat<D, E>
+where
+    D: DeserializeOwned + Send + Sync + 'static,
+    E: Encoding,
+{
+    fn name(&self) -> &'static str {
+        E::NAME
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        E::EXTENSIONS
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        E::decode(&bytes)
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A wire encoding for `SerdeFormat`.
+///
+/// This trait is sealed, the encodings are `Ron` and, with the `bincode_format`,
+/// `json_format`, `msgpack_format`, `toml_format` and `yaml_format` features, `Bincode`,
+/// `Json`, `MessagePack`, `Toml` and `Yaml`.
+pub trait Encoding: sealed::Sealed + Send + Sync + 'static {
+    /// The name of the encoding, which is also used as the name of the format.
+    const NAME: &'static str;
+
+    /// The file extensions (without the leading dot) used for this encoding.
+    const EXTENSIONS: &'static [&'static str];
+
+    /// Deserializes a value from `bytes`.
+    fn decode<D: DeserializeOwned>(bytes: &[u8]) -> Result<D, Error>;
+}
+
+/// The Ron encoding, see `SerdeFormat`.
+#[derive(Clone, Copy, Debug)]
+pub enum Ron {}
+
+impl sealed::Sealed for Ron {}
+
+impl Encoding for Ron {
+    const NAME: &'static str = "Ron";
+    const EXTENSIONS: &'static [&'static str] = &["ron"];
+
+    fn decode<D: DeserializeOwned>(bytes: &[u8]) -> Result<D, Error> {
+        use ron::de::{Deserializer, Error as RonError};
+
+        let position = |e: &RonError| {
+            let location = match *e {
+                RonError::Parser(_, position) => Some(ErrorLocation::LineColumn {
+                    line: position.line,
+                    column: position.col,
+                }),
+                _ => None,
+            };
+            Error::from(DecodeError {
+                encoding: Self::NAME,
+                location,
+            })
+        };
+        let mut d = Deserializer::from_bytes(bytes)
+            .with_context(|_| format_err!("Failed deserializing Ron data"))?;
+        let val = D::deserialize(&mut d).map_err(|e| position(&e).with_source(e))?;
+        d.end().map_err(|e| position(&e).with_source(e))?;
+
+        Ok(val)
+    }
+}
+
+/// The Bincode encoding, see `SerdeFormat`.
+#[cfg(feature = "bincode_format")]
+#[derive(Clone, Copy, Debug)]
+pub enum Bincode {}
+
+#[cfg(feature = "bincode_format")]
+impl sealed::Sealed for Bincode {}
+
+#[cfg(feature = "bincode_format")]
+impl Encoding for Bincode {
+    const NAME: &'static str = "Bincode";
+    const EXTENSIONS: &'static [&'static str] = &["bin"];
+
+    fn decode<D: DeserializeOwned>(bytes: &[u8]) -> Result<D, Error> {
+        let mut reader = CountingReader { bytes, read: 0 };
+        bincode::deserialize_from(&mut reader)
+            .map_err(|e| Error::from(reader.decode_error(Self::NAME)).with_source(e))
+    }
+}
+
+/// The Json encoding, see `SerdeFormat`.
+#[cfg(feature = "json_format")]
+#[derive(Clone, Copy, Debug)]
+pub enum Json {}
+
+#[cfg(feature = "json_format")]
+impl sealed::Sealed for Json {}
+
+#[cfg(feature = "json_format")]
+impl Encoding for Json {
+    const NAME: &'static str = "Json";
+    const EXTENSIONS: &'static [&'static str] = &["json"];
+
+    fn decode<D: DeserializeOwned>(bytes: &[u8]) -> Result<D, Error> {
+        use serde_json::de::Deserializer;
+
+        let position = |e: &serde_json::Error| {
+            Error::from(DecodeError {
+                encoding: Self::NAME,
+                location: Some(ErrorLocation::LineColumn {
+                    line: e.line(),
+                    column: e.column(),
+                }),
+            })
+        };
+        let mut d = Deserializer::from_slice(bytes);
+        let val = D::deserialize(&mut d).map_err(|e| position(&e).with_source(e))?;
+        d.end().map_err(|e| position(&e).with_source(e))?;
+
+        Ok(val)
+    }
+}
+
+/// Reader for binary encodings, counting the bytes read to tell where decoding failed.
+#[cfg(any(feature = "bincode_format", feature = "msgpack_format"))]
+struct CountingReader<'a> {
+    bytes: &'a [u8],
+    read: usize,
+}
+
+#[cfg(any(feature = "bincode_format", feature = "msgpack_format"))]
+impl CountingReader<'_> {
+    fn decode_error(&self, encoding: &'static str) -> DecodeError {
+        DecodeError {
+            encoding,
+            location: Some(ErrorLocation::Offset(self.read as u64)),
+        }
+    }
+}
+
+#[cfg(any(feature = "bincode_format", feature = "msgpack_format"))]
+impl Read for CountingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = (&self.bytes[self.read..]).read(buf)?;
+        self.read += read;
+        Ok(read)
+    }
+}
 
 /// Format for loading from Ron files. Mostly useful for prefabs.
 /// This type cannot be used for tagged deserialization.
@@ -19,25 +217,21 @@ where
         "Ron"
     }
 
-    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
-        use ron::de::Deserializer;
-        let mut d = Deserializer::from_bytes(&bytes)
-            .with_context(|_| format_err!("Failed deserializing Ron file"))?;
-        let val =
-            D::deserialize(&mut d).with_context(|_| format_err!("Failed parsing Ron file"))?;
-        d.end()
-            .with_context(|_| format_err!("Failed parsing Ron file"))?;
+    fn extensions(&self) -> &[&'static str] {
+        &["ron"]
+    }
 
-        Ok(val)
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        Ron::decode(&bytes)
     }
 }
 
-/// Format for loading from Ron files. Mostly useful for prefabs.
+/// Format for loading from Json files. Mostly useful for prefabs.
 /// This type can only be used as manually specified to the loader.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct JsonFormat;
 
-#[cfg(feature = "json")]
+#[cfg(feature = "json_format")]
 impl<D> Format<D> for JsonFormat
 where
     D: for<'a> Deserialize<'a> + Send + Sync + 'static,
@@ -46,14 +240,1351 @@ where
         "Json"
     }
 
+    fn extensions(&self) -> &[&'static str] {
+        &["json"]
+    }
+
     fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
-        use serde_json::de::Deserializer;
-        let mut d = Deserializer::from_slice(&bytes);
-        let val = D::deserialize(&mut d)
-            .with_context(|_| format_err!("Failed deserializing Json file"))?;
-        d.end()
-            .with_context(|_| format_err!("Failed deserializing Json file"))?;
+        Json::decode(&bytes)
+    }
+}
 
-        Ok(val)
+/// The MessagePack encoding, see `SerdeFormat` and `MessagePackFormat`.
+///
+/// Structs can be encoded both as arrays and as maps keyed by the field names.
+#[cfg(feature = "msgpack_format")]
+#[derive(Clone, Copy, Debug)]
+pub enum MessagePack {}
+
+#[cfg(feature = "msgpack_format")]
+impl sealed::Sealed for MessagePack {}
+
+#[cfg(feature = "msgpack_format")]
+impl Encoding for MessagePack {
+    const NAME: &'static str = "MessagePack";
+    const EXTENSIONS: &'static [&'static str] = &["msgpack", "mpk"];
+
+    fn decode<D: DeserializeOwned>(bytes: &[u8]) -> Result<D, Error> {
+        let mut reader = CountingReader { bytes, read: 0 };
+        rmp_serde::from_read(&mut reader)
+            .map_err(|e| Error::from(reader.decode_error(Self::NAME)).with_source(e))
+    }
+}
+
+/// Format for loading from MessagePack data, e.g. baked by a server.
+/// This type can only be used as manually specified to the loader.
+///
+/// Data is loaded the same way with both constructors, which only decide how
+/// `encode` writes structs.
+#[cfg(feature = "msgpack_format")]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct MessagePackFormat {
+    named: bool,
+}
+
+#[cfg(feature = "msgpack_format")]
+impl MessagePackFormat {
+    /// Creates a format encoding structs as arrays of their fields, which is smaller
+    /// but breaks when fields are reordered, added or removed.
+    pub fn compact() -> Self {
+        MessagePackFormat { named: false }
+    }
+
+    /// Creates a format encoding structs as maps keyed by the field names, which
+    /// tolerates adding and reordering fields.
+    pub fn named() -> Self {
+        MessagePackFormat { named: true }
+    }
+
+    /// Returns `true` if this format encodes structs as maps, see `named`.
+    pub fn is_named(&self) -> bool {
+        self.named
+    }
+
+    /// Encodes `value` in the layout chosen when creating this format, e.g. to bake assets.
+    pub fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, Error> {
+        let bytes = if self.named {
+            rmp_serde::to_vec_named(value)
+        } else {
+            rmp_serde::to_vec(value)
+        };
+
+        bytes.with_context(|_| format_err!("Failed serializing MessagePack data"))
+    }
+}
+
+#[cfg(feature = "msgpack_format")]
+impl Default for MessagePackFormat {
+    fn default() -> Self {
+        MessagePackFormat::named()
+    }
+}
+
+#[cfg(feature = "msgpack_format")]
+impl<D> Format<D> for MessagePackFormat
+where
+    D: for<'a> Deserialize<'a> + Send + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        MessagePack::NAME
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        MessagePack::EXTENSIONS
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        MessagePack::decode(&bytes)
+    }
+}
+
+/// The Toml encoding, see `SerdeFormat`.
+///
+/// Datetime values are only accepted by fields of type `toml::value::Datetime`,
+/// other types fail with an error naming the keys of the datetimes.
+#[cfg(feature = "toml_format")]
+#[derive(Clone, Copy, Debug)]
+pub enum Toml {}
+
+#[cfg(feature = "toml_format")]
+impl sealed::Sealed for Toml {}
+
+#[cfg(feature = "toml_format")]
+impl Encoding for Toml {
+    const NAME: &'static str = "Toml";
+    const EXTENSIONS: &'static [&'static str] = &["toml"];
+
+    fn decode<D: DeserializeOwned>(bytes: &[u8]) -> Result<D, Error> {
+        toml::from_slice(bytes).map_err(|e| {
+            let context = Error::from(DecodeError {
+                encoding: Self::NAME,
+                location: e
+                    .line_col()
+                    .map(|(line, column)| ErrorLocation::LineColumn {
+                        line: line + 1,
+                        column: column + 1,
+                    }),
+            });
+            // Datetimes reach serde as maps, so the error itself doesn't mention them.
+            let mut datetimes = Vec::new();
+            if let Ok(value) = toml::from_slice::<toml::Value>(bytes) {
+                find_datetimes(&value, "", &mut datetimes);
+            }
+            if datetimes.is_empty() {
+                context.with_source(e)
+            } else {
+                context.with_source(
+                    format_err!(
+                        "Note that datetime values (at {}) are only supported by fields \
+                         of type `toml::value::Datetime`",
+                        datetimes.join(", ")
+                    )
+                    .with_source(e),
+                )
+            }
+        })
+    }
+}
+
+/// Collects the key paths of all datetime values in `value`.
+#[cfg(feature = "toml_format")]
+fn find_datetimes(value: &toml::Value, path: &str, found: &mut Vec<String>) {
+    match *value {
+        toml::Value::Datetime(_) => found.push(path.to_owned()),
+        toml::Value::Array(ref values) => {
+            for (index, value) in values.iter().enumerate() {
+                find_datetimes(value, &format!("{}[{}]", path, index), found);
+            }
+        }
+        toml::Value::Table(ref table) => {
+            for (key, value) in table {
+                let path = match path {
+                    "" => key.clone(),
+                    _ => format!("{}.{}", path, key),
+                };
+                find_datetimes(value, &path, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Format for loading from Toml files, e.g. for configuration-style data or prefabs.
+/// This type can only be used as manually specified to the loader.
+#[cfg(feature = "toml_format")]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TomlFormat;
+
+#[cfg(feature = "toml_format")]
+impl<D> Format<D> for TomlFormat
+where
+    D: for<'a> Deserialize<'a> + Send + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        Toml::NAME
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        Toml::EXTENSIONS
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        Toml::decode(&bytes)
+    }
+}
+
+/// The Yaml encoding, see `SerdeFormat`.
+///
+/// Anchors and aliases are resolved. Files containing more than one document are
+/// rejected with an error.
+#[cfg(feature = "yaml_format")]
+#[derive(Clone, Copy, Debug)]
+pub enum Yaml {}
+
+#[cfg(feature = "yaml_format")]
+impl sealed::Sealed for Yaml {}
+
+#[cfg(feature = "yaml_format")]
+impl Encoding for Yaml {
+    const NAME: &'static str = "Yaml";
+    const EXTENSIONS: &'static [&'static str] = &["yaml", "yml"];
+
+    fn decode<D: DeserializeOwned>(bytes: &[u8]) -> Result<D, Error> {
+        serde_yaml::from_slice(bytes).map_err(|e| {
+            let location = e.location().map(|location| ErrorLocation::LineColumn {
+                line: location.line(),
+                column: location.column(),
+            });
+            Error::from(DecodeError {
+                encoding: Self::NAME,
+                location,
+            })
+            .with_source(e)
+        })
+    }
+}
+
+/// Format for loading from Yaml files, e.g. for hand-edited prefabs.
+/// This type can only be used as manually specified to the loader.
+///
+/// See `Yaml` for how anchors and multiple documents are handled.
+#[cfg(feature = "yaml_format")]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct YamlFormat;
+
+#[cfg(feature = "yaml_format")]
+impl<D> Format<D> for YamlFormat
+where
+    D: for<'a> Deserialize<'a> + Send + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        Yaml::NAME
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        Yaml::EXTENSIONS
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        Yaml::decode(&bytes)
+    }
+}
+
+/// Format for loading rows of type `R` from CSV files, e.g. balance data exported from
+/// a spreadsheet. Usually used to load a `Table<R>`.
+///
+/// By default, the first row is a header naming the columns, which are matched with
+/// the field names of `R`, and whitespace around fields is trimmed.
+///
+/// ```rust,ignore
+/// let format = CsvFormat::<Weapon>::new().with_delimiter(b';');
+/// let handle = loader.load("tables/weapons.csv", format, (), &storage);
+/// ```
+#[cfg(feature = "csv_format")]
+pub struct CsvFormat<R> {
+    delimiter: u8,
+    headers: bool,
+    trim: bool,
+    marker: PhantomData<fn() -> R>,
+}
+
+#[cfg(feature = "csv_format")]
+impl<R> CsvFormat<R> {
+    /// Creates a new format, separating fields with commas.
+    pub fn new() -> Self {
+        CsvFormat {
+            delimiter: b',',
+            headers: true,
+            trim: true,
+            marker: PhantomData,
+        }
+    }
+
+    /// Sets the byte separating fields, e.g. `b'\t'` for tab separated values.
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets if the first row is a header naming the columns. Without headers, the
+    /// columns are matched with the fields of `R` by position.
+    pub fn with_headers(mut self, headers: bool) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Sets if whitespace around fields and headers is trimmed.
+    pub fn with_trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+}
+
+#[cfg(feature = "csv_format")]
+impl<R> Clone for CsvFormat<R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "csv_format")]
+impl<R> Copy for CsvFormat<R> {}
+
+#[cfg(feature = "csv_format")]
+impl<R> Default for CsvFormat<R> {
+    fn default() -> Self {
+        CsvFormat::new()
+    }
+}
+
+#[cfg(feature = "csv_format")]
+impl<R> fmt::Debug for CsvFormat<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CsvFormat")
+            .field("delimiter", &(self.delimiter as char))
+            .field("headers", &self.headers)
+            .field("trim", &self.trim)
+            .finish()
+    }
+}
+
+#[cfg(feature = "csv_format")]
+impl<R> Format<Vec<R>> for CsvFormat<R>
+where
+    R: DeserializeOwned + Send + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "CSV"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["csv"]
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<Vec<R>, Error> {
+        let trim = if self.trim {
+            csv::Trim::All
+        } else {
+            csv::Trim::None
+        };
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(self.headers)
+            .trim(trim)
+            .from_reader(&bytes[..]);
+        let headers = if self.headers {
+            Some(
+                reader
+                    .headers()
+                    .with_context(|_| format_err!("Failed parsing the CSV header row"))?
+                    .clone(),
+            )
+        } else {
+            None
+        };
+
+        reader
+            .deserialize()
+            .map(|row| row.map_err(|e| csv_error(e, headers.as_ref())))
+            .collect()
+    }
+}
+
+/// Names the row and column of a CSV error, e.g. `row 4, column "damage"`.
+#[cfg(feature = "csv_format")]
+fn csv_error(e: csv::Error, headers: Option<&csv::StringRecord>) -> Error {
+    let (position, field) = match e.kind() {
+        csv::ErrorKind::Deserialize { pos, err } => (pos.as_ref(), err.field()),
+        csv::ErrorKind::Utf8 { pos, err } => (pos.as_ref(), Some(err.field() as u64)),
+        csv::ErrorKind::UnequalLengths { pos, .. } => (pos.as_ref(), None),
+        _ => (None, None),
+    };
+    let row = position.map_or_else(|| "?".to_owned(), |pos| pos.line().to_string());
+    let message = match field {
+        Some(field) => {
+            let column = headers
+                .and_then(|headers| headers.get(field as usize))
+                .map_or_else(|| format!("#{}", field + 1), |name| format!("{:?}", name));
+            format!("Failed parsing CSV row {}, column {}", row, column)
+        }
+        None => format!("Failed parsing CSV row {}", row),
+    };
+
+    format_err!("{}", message).with_source(e)
+}
+
+/// A format delegating to one of several formats, depending on the extension of the
+/// loaded path, so e.g. `"level.ron"` and `"level.json"` can be loaded the same way.
+///
+/// Extensions are compared case-insensitively. Hot reloads dispatch again, so they
+/// pick up a file which has been exported in another format.
+///
+/// ```rust,ignore
+/// let format = ByExtension::new()
+///     .with("ron", RonFormat)
+///     .with("json", JsonFormat)
+///     .with("bin", SerdeFormat::<LevelData, Bincode>::new());
+/// loader.load(path, format, (), &storage);
+/// ```
+pub struct ByExtension<D> {
+    extensions: Vec<&'static str>,
+    formats: Vec<Box<dyn Format<D>>>,
+}
+
+impl<D: 'static> ByExtension<D> {
+    /// Creates a format without any inner formats, see `with`.
+    pub fn new() -> Self {
+        ByExtension {
+            extensions: Vec::new(),
+            formats: Vec::new(),
+        }
+    }
+
+    /// Creates a format from pairs of extensions (without the leading dot) and the
+    /// formats used for them.
+    pub fn from_formats(formats: Vec<(&'static str, Box<dyn Format<D>>)>) -> Self {
+        let (extensions, formats) = formats.into_iter().unzip();
+        ByExtension {
+            extensions,
+            formats,
+        }
+    }
+
+    /// Uses `format` for paths with the given extension (without the leading dot).
+    /// Earlier formats win if an extension is added twice.
+    pub fn with<F>(mut self, extension: &'static str, format: F) -> Self
+    where
+        F: Format<D>,
+    {
+        self.extensions.push(extension);
+        self.formats.push(Box::new(format));
+        self
+    }
+
+    fn format_for(&self, path: &str) -> Result<&dyn Format<D>, Error> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or("");
+
+        self.extensions
+            .iter()
+            .position(|known| known.eq_ignore_ascii_case(extension))
+            .map(|index| &*self.formats[index])
+            .ok_or_else(|| {
+                format_err!(
+                    "No format for the extension {:?} of {:?}, known extensions are: {}",
+                    extension,
+                    path,
+                    self.extensions.join(", "),
+                )
+            })
+    }
+}
+
+impl<D: 'static> Clone for ByExtension<D> {
+    fn clone(&self) -> Self {
+        ByExtension {
+            extensions: self.extensions.clone(),
+            formats: self.formats.clone(),
+        }
+    }
+}
+
+impl<D: 'static> Default for ByExtension<D> {
+    fn default() -> Self {
+        ByExtension::new()
+    }
+}
+
+impl<D: 'static> fmt::Debug for ByExtension<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.extensions.iter().zip(self.formats.iter()))
+            .finish()
+    }
+}
+
+impl<D: 'static> Format<D> for ByExtension<D> {
+    fn name(&self) -> &'static str {
+        "ByExtension"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &self.extensions
+    }
+
+    fn shareable(&self) -> bool {
+        self.formats.iter().all(|format| format.shareable())
+    }
+
+    fn import_simple(&self, _: Vec<u8>) -> Result<D, Error> {
+        Err(format_err!(
+            "ByExtension needs the path of an asset, use `import` instead"
+        ))
+    }
+
+    fn import(
+        &self,
+        name: String,
+        source: Arc<dyn Source>,
+        create_reload: Option<Box<dyn Format<D>>>,
+    ) -> Result<FormatValue<D>, Error> {
+        let format = self.format_for(&name)?;
+        debug!(
+            "Importing {:?} with format ByExtension({})",
+            name,
+            format.name()
+        );
+
+        // Reloads go through this format again, so they dispatch again as well.
+        let create_reload = create_reload.map(|_| Box::new(self.clone()) as Box<dyn Format<D>>);
+        let format_name = format.name();
+        format
+            .import(name, source, create_reload)
+            .with_context(|_| {
+                format_err!("Format ByExtension({}) could not load asset", format_name)
+            })
+    }
+}
+
+/// A format delegating to one of several formats, depending on the first bytes of the
+/// loaded asset, for assets which have a wrong or missing extension.
+///
+/// Signatures are checked in the order they were added; content matching none of them
+/// is passed to the fallback format, if there is one. Hot reloads sniff again.
+///
+/// Only the `import_simple` method of the inner formats is used.
+///
+/// ```rust,ignore
+/// let format = SniffFormat::new()
+///     .with(&[0x89, b'P', b'N', b'G'], PngFormat)
+///     .with_at(8, b"WAVE", WavFormat)
+///     .with(b"OggS", OggFormat)
+///     .with_text(RonFormat);
+/// loader.load("legacy/asset.dat", format, (), &storage);
+/// ```
+pub struct SniffFormat<D> {
+    signatures: Vec<Signature>,
+    formats: Vec<Box<dyn Format<D>>>,
+    fallback: Option<Box<dyn Format<D>>>,
+    sniff_len: usize,
+}
+
+/// What the first bytes of an asset have to look like, see `SniffFormat`.
+#[derive(Clone, Copy, Debug)]
+enum Signature {
+    /// The bytes at `offset` are exactly `bytes`.
+    Bytes { offset: usize, bytes: &'static [u8] },
+    /// The first bytes are text, see `is_text`.
+    Text,
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Signature::Bytes { offset: 0, bytes } => f.write_str(&hex(bytes)),
+            Signature::Bytes { offset, bytes } => write!(f, "{} at {}", hex(bytes), offset),
+            Signature::Text => f.write_str("UTF-8 text"),
+        }
+    }
+}
+
+impl<D: 'static> SniffFormat<D> {
+    /// Creates a format without any inner formats, see `with`.
+    ///
+    /// By default, the first 64 bytes are looked at to decide if an asset is text.
+    pub fn new() -> Self {
+        SniffFormat {
+            signatures: Vec::new(),
+            formats: Vec::new(),
+            fallback: None,
+            sniff_len: 64,
+        }
+    }
+
+    /// Uses `format` for assets starting with `signature`.
+    pub fn with<F>(self, signature: &'static [u8], format: F) -> Self
+    where
+        F: Format<D>,
+    {
+        self.with_at(0, signature, format)
+    }
+
+    /// Uses `format` for assets which contain `signature` at `offset`, e.g. `b"WAVE"` at
+    /// offset 8 for RIFF/WAVE files.
+    pub fn with_at<F>(mut self, offset: usize, signature: &'static [u8], format: F) -> Self
+    where
+        F: Format<D>,
+    {
+        assert!(!signature.is_empty(), "Signatures must not be empty");
+        self.signatures.push(Signature::Bytes {
+            offset,
+            bytes: signature,
+        });
+        self.formats.push(Box::new(format));
+        self
+    }
+
+    /// Uses `format` for assets starting with valid UTF-8 text, which contains no
+    /// control characters other than whitespace.
+    pub fn with_text<F>(mut self, format: F) -> Self
+    where
+        F: Format<D>,
+    {
+        self.signatures.push(Signature::Text);
+        self.formats.push(Box::new(format));
+        self
+    }
+
+    /// Uses `format` for assets matching none of the signatures.
+    pub fn with_fallback<F>(mut self, format: F) -> Self
+    where
+        F: Format<D>,
+    {
+        self.fallback = Some(Box::new(format));
+        self
+    }
+
+    /// Sets how many bytes are looked at to decide if an asset is text.
+    /// Byte signatures are always compared completely.
+    pub fn with_sniff_len(mut self, sniff_len: usize) -> Self {
+        self.sniff_len = sniff_len;
+        self
+    }
+
+    fn format_for(&self, bytes: &[u8]) -> Result<&dyn Format<D>, Error> {
+        let prefix = &bytes[..bytes.len().min(self.sniff_len)];
+        self.signatures
+            .iter()
+            .position(|signature| match *signature {
+                Signature::Bytes { offset, bytes: s } => {
+                    bytes.get(offset..offset + s.len()) == Some(s)
+                }
+                Signature::Text => is_text(prefix),
+            })
+            .map(|index| &*self.formats[index])
+            .or_else(|| self.fallback.as_ref().map(|format| &**format))
+            .ok_or_else(|| {
+                let checked: Vec<_> = self.signatures.iter().map(|s| s.to_string()).collect();
+                format_err!(
+                    "No format for content starting with {}, checked signatures: {}",
+                    hex(&bytes[..bytes.len().min(8)]),
+                    checked.join(", "),
+                )
+            })
+    }
+}
+
+/// Checks if `prefix` looks like the start of a text file. A UTF-8 sequence cut off at
+/// the end of the prefix is fine.
+fn is_text(prefix: &[u8]) -> bool {
+    let text = match std::str::from_utf8(prefix) {
+        Ok(text) => text,
+        Err(e) if e.error_len().is_none() => {
+            std::str::from_utf8(&prefix[..e.valid_up_to()]).expect("Checked above")
+        }
+        Err(_) => return false,
+    };
+
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| !c.is_control() || c.is_ascii_whitespace())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl<D: 'static> Clone for SniffFormat<D> {
+    fn clone(&self) -> Self {
+        SniffFormat {
+            signatures: self.signatures.clone(),
+            formats: self.formats.clone(),
+            fallback: self.fallback.clone(),
+            sniff_len: self.sniff_len,
+        }
+    }
+}
+
+impl<D: 'static> Default for SniffFormat<D> {
+    fn default() -> Self {
+        SniffFormat::new()
+    }
+}
+
+impl<D: 'static> fmt::Debug for SniffFormat<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SniffFormat")
+            .field(
+                "signatures",
+                &self
+                    .signatures
+                    .iter()
+                    .zip(self.formats.iter())
+                    .map(|(signature, format)| (signature.to_string(), format))
+                    .collect::<Vec<_>>(),
+            )
+            .field("fallback", &self.fallback)
+            .field("sniff_len", &self.sniff_len)
+            .finish()
+    }
+}
+
+impl<D: 'static> Format<D> for SniffFormat<D> {
+    fn name(&self) -> &'static str {
+        "SniffFormat"
+    }
+
+    fn shareable(&self) -> bool {
+        self.formats
+            .iter()
+            .chain(self.fallback.iter())
+            .all(|format| format.shareable())
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        let format = self.format_for(&bytes)?;
+        debug!("Importing asset with format SniffFormat({})", format.name());
+
+        let format_name = format.name();
+        format.import_simple(bytes).with_context(|_| {
+            format_err!("Format SniffFormat({}) could not load asset", format_name)
+        })
+    }
+}
+
+/// A format applying `mapper` to the data imported by the format `F`, for small
+/// transformations like scaling meshes exported in centimeters. Usually created with
+/// `Format::map`.
+///
+/// The reload object of the inner format is kept, and the mapping is applied again
+/// when the asset is hot reloaded.
+#[derive(Clone)]
+pub struct MapFormat<F, M> {
+    format: F,
+    mapper: M,
+}
+
+impl<F, M> MapFormat<F, M> {
+    /// Creates a format applying `mapper` to the data imported by `format`.
+    pub fn new(format: F, mapper: M) -> Self {
+        MapFormat { format, mapper }
+    }
+}
+
+impl<F: fmt::Debug, M> fmt::Debug for MapFormat<F, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapFormat")
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+impl<D, F, M> Format<D> for MapFormat<F, M>
+where
+    D: 'static,
+    F: Format<D> + Clone,
+    M: Fn(D) -> Result<D, Error> + Clone + Send + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        self.format.name()
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        self.format.extensions()
+    }
+
+    // The mapper can't be told apart from other mappers.
+    fn shareable(&self) -> bool {
+        false
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        let data = self.format.import_simple(bytes)?;
+        (self.mapper)(data).with_context(|_| {
+            format_err!("Failed to map asset imported with format {}", self.name())
+        })
+    }
+
+    fn import(
+        &self,
+        name: String,
+        source: Arc<dyn Source>,
+        create_reload: Option<Box<dyn Format<D>>>,
+    ) -> Result<FormatValue<D>, Error> {
+        // Reloads import through this format again, so they are mapped as well.
+        let create_reload = create_reload.map(|_| Box::new(self.clone()) as Box<dyn Format<D>>);
+        let FormatValue { data, reload } =
+            self.format.import(name.clone(), source, create_reload)?;
+        let data = (self.mapper)(data).with_context(|_| {
+            format_err!(
+                "Failed to map asset {:?} imported with format {}",
+                name,
+                self.name()
+            )
+        })?;
+
+        Ok(FormatValue { data, reload })
+    }
+}
+
+/// Asset data tagged with the version of its schema, as read by `VersionedFormat`.
+///
+/// In RON this looks like `(version: 2, data: (...))`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Versioned<T> {
+    /// The version of the schema `data` was written with.
+    pub version: u32,
+    /// The asset data.
+    pub data: T,
+}
+
+#[derive(Deserialize)]
+struct VersionHeader {
+    version: u32,
+}
+
+type AnyData = Box<dyn Any + Send>;
+type Migration = Arc<dyn Fn(AnyData) -> Result<AnyData, Error> + Send + Sync>;
+
+#[derive(Clone)]
+struct VersionStep {
+    decode: fn(&[u8]) -> Result<AnyData, Error>,
+    // Migrates from this version to the next one, `None` for the current version.
+    migrate: Option<Migration>,
+}
+
+fn decode_version<T, E>(bytes: &[u8]) -> Result<AnyData, Error>
+where
+    T: DeserializeOwned + Send + 'static,
+    E: Encoding,
+{
+    E::decode::<Versioned<T>>(bytes).map(|v| Box::new(v.data) as AnyData)
+}
+
+/// A `Format` for serde asset data of type `D` whose schema changed over time, stored
+/// as `Versioned` in the wire encoding `E`.
+///
+/// The format starts with the oldest supported schema and every call to `migrate` adds
+/// the next version together with the function upgrading the previous data. Data is
+/// deserialized with the schema of its version and then migrated step by step to `D`.
+///
+/// ```rust,ignore
+/// let format = VersionedFormat::<LevelV1, Ron>::new(1)
+///     .migrate(|v1: LevelV1| Ok(LevelV2 { name: v1.name, scale: 1.0 }))
+///     .migrate(|v2: LevelV2| Ok(Level::from(v2)));
+/// // Reads data of versions 1, 2 and 3.
+/// loader.load("level.ron", format, (), &storage);
+/// ```
+pub struct VersionedFormat<D, E = Ron> {
+    first: u32,
+    steps: Vec<VersionStep>,
+    marker: PhantomData<fn() -> (D, E)>,
+}
+
+impl<D, E> VersionedFormat<D, E>
+where
+    D: DeserializeOwned + Send + 'static,
+    E: Encoding,
+{
+    /// Creates a format reading data of type `D` with the schema version `version`.
+    pub fn new(version: u32) -> Self {
+        VersionedFormat {
+            first: version,
+            steps: vec![VersionStep {
+                decode: decode_version::<D, E>,
+                migrate: None,
+            }],
+            marker: PhantomData,
+        }
+    }
+
+    /// Adds the next schema version with data of type `N`, which older data is
+    /// migrated to with `migration`.
+    pub fn migrate<N, F>(mut self, migration: F) -> VersionedFormat<N, E>
+    where
+        N: DeserializeOwned + Send + 'static,
+        F: Fn(D) -> Result<N, Error> + Send + Sync + 'static,
+    {
+        let from = self.version();
+        let migration: Migration = Arc::new(move |data: AnyData| {
+            let data = data
+                .downcast::<D>()
+                .expect("Versioned data has the type of its step");
+            migration(*data)
+                .map(|data| Box::new(data) as AnyData)
+                .with_context(|_| {
+                    format_err!(
+                        "Failed to migrate data from version {} to {}",
+                        from,
+                        from + 1
+                    )
+                })
+        });
+        self.steps
+            .last_mut()
+            .expect("A versioned format has at least one version")
+            .migrate = Some(migration);
+        self.steps.push(VersionStep {
+            decode: decode_version::<N, E>,
+            migrate: None,
+        });
+
+        VersionedFormat {
+            first: self.first,
+            steps: self.steps,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<D, E> VersionedFormat<D, E> {
+    /// Returns the current version, which is read as `D` without migrations.
+    pub fn version(&self) -> u32 {
+        self.first + self.steps.len() as u32 - 1
+    }
+}
+
+impl<D, E> Clone for VersionedFormat<D, E> {
+    fn clone(&self) -> Self {
+        VersionedFormat {
+            first: self.first,
+            steps: self.steps.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<D, E: Encoding> fmt::Debug for VersionedFormat<D, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "VersionedFormat<{}>({}..={})",
+            E::NAME,
+            self.first,
+            self.version()
+        )
+    }
+}
+
+impl<D, E> Format<D> for VersionedFormat<D, E>
+where
+    D: Send + Sync + 'static,
+    E: Encoding,
+{
+    fn name(&self) -> &'static str {
+        E::NAME
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        E::EXTENSIONS
+    }
+
+    // The migrations can't be told apart from other migrations.
+    fn shareable(&self) -> bool {
+        false
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        let VersionHeader { version } = E::decode(&bytes)
+            .with_context(|_| format_err!("Failed to read the version of the asset data"))?;
+        if version > self.version() {
+            return Err(format_err!(
+                "Asset data has version {}, but this build only supports versions up to {}; \
+                 it was written by a build against newer data",
+                version,
+                self.version()
+            ));
+        }
+        if version < self.first {
+            return Err(format_err!(
+                "Asset data has version {}, which is older than the oldest supported version {}",
+                version,
+                self.first
+            ));
+        }
+
+        let steps = &self.steps[(version - self.first) as usize..];
+        let mut data = (steps[0].decode)(&bytes)
+            .with_context(|_| format_err!("Failed to read asset data of version {}", version))?;
+        for migrate in steps.iter().filter_map(|step| step.migrate.as_ref()) {
+            data = migrate(data)?;
+        }
+
+        Ok(*data
+            .downcast::<D>()
+            .expect("The last version has the type of the format"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "bincode_format")]
+    use super::Bincode;
+    use super::{Ron, SerdeFormat};
+    use crate::Format;
+
+    #[cfg(feature = "bincode_format")]
+    #[test]
+    fn dispatches_by_extension() {
+        use std::sync::Arc;
+
+        use super::{ByExtension, RonFormat};
+        use crate::MemorySource;
+
+        let format = ByExtension::new()
+            .with("ron", RonFormat)
+            .with("bin", SerdeFormat::<(u32, String), Bincode>::new());
+        let mut source = MemorySource::new();
+        source.insert("a.RON", &b"(1, \"ron\")"[..]);
+        source.insert("b.bin", bincode::serialize(&(2u32, "bin")).unwrap());
+        source.insert("c.json", &b"[3, \"json\"]"[..]);
+        let source = Arc::new(source);
+
+        let a = format.import("a.RON".into(), source.clone(), None).unwrap();
+        assert_eq!((1, "ron".to_owned()), a.data);
+        let b = format.import("b.bin".into(), source.clone(), None).unwrap();
+        assert_eq!((2, "bin".to_owned()), b.data);
+        let e = format.import("c.json".into(), source, None).unwrap_err();
+        assert!(e.to_string().contains("ron, bin"), "{}", e);
+    }
+
+    #[cfg(feature = "bincode_format")]
+    #[test]
+    fn dispatches_by_content() {
+        use super::{RonFormat, SniffFormat};
+
+        // Bincode starts with the little endian `u32` here.
+        let format = SniffFormat::new()
+            .with(&[7, 0, 0, 0], SerdeFormat::<(u32, String), Bincode>::new())
+            .with_text(RonFormat);
+
+        let bin = bincode::serialize(&(7u32, "bin")).unwrap();
+        assert_eq!((7, "bin".to_owned()), format.import_simple(bin).unwrap());
+        let ron = format
+            .import_simple(b"(1, \"r\xC3\xB6n\")".to_vec())
+            .unwrap();
+        assert_eq!((1, "r\u{f6}n".to_owned()), ron);
+        let e = format
+            .import_simple(vec![0x89, b'P', b'N', b'G', 0])
+            .unwrap_err();
+        assert!(e.to_string().contains("07 00 00 00, UTF-8 text"), "{}", e);
+    }
+
+    #[test]
+    fn maps_imported_data() {
+        use super::RonFormat;
+
+        let format = Format::<(u32, String)>::map(RonFormat, |(n, s)| {
+            if n == 0 {
+                return Err(amethyst_error::format_err!("Zero"));
+            }
+            Ok((n * 2, s.to_lowercase()))
+        });
+        let data = format.import_simple(b"(2, \"KEY\")".to_vec()).unwrap();
+        assert_eq!((4, "key".to_owned()), data);
+        let e = format.import_simple(b"(0, \"KEY\")".to_vec()).unwrap_err();
+        assert!(e.to_string().contains("Failed to map"), "{}", e);
+    }
+
+    #[test]
+    fn migrates_versioned_data() {
+        use serde::Deserialize;
+
+        use super::VersionedFormat;
+
+        #[derive(Deserialize)]
+        struct V1 {
+            name: String,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct V2 {
+            name: String,
+            scale: f32,
+        }
+
+        let format = VersionedFormat::<V1, Ron>::new(1).migrate(|v1: V1| {
+            if v1.name.is_empty() {
+                return Err(amethyst_error::format_err!("Empty name"));
+            }
+            Ok(V2 {
+                name: v1.name,
+                scale: 1.0,
+            })
+        });
+        assert_eq!(2, format.version());
+
+        let old = b"(version: 1, data: (name: \"a\"))".to_vec();
+        let expected = V2 {
+            name: "a".into(),
+            scale: 1.0,
+        };
+        assert_eq!(expected, format.import_simple(old).unwrap());
+        let current = b"(version: 2, data: (name: \"b\", scale: 2.0))".to_vec();
+        let expected = V2 {
+            name: "b".into(),
+            scale: 2.0,
+        };
+        assert_eq!(expected, format.import_simple(current).unwrap());
+
+        let e = format
+            .import_simple(b"(version: 1, data: (name: \"\"))".to_vec())
+            .unwrap_err();
+        assert!(e.to_string().contains("from version 1 to 2"), "{}", e);
+        let e = format
+            .import_simple(b"(version: 3, data: ())".to_vec())
+            .unwrap_err();
+        assert!(e.to_string().contains("newer data"), "{}", e);
+    }
+
+    #[cfg(feature = "csv_format")]
+    #[test]
+    fn loads_csv_rows() {
+        use serde::Deserialize;
+
+        use super::CsvFormat;
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Weapon {
+            name: String,
+            damage: u32,
+        }
+
+        let format = CsvFormat::<Weapon>::new();
+        let rows = format
+            .import_simple(b"name, damage\nsword, 10\naxe, 14\n".to_vec())
+            .unwrap();
+        assert_eq!(
+            vec![
+                Weapon {
+                    name: "sword".into(),
+                    damage: 10
+                },
+                Weapon {
+                    name: "axe".into(),
+                    damage: 14
+                },
+            ],
+            rows
+        );
+
+        let e = format
+            .import_simple(b"name,damage\nsword,10\naxe,lots\n".to_vec())
+            .unwrap_err();
+        assert!(e.to_string().contains("row 3, column \"damage\""), "{}", e);
+
+        let rows = CsvFormat::<(String, u32)>::new()
+            .with_delimiter(b'\t')
+            .with_headers(false)
+            .import_simple(b"bow\t7\n".to_vec())
+            .unwrap();
+        assert_eq!(vec![("bow".to_owned(), 7)], rows);
+    }
+
+    #[test]
+    fn decode_errors_have_locations() {
+        use crate::{ErrorLocation, RonFormat};
+
+        let e =
+            Format::<(u32, String)>::import_simple(&RonFormat, b"(1,\n 2)".to_vec()).unwrap_err();
+        match ErrorLocation::find(&e) {
+            Some(ErrorLocation::LineColumn { line: 2, .. }) => {}
+            other => panic!("Unexpected location {:?} of {}", other, e),
+        }
+        let location = ErrorLocation::LineColumn { line: 2, column: 3 };
+        assert_eq!(
+            "level.ron:2:3",
+            ErrorLocation::describe(Some(location), "level.ron")
+        );
+    }
+
+    #[cfg(feature = "bincode_format")]
+    #[test]
+    fn binary_decode_errors_have_offsets() {
+        use crate::ErrorLocation;
+
+        let mut bytes = bincode::serialize(&(1u32, "two")).unwrap();
+        bytes.truncate(6);
+        let e = SerdeFormat::<(u32, String), Bincode>::new()
+            .import_simple(bytes)
+            .unwrap_err();
+        // The `u32` has been read, the length of the string is cut off.
+        match ErrorLocation::find(&e) {
+            Some(ErrorLocation::Offset(offset)) => assert!(offset >= 4 && offset <= 6),
+            other => panic!("Unexpected location {:?} of {}", other, e),
+        }
+    }
+
+    #[cfg(feature = "bincode_format")]
+    #[test]
+    fn bincode_round_trip() {
+        let bytes = bincode::serialize(&(1u32, "two".to_owned())).unwrap();
+        let format = SerdeFormat::<(u32, String), Bincode>::new();
+        assert_eq!((1, "two".to_owned()), format.import_simple(bytes).unwrap());
+    }
+
+    #[cfg(feature = "msgpack_format")]
+    #[test]
+    fn message_pack_loads_through_the_loader() {
+        use std::{collections::BTreeMap, sync::Arc};
+
+        use amethyst_core::ecs::prelude::VecStorage;
+        use rayon::ThreadPoolBuilder;
+        use serde::{Deserialize, Serialize};
+
+        use super::MessagePackFormat;
+        use crate::{Asset, AssetStorage, Handle, Loader, MemorySource, ProcessingState};
+
+        #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+        enum Shape {
+            Circle(f32),
+            Rect { width: f32, height: f32 },
+        }
+
+        #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+        struct Entity {
+            name: String,
+            parent: Option<usize>,
+            shape: Shape,
+            stats: BTreeMap<String, u32>,
+            tags: Vec<String>,
+        }
+
+        #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+        struct Scene {
+            entities: Vec<Entity>,
+        }
+
+        impl Asset for Scene {
+            const NAME: &'static str = "test::Scene";
+            type Data = Self;
+            type HandleStorage = VecStorage<Handle<Scene>>;
+        }
+
+        let scene = Scene {
+            entities: vec![
+                Entity {
+                    name: "root".to_owned(),
+                    parent: None,
+                    shape: Shape::Circle(1.5),
+                    stats: BTreeMap::new(),
+                    tags: vec!["static".to_owned()],
+                },
+                Entity {
+                    name: "child".to_owned(),
+                    parent: Some(0),
+                    shape: Shape::Rect {
+                        width: 2.0,
+                        height: 0.5,
+                    },
+                    stats: vec![("hp".to_owned(), 10)].into_iter().collect(),
+                    tags: Vec::new(),
+                },
+            ],
+        };
+
+        let pool = Arc::new(
+            ThreadPoolBuilder::new()
+                .num_threads(1)
+                .build()
+                .expect("Invalid config"),
+        );
+        let loader = Loader::new(".", pool.clone());
+        let mut source = MemorySource::new();
+        for &(path, format) in &[
+            ("compact", MessagePackFormat::compact()),
+            ("named", MessagePackFormat::named()),
+        ] {
+            source.insert(path, format.encode(&scene).unwrap());
+        }
+        loader.add_source("memory", source);
+
+        let mut storage = AssetStorage::<Scene>::new();
+        let handles: Vec<_> = ["compact", "named"]
+            .iter()
+            .map(|path| {
+                loader.load_from(*path, MessagePackFormat::default(), "memory", (), &storage)
+            })
+            .collect();
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(data)),
+            &pool,
+            |storage| handles.iter().all(|handle| storage.contains(handle)),
+        );
+        for handle in &handles {
+            assert_eq!(Some(&scene), storage.get(handle));
+        }
+    }
+
+    #[cfg(feature = "toml_format")]
+    #[test]
+    fn toml_errors_name_the_key() {
+        use std::collections::HashMap;
+
+        use super::TomlFormat;
+
+        type Stats = HashMap<String, HashMap<String, u32>>;
+        let e = Format::<Stats>::import_simple(&TomlFormat, b"[sword]\ndamage = \"x\"\n".to_vec())
+            .unwrap_err();
+        assert!(e.to_string().contains("line"), "{}", e);
+        let cause = e.source().expect("Toml error is missing").to_string();
+        assert!(cause.contains("sword.damage"), "{}", cause);
+
+        let e =
+            Format::<Stats>::import_simple(&TomlFormat, b"[sword]\nforged = 1979-05-27\n".to_vec())
+                .unwrap_err();
+        assert!(e.to_string().contains("sword.forged"), "{}", e);
+    }
+
+    #[cfg(feature = "yaml_format")]
+    #[test]
+    fn yaml_anchors_and_documents() {
+        use super::YamlFormat;
+
+        let format = YamlFormat;
+        let pair: (Vec<u32>, Vec<u32>) = format
+            .import_simple(b"- &shared [1, 2]\n- *shared\n".to_vec())
+            .unwrap();
+        assert_eq!((vec![1, 2], vec![1, 2]), pair);
+
+        let e = Format::<u32>::import_simple(&format, b"1\n---\n2\n".to_vec()).unwrap_err();
+        assert!(e.to_string().contains("Yaml"), "{}", e);
+
+        let e = Format::<(u32, u32)>::import_simple(&format, b"- 1\n- x\n".to_vec()).unwrap_err();
+        assert!(e.to_string().contains("line 2"), "{}", e);
+    }
+
+    #[test]
+    fn ron_errors_contain_the_position() {
+        let format = SerdeFormat::<(u32, u32), Ron>::new();
+        let e = format.import_simple(b"(1,\n x)".to_vec()).unwrap_err();
+        assert!(e.to_string().contains("line 2"), "{}", e);
     }
 }
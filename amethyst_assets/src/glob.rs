@@ -0,0 +1,83 @@
+//! Minimal glob matching for asset paths.
+//!
+//! Supports `*` and `?` within a path component and `**` matching any
+//! number of components. Paths always use `/` as separator.
+
+/// Returns the leading components of `pattern` which contain no wildcards.
+pub(crate) fn base_dir(pattern: &str) -> &str {
+    let mut end = 0;
+    for (i, component) in pattern.split('/').enumerate() {
+        if component.contains(|c| c == '*' || c == '?') {
+            break;
+        }
+        end = if i == 0 {
+            component.len()
+        } else {
+            end + 1 + component.len()
+        };
+    }
+
+    // The last component is a file name if the pattern has no wildcards at all.
+    if end == pattern.len() {
+        return pattern.rfind('/').map(|i| &pattern[..i]).unwrap_or("");
+    }
+
+    &pattern[..end]
+}
+
+/// Checks if `path` matches the glob `pattern`.
+pub(crate) fn matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<_> = pattern.split('/').collect();
+    let path: Vec<_> = path.split('/').collect();
+
+    matches_components(&pattern, &path)
+}
+
+fn matches_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| matches_components(rest, &path[i..])),
+        Some((component, rest)) => match path.split_first() {
+            Some((name, path_rest)) => {
+                let component: Vec<_> = component.chars().collect();
+                let name: Vec<_> = name.chars().collect();
+
+                matches_component(&component, &name) && matches_components(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+fn matches_component(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|i| matches_component(rest, &name[i..])),
+        Some(('?', rest)) => !name.is_empty() && matches_component(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && matches_component(rest, &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base_dir, matches};
+
+    #[test]
+    fn base_dir_stops_at_first_wildcard() {
+        assert_eq!("levels", base_dir("levels/**/*.ron"));
+        assert_eq!("a/b", base_dir("a/b/c*/d"));
+        assert_eq!("", base_dir("*.ron"));
+        assert_eq!("a", base_dir("a/b.ron"));
+    }
+
+    #[test]
+    fn matches_wildcards() {
+        assert!(matches("levels/*.ron", "levels/one.ron"));
+        assert!(!matches("levels/*.ron", "levels/sub/one.ron"));
+        assert!(matches("levels/**/*.ron", "levels/one.ron"));
+        assert!(matches("levels/**/*.ron", "levels/sub/deeper/one.ron"));
+        assert!(matches("levels/level?.ron", "levels/level1.ron"));
+        assert!(!matches("levels/level?.ron", "levels/level10.ron"));
+        assert!(!matches("levels/*.ron", "levels/one.json"));
+    }
+}
@@ -1,9 +1,10 @@
-use std::{borrow::Borrow, hash::Hash};
+use std::{borrow::Borrow, future::Future, hash::Hash};
 
 use amethyst_core::ecs::{Read, ReadExpect};
+use amethyst_error::Error;
 use shred_derive::SystemData;
 
-use crate::{Asset, AssetStorage, Format, Handle, Loader, Progress};
+use crate::{Asset, AssetStorage, Format, Handle, Loader, ProcessableAsset, Progress};
 
 /// Helper type for loading assets
 #[derive(SystemData)]
@@ -55,6 +56,25 @@ where
             .load_from(name, format, source, progress, &*self.storage)
     }
 
+    /// Loads an asset with a given id and format from a custom source, returning a future
+    /// which resolves once the asset has been processed.
+    ///
+    /// See `Loader::load_async` for more information.
+    pub fn load_async<F, N, S>(
+        &self,
+        name: N,
+        format: F,
+        source: &S,
+    ) -> impl Future<Output = Result<Handle<A>, Error>>
+    where
+        F: Format<A::Data>,
+        N: Into<String>,
+        S: AsRef<str> + Eq + Hash + ?Sized,
+        String: Borrow<S>,
+    {
+        self.loader.load_async(name, format, source, &*self.storage)
+    }
+
     /// Load an asset from data and return a handle.
     pub fn load_from_data<P>(&self, data: A::Data, progress: P) -> Handle<A>
     where
@@ -74,4 +94,21 @@ where
         self.loader
             .load_from_data_async(data, progress, &*self.storage)
     }
+
+    /// Load an asset from data, converting it into the asset on a worker thread.
+    ///
+    /// See `Loader::load_from_data_async_processed` for more information.
+    pub fn load_from_data_async_processed<P>(
+        &self,
+        data: A::Data,
+        name: Option<String>,
+        progress: P,
+    ) -> Handle<A>
+    where
+        A: ProcessableAsset,
+        P: Progress,
+    {
+        self.loader
+            .load_from_data_async_processed(data, name, progress, &*self.storage)
+    }
 }
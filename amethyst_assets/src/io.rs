@@ -0,0 +1,321 @@
+#[cfg(feature = "async_source")]
+use std::task::Context;
+use std::{
+    io::Read,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+#[cfg(feature = "async_source")]
+use amethyst_error::format_err;
+use amethyst_error::Error;
+#[cfg(feature = "async_source")]
+use futures::{
+    channel::oneshot,
+    future::{BoxFuture, FutureExt},
+    task::{waker_ref, ArcWake},
+};
+use log::error;
+use parking_lot::{Condvar, Mutex};
+use rayon::ThreadPool;
+
+#[cfg(feature = "async_source")]
+use crate::{loader::LoadPriority, source::AsyncSource};
+use crate::{
+    loader::{JobQueue, SharedPriority},
+    source::{Source, SourceEntry},
+};
+
+/// Runs a job on some thread.
+#[cfg(feature = "async_source")]
+pub(crate) type Spawner = Arc<dyn Fn(Box<dyn FnOnce() + Send>) + Send + Sync>;
+
+/// A set of OS threads dedicated to blocking reads from sources.
+///
+/// Reading from slow media (e.g. a network or a cold hard drive) can take a long time,
+/// during which a thread does nothing but wait. Doing that on the rayon pool, which
+/// also runs parallel systems, would starve them, so the `Loader` reads asset data on
+/// these threads and only hands the data to the rayon pool for decoding.
+///
+/// The threads exit once the pool is dropped and all queued reads are done.
+pub struct IoPool {
+    shared: Arc<Shared>,
+    threads: usize,
+}
+
+struct Shared {
+    available: Condvar,
+    jobs: Mutex<JobQueue>,
+    shutdown: AtomicBool,
+}
+
+impl IoPool {
+    /// The number of threads used by the `Loader` by default.
+    pub const DEFAULT_THREADS: usize = 2;
+
+    /// Creates a new pool with `threads` threads, at least one.
+    pub fn new(threads: usize) -> Self {
+        let threads = threads.max(1);
+        let shared = Arc::new(Shared {
+            available: Condvar::new(),
+            jobs: Mutex::new(JobQueue::default()),
+            shutdown: AtomicBool::new(false),
+        });
+
+        for index in 0..threads {
+            let shared = shared.clone();
+            thread::Builder::new()
+                .name(format!("amethyst_assets io {}", index))
+                .spawn(move || shared.run())
+                .expect("Failed to spawn asset IO thread");
+        }
+
+        IoPool { shared, threads }
+    }
+
+    /// Returns the number of threads of this pool.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Queues a job which is run on one of the threads once all jobs with a higher
+    /// priority, and those with the same priority which were queued earlier, started.
+    pub(crate) fn spawn<J>(&self, priority: SharedPriority, job: J)
+    where
+        J: FnOnce() + Send + 'static,
+    {
+        self.shared.push(priority, Box::new(job));
+    }
+
+    /// Reorders the queued jobs after the priority of some of them has been raised.
+    pub(crate) fn reprioritize(&self) {
+        self.shared.jobs.lock().reprioritize();
+    }
+
+    /// Returns a `Spawner` queueing jobs on this pool with normal priority.
+    #[cfg(feature = "async_source")]
+    pub(crate) fn spawner(&self) -> Spawner {
+        let shared = self.shared.clone();
+        Arc::new(move |job| shared.push(LoadPriority::Normal.into(), job))
+    }
+}
+
+impl Drop for IoPool {
+    fn drop(&mut self) {
+        // Taking the lock makes sure no thread misses the notification
+        // between checking the flag and starting to wait.
+        let _jobs = self.shared.jobs.lock();
+        self.shared.shutdown.store(true, Ordering::Relaxed);
+        self.shared.available.notify_all();
+    }
+}
+
+impl Shared {
+    fn push(&self, priority: SharedPriority, job: Box<dyn FnOnce() + Send>) {
+        self.jobs.lock().push(priority, job);
+        self.available.notify_one();
+    }
+
+    fn run(&self) {
+        loop {
+            let job = {
+                let mut jobs = self.jobs.lock();
+                loop {
+                    if let Some(job) = jobs.pop() {
+                        break job;
+                    }
+                    if self.shutdown.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    self.available.wait(&mut jobs);
+                }
+            };
+
+            if catch_unwind(AssertUnwindSafe(job)).is_err() {
+                error!("An asset IO job panicked");
+            }
+        }
+    }
+}
+
+/// Wraps the source of a load whose data has already been read on an IO thread,
+/// handing out that data for the first read of `path` and forwarding everything else.
+pub(crate) struct Prefetched {
+    data: Mutex<Option<Result<(Vec<u8>, u64), Error>>>,
+    metadata: bool,
+    path: String,
+    source: Arc<dyn Source>,
+}
+
+impl Prefetched {
+    /// Reads `path` from `source`, including its modification time if `metadata` is `true`.
+    pub(crate) fn read(source: Arc<dyn Source>, path: String, metadata: bool) -> Self {
+        let data = if metadata {
+            source.load_with_metadata(&path)
+        } else {
+            source.load(&path).map(|bytes| (bytes, 0))
+        };
+
+        Prefetched {
+            data: Mutex::new(Some(data)),
+            metadata,
+            path,
+            source,
+        }
+    }
+
+    /// Wraps `source`, handing out `bytes` and their modification time `modified`
+    /// for the first read of `path`.
+    pub(crate) fn new(
+        source: Arc<dyn Source>,
+        path: String,
+        bytes: Vec<u8>,
+        modified: u64,
+    ) -> Self {
+        Prefetched {
+            data: Mutex::new(Some(Ok((bytes, modified)))),
+            metadata: true,
+            path,
+            source,
+        }
+    }
+
+    fn take(&self, path: &str) -> Option<Result<(Vec<u8>, u64), Error>> {
+        if path == self.path {
+            self.data.lock().take()
+        } else {
+            None
+        }
+    }
+}
+
+impl Source for Prefetched {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        self.source.modified(path)
+    }
+
+    fn resolve(&self, path: &str) -> Option<String> {
+        self.source.resolve(path)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        match self.take(path) {
+            Some(data) => data.map(|(bytes, _)| bytes),
+            None => self.source.load(path),
+        }
+    }
+
+    fn open(&self, path: &str) -> Result<Box<dyn Read + Send>, Error> {
+        self.source.open(path)
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        self.source.list(path)
+    }
+
+    fn entries(&self, prefix: &str, recursive: bool) -> Result<Vec<SourceEntry>, Error> {
+        self.source.entries(prefix, recursive)
+    }
+
+    fn is_available(&self) -> bool {
+        self.source.is_available()
+    }
+
+    fn load_with_metadata(&self, path: &str) -> Result<(Vec<u8>, u64), Error> {
+        match self.take(path) {
+            Some(data) => {
+                if self.metadata {
+                    data
+                } else {
+                    let (bytes, _) = data?;
+                    Ok((bytes, self.source.modified(path)?))
+                }
+            }
+            None => self.source.load_with_metadata(path),
+        }
+    }
+}
+
+/// Runs `future` to completion, polling it using `spawner` whenever it is woken.
+#[cfg(feature = "async_source")]
+pub(crate) fn spawn_future(spawner: Spawner, future: BoxFuture<'static, ()>) {
+    let task = Arc::new(Task {
+        future: Mutex::new(Some(future)),
+        spawner,
+    });
+    task.schedule();
+}
+
+/// A future which is polled by jobs of a `Spawner`.
+#[cfg(feature = "async_source")]
+struct Task {
+    future: Mutex<Option<BoxFuture<'static, ()>>>,
+    spawner: Spawner,
+}
+
+#[cfg(feature = "async_source")]
+impl Task {
+    fn schedule(self: Arc<Self>) {
+        let spawner = self.spawner.clone();
+        spawner(Box::new(move || self.poll()));
+    }
+
+    fn poll(self: Arc<Self>) {
+        let mut slot = self.future.lock();
+        if let Some(mut future) = slot.take() {
+            let waker = waker_ref(&self);
+            let mut cx = Context::from_waker(&*waker);
+            if future.as_mut().poll(&mut cx).is_pending() {
+                *slot = Some(future);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async_source")]
+impl ArcWake for Task {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.clone().schedule();
+    }
+}
+
+/// Turns a blocking `Source` into an `AsyncSource` by loading on the IO threads,
+/// or the `ThreadPool` if there are none.
+#[cfg(feature = "async_source")]
+pub(crate) struct Blocking {
+    source: Arc<dyn Source>,
+    spawner: Spawner,
+}
+
+#[cfg(feature = "async_source")]
+impl Blocking {
+    pub(crate) fn new(source: Arc<dyn Source>, io: Option<&IoPool>, pool: Arc<ThreadPool>) -> Self {
+        let spawner: Spawner = match io {
+            Some(io) => io.spawner(),
+            None => Arc::new(move |job: Box<dyn FnOnce() + Send>| pool.spawn(job)),
+        };
+
+        Blocking { source, spawner }
+    }
+}
+
+#[cfg(feature = "async_source")]
+impl AsyncSource for Blocking {
+    fn load(&self, path: &str) -> BoxFuture<'static, Result<Vec<u8>, Error>> {
+        let (sender, receiver) = oneshot::channel();
+        let source = self.source.clone();
+        let path = path.to_owned();
+        let failed = format_err!("Loading {:?} panicked", path);
+        (self.spawner)(Box::new(move || {
+            let _ = sender.send(source.load(&path));
+        }));
+
+        receiver
+            .map(|result| result.unwrap_or_else(|_| Err(failed)))
+            .boxed()
+    }
+}
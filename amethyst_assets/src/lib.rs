@@ -9,20 +9,72 @@
 
 #![warn(missing_docs, rust_2018_idioms, rust_2018_compatibility)]
 
-#[cfg(feature = "json")]
-pub use crate::formats::JsonFormat;
+#[cfg(feature = "bincode_format")]
+pub use crate::formats::Bincode;
+#[cfg(feature = "csv_format")]
+pub use crate::formats::CsvFormat;
+#[cfg(feature = "json_format")]
+pub use crate::formats::{Json, JsonFormat};
+#[cfg(feature = "msgpack_format")]
+pub use crate::formats::{MessagePack, MessagePackFormat};
+#[cfg(feature = "toml_format")]
+pub use crate::formats::{Toml, TomlFormat};
+#[cfg(feature = "yaml_format")]
+pub use crate::formats::{Yaml, YamlFormat};
+#[cfg(feature = "aes_encryption")]
+pub use crate::source::AesGcmCipher;
+#[cfg(all(target_os = "android", feature = "android_source"))]
+pub use crate::source::ApkSource;
+#[cfg(feature = "async_source")]
+pub use crate::source::AsyncSource;
+#[cfg(all(any(target_os = "macos", target_os = "ios"), feature = "bundle_source"))]
+pub use crate::source::BundleSource;
+#[cfg(any(feature = "zstd_compression", feature = "lz4_compression"))]
+pub use crate::source::CompressedSource;
+#[cfg(all(target_arch = "wasm32", feature = "wasm_source"))]
+pub use crate::source::FetchSource;
+#[cfg(feature = "tar_source")]
+pub use crate::source::TarSource;
+#[cfg(feature = "zip_source")]
+pub use crate::source::ZipSource;
+#[cfg(feature = "http_source")]
+pub use crate::source::{HttpError, HttpSource};
+#[cfg(feature = "file_watcher")]
+pub use crate::watcher::FileWatcher;
 pub use crate::{
-    asset::{Asset, Format, FormatValue, ProcessableAsset, SerializableFormat},
-    cache::Cache,
-    dyn_format::FormatRegisteredData,
-    formats::RonFormat,
+    asset::{Asset, Format, FormatValue, ProcessableAsset, SerializableFormat, StreamingFormat},
+    cache::{Cache, WeakHandleCache},
+    dyn_format::{FormatRegisteredData, FormatRegistry},
+    error::{DecodeError, ErrorLocation, GetError, LoadStage, ProcessingError},
+    formats::{
+        ByExtension, Encoding, MapFormat, Ron, RonFormat, SerdeFormat, SniffFormat, Versioned,
+        VersionedFormat,
+    },
     helper::AssetLoaderSystemData,
-    loader::Loader,
+    io::IoPool,
+    loader::{LoadOptions, LoadPriority, LoadToken, Loader},
+    manifest::{Manifest, ManifestEntry, ManifestSystem},
+    pending::PendingLoad,
     prefab::{AssetPrefab, Prefab, PrefabData, PrefabLoader, PrefabLoaderSystem},
-    progress::{Completion, Progress, ProgressCounter, Tracker},
-    reload::{HotReloadBundle, HotReloadStrategy, HotReloadSystem, Reload, SingleFile},
-    source::{Directory, Source},
-    storage::{AssetStorage, Handle, ProcessingState, Processor, WeakHandle},
+    progress::{
+        AssetProgressEvent, AssetProgressOutcome, Completion, Progress, ProgressCounter, Tracker,
+    },
+    reload::{
+        content_hash, HotReloadBundle, HotReloadEvent, HotReloadForSystem, HotReloadStrategy,
+        HotReloadStrategyFor, HotReloadSystem, MultiFile, MultiFileReader, Reload, ReloadOutcome,
+        Reloaded, SingleFile,
+    },
+    retry::RetryPolicy,
+    source::{
+        encrypt_directory, Cipher, Directory, EncryptedSource, MemorySource, OverlaySource, Source,
+        SourceEntry, XorObfuscation,
+    },
+    storage::{
+        AssetProcessor, AssetStorage, FrozenAssetStorage, Handle, HandleStatus, MockAssetStorage,
+        ProcessingStage, ProcessingState, Processor, ProcessorWithDrop, Provenance, RecyclePolicy,
+        ReloadFailure, ReloadInfo, StageState, WeakHandle,
+    },
+    table::Table,
 };
 
 pub use rayon::ThreadPool;
@@ -32,13 +84,21 @@ mod cache;
 mod dyn_format;
 mod error;
 mod formats;
+mod glob;
 mod helper;
+mod io;
 mod loader;
+mod manifest;
+mod pending;
 mod prefab;
 mod progress;
 mod reload;
+mod retry;
 mod source;
 mod storage;
+mod table;
+#[cfg(feature = "file_watcher")]
+mod watcher;
 
 // used in macros. Private API otherwise.
 #[doc(hidden)]
@@ -1,24 +1,72 @@
-use std::{borrow::Borrow, hash::Hash, path::PathBuf, sync::Arc};
+use std::{
+    any::{Any, TypeId},
+    borrow::Borrow,
+    cmp::Ordering as CmpOrdering,
+    collections::BinaryHeap,
+    future::Future,
+    hash::Hash,
+    io::Read,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant, SystemTime},
+};
 
 use fnv::FnvHashMap;
-use log::debug;
+#[cfg(feature = "async_source")]
+use futures::future::{BoxFuture, FutureExt};
+use log::{debug, warn};
+use parking_lot::{Mutex, RwLock};
 use rayon::ThreadPool;
 
-use amethyst_error::ResultExt;
+use amethyst_error::{format_err, Error, ResultExt};
 #[cfg(feature = "profiler")]
 use thread_profiler::profile_scope;
 
 use crate::{
-    error::Error,
-    storage::{AssetStorage, Handle, Processed},
-    Asset, Directory, Format, FormatValue, Progress, Source,
+    error, glob,
+    io::{IoPool, Prefetched},
+    manifest::Manifest,
+    pending::{PendingLoad, PendingLoads},
+    progress::{self, CompletionFuture, SharedLoadState, SharedProgress, Tracker},
+    reload::content_hash,
+    retry::{RetryPolicy, Retrying},
+    source::{BytesSource, MountedSource, SourceEntry},
+    storage::{
+        AssetStorage, Handle, Processed, ProcessedQueue, ProcessingState, Provenance, WeakHandle,
+    },
+    Asset, Directory, Format, FormatValue, ProcessableAsset, Progress, ProgressCounter, Source,
+    StreamingFormat,
+};
+#[cfg(feature = "async_source")]
+use crate::{
+    io::{self, Blocking, Spawner},
+    source::AsyncSource,
 };
 
 /// The asset loader, holding the sources and a reference to the `ThreadPool`.
 pub struct Loader {
+    #[cfg(feature = "async_source")]
+    async_sources: RwLock<FnvHashMap<String, Arc<dyn AsyncSource>>>,
+    compare_contents: bool,
+    #[cfg(feature = "async_source")]
+    executor: Option<Executor>,
     hot_reload: bool,
+    io: Mutex<Option<Arc<IoPool>>>,
+    io_threads: usize,
+    jobs: Arc<Mutex<JobQueue>>,
+    manifest: RwLock<Manifest>,
+    mounts: RwLock<Vec<(String, String)>>,
+    pending: PendingLoads,
     pool: Arc<ThreadPool>,
-    sources: FnvHashMap<String, Arc<dyn Source>>,
+    retry: RetryPolicy,
+    shared: Mutex<SharedLoads>,
+    sources: RwLock<FnvHashMap<String, Arc<MountedSource>>>,
+    timeout: Option<Duration>,
 }
 
 impl Loader {
@@ -36,10 +84,24 @@ impl Loader {
     where
         S: Source,
     {
-        let mut loader = Loader {
+        let loader = Loader {
+            #[cfg(feature = "async_source")]
+            async_sources: Default::default(),
+            compare_contents: false,
+            #[cfg(feature = "async_source")]
+            executor: None,
             hot_reload: true,
+            io: Default::default(),
+            io_threads: IoPool::DEFAULT_THREADS,
+            jobs: Default::default(),
+            manifest: Default::default(),
+            mounts: Default::default(),
+            pending: Default::default(),
             pool,
+            retry: Default::default(),
+            shared: Default::default(),
             sources: Default::default(),
+            timeout: None,
         };
 
         loader.set_default_source(source);
@@ -47,23 +109,129 @@ impl Loader {
     }
 
     /// Add a source to the `Loader`, given an id and the source.
-    pub fn add_source<I, S>(&mut self, id: I, source: S)
+    ///
+    /// This can be done at any time, replacing any source previously added with the same id.
+    pub fn add_source<I, S>(&self, id: I, source: S)
     where
         I: Into<String>,
         S: Source,
     {
+        let old = self
+            .sources
+            .write()
+            .insert(id.into(), Arc::new(MountedSource::new(source)));
+        if let Some(old) = old {
+            old.unmount();
+        }
+    }
+
+    /// Removes the source with the given id from the `Loader`, returning `true`
+    /// if there was such a source.
+    ///
+    /// Loads from this source which are already in flight complete (or fail) normally.
+    /// Assets loaded from it won't be hot reloaded anymore.
+    pub fn remove_source(&self, id: &str) -> bool {
+        match self.sources.write().remove(id) {
+            Some(source) => {
+                source.unmount();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Add an asynchronous source to the `Loader`, given an id and the source.
+    ///
+    /// Assets are loaded from it using `load_from_async_source`. The id may be the same
+    /// as the one of a source added with `add_source`; `load_from_async_source` prefers
+    /// the asynchronous source then, while the other methods keep using the blocking one.
+    #[cfg(feature = "async_source")]
+    pub fn add_async_source<I, S>(&self, id: I, source: S)
+    where
+        I: Into<String>,
+        S: AsyncSource,
+    {
+        self.async_sources
+            .write()
+            .insert(id.into(), Arc::new(source));
+    }
+
+    /// Removes the asynchronous source with the given id from the `Loader`, returning
+    /// `true` if there was such a source.
+    #[cfg(feature = "async_source")]
+    pub fn remove_async_source(&self, id: &str) -> bool {
+        self.async_sources.write().remove(id).is_some()
+    }
+
+    /// Returns `true` if a source with the given id has been added to the `Loader`.
+    pub fn has_source(&self, id: &str) -> bool {
+        self.sources.read().contains_key(id)
+    }
+
+    /// Returns the directories read by the `Directory` sources which have been added to
+    /// the `Loader`, including the default source and mounted ones.
+    ///
+    /// `HotReloadBundle::with_watcher` watches these with its `FileWatcher`.
+    pub fn directories(&self) -> Vec<PathBuf> {
         self.sources
-            .insert(id.into(), Arc::new(source) as Arc<dyn Source>);
+            .read()
+            .values()
+            .filter_map(|source| source.root().map(Path::to_owned))
+            .collect()
+    }
+
+    /// Mounts a source at the given path prefix, so assets loaded with `load` whose path
+    /// starts with the prefix are loaded from that source instead of the default source.
+    ///
+    /// The prefix is stripped from the path before it is passed to the source, so with
+    /// a source mounted at `mods/coolmod`, `mods/coolmod/units/tank.ron` is loaded as
+    /// `units/tank.ron`. If several prefixes match a path, the longest one wins; paths
+    /// matching none are loaded from the default source. Mounting another source at the
+    /// same prefix replaces the previous one.
+    pub fn mount<S>(&self, prefix: &str, source: S)
+    where
+        S: Source,
+    {
+        let prefix = prefix.trim_matches('/').to_owned();
+        let id = format!("[mount {}]", prefix);
+        self.add_source(id.clone(), source);
+
+        let mut mounts = self.mounts.write();
+        mounts.retain(|(p, _)| *p != prefix);
+        mounts.push((prefix, id));
+    }
+
+    /// Unmounts the source mounted at the given prefix, returning `true` if there was one.
+    ///
+    /// Like with `remove_source`, assets loaded from it won't be hot reloaded anymore,
+    /// even if another source is mounted at the same prefix later.
+    pub fn unmount(&self, prefix: &str) -> bool {
+        let prefix = prefix.trim_matches('/');
+        let mut mounts = self.mounts.write();
+        match mounts.iter().position(|(p, _)| p == prefix) {
+            Some(i) => {
+                let (_, id) = mounts.swap_remove(i);
+                self.remove_source(&id)
+            }
+            None => false,
+        }
     }
 
     /// Set the default source of the `Loader`.
-    pub fn set_default_source<S>(&mut self, source: S)
+    pub fn set_default_source<S>(&self, source: S)
     where
         S: Source,
     {
         self.add_source(String::new(), source);
     }
 
+    /// Sets the `Manifest` used to resolve logical asset names in `load`.
+    ///
+    /// This only affects subsequent loads.
+    pub fn set_manifest(&self, manifest: Manifest) {
+        *self.manifest.write() = manifest;
+    }
+
     /// If set to `true`, this `Loader` will ask formats to
     /// generate "reload instructions" which *allow* reloading.
     /// Calling `set_hot_reload(true)` does not actually enable
@@ -73,9 +241,79 @@ impl Loader {
         self.hot_reload = value;
     }
 
+    /// If set to `true`, the contents of hot reloadable assets are hashed when they are
+    /// loaded, so reloads can be skipped if they didn't change (see
+    /// `HotReloadStrategy::with_content_comparison`). Disabled by default, so big files
+    /// are not hashed if contents are never compared.
+    pub fn set_content_comparison(&mut self, compare: bool) {
+        self.compare_contents = compare;
+    }
+
+    /// Sets the policy for retrying failed reads from sources, which is used for all loads
+    /// unless `load_with_options` is given another one. By default, reads are not retried.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
+    }
+
+    /// Sets the time after which loads fail with a timeout error if they didn't finish,
+    /// which is used for all loads unless `load_with_options` is given another one.
+    /// By default, loads never time out.
+    ///
+    /// The timeout is checked by `AssetStorage::process`, which reports whether the
+    /// load was still reading from its source or stuck processing.
+    pub fn set_load_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Sets the number of threads used to read asset data from sources, which defaults
+    /// to `IoPool::DEFAULT_THREADS`.
+    ///
+    /// Reads block these threads instead of the `ThreadPool`, which only gets to decode
+    /// the data once it has been read. Passing `0` reads on the `ThreadPool` as well.
+    /// The threads are only started by the first load which needs them. Loads which are
+    /// already queued keep using the previous threads.
+    pub fn set_io_threads(&mut self, threads: usize) {
+        self.io_threads = threads;
+        *self.io.get_mut() = None;
+    }
+
+    /// Sets the executor running the futures of asynchronous sources, see
+    /// `load_from_async_source`.
+    ///
+    /// By default, the futures are polled on the IO threads (or the `ThreadPool` if
+    /// there are none). Sources whose futures need a specific runtime, like the Tokio
+    /// runtime for `HttpSource`, require an executor spawning them on that runtime.
+    #[cfg(feature = "async_source")]
+    pub fn set_executor<E>(&mut self, executor: E)
+    where
+        E: Fn(BoxFuture<'static, ()>) + Send + Sync + 'static,
+    {
+        self.executor = Some(Box::new(executor));
+    }
+
+    /// Returns the threads used to read asset data from sources, see `set_io_threads`,
+    /// starting them if no load has needed them yet.
+    ///
+    /// `Processor` runs hot reloads on them as well.
+    pub fn io_pool(&self) -> Option<Arc<IoPool>> {
+        match self.io_threads {
+            0 => None,
+            threads => Some(
+                self.io
+                    .lock()
+                    .get_or_insert_with(|| Arc::new(IoPool::new(threads)))
+                    .clone(),
+            ),
+        }
+    }
+
     /// Loads an asset with a given format from the default (directory) source.
     /// If you want to load from a custom source instead, use `load_from`.
     ///
+    /// If a `Manifest` has been set and `name` is one of its logical names, the asset
+    /// is loaded from the location listed in the manifest instead. If `name` starts with
+    /// the prefix of a source mounted with `mount`, it is loaded from that source.
+    ///
     /// See `load_from` for more information.
     pub fn load<A, F, N, P>(
         &self,
@@ -92,12 +330,171 @@ impl Loader {
     {
         #[cfg(feature = "profiler")]
         profile_scope!("initialise_loading_assets");
-        self.load_from::<A, F, _, _, _>(name, format, "", progress, storage)
+        match self.resolve(name.into(), format.name()) {
+            Ok((source, path)) => {
+                self.load_from::<A, F, _, _, _>(path, format, source.as_str(), progress, storage)
+            }
+            Err((name, e)) => self.load_failed(name, e, progress, storage),
+        }
+    }
+
+    /// Loads all files directly inside the directory `dir` of the default source which
+    /// have one of the extensions of the given format (or all files if the format
+    /// doesn't specify any), returning their names together with their handles.
+    ///
+    /// Every file is loaded separately and tracked by `progress`, so a file failing
+    /// to load does not affect the others. Returns an error if the source can't
+    /// list the contents of `dir`.
+    pub fn load_dir<A, F>(
+        &self,
+        dir: &str,
+        format: F,
+        progress: &mut ProgressCounter,
+        storage: &AssetStorage<A>,
+    ) -> Result<Vec<(String, Handle<A>)>, Error>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+    {
+        let dir = dir.trim_end_matches('/');
+        let extensions = format.extensions();
+        let names = self
+            .source("")?
+            .list(dir)?
+            .into_iter()
+            .filter(|name| {
+                let file_name = match dir {
+                    "" => Some(name.as_str()),
+                    dir => name.strip_prefix(dir).and_then(|s| s.strip_prefix('/')),
+                };
+                match file_name {
+                    Some(file_name) => !file_name.contains('/'),
+                    None => {
+                        warn!(
+                            "Skipping {:?}, which the source listed for {:?}, but is not inside it",
+                            name, dir
+                        );
+                        false
+                    }
+                }
+            })
+            .filter(|name| {
+                extensions.is_empty()
+                    || Path::new(name)
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .map(|ext| {
+                            extensions
+                                .iter()
+                                .any(|candidate| candidate.eq_ignore_ascii_case(ext))
+                        })
+                        .unwrap_or(false)
+            })
+            .collect();
+
+        Ok(self.load_all(names, format, progress, storage))
+    }
+
+    /// Loads all files of the default source matching the glob `pattern`, returning
+    /// their names together with their handles.
+    ///
+    /// `*` and `?` match within a path component, `**` matches any number of
+    /// directories, e.g. `"levels/**/*.ron"`. See `load_dir` for more information.
+    pub fn load_glob<A, F>(
+        &self,
+        pattern: &str,
+        format: F,
+        progress: &mut ProgressCounter,
+        storage: &AssetStorage<A>,
+    ) -> Result<Vec<(String, Handle<A>)>, Error>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+    {
+        let names = self
+            .source("")?
+            .list(glob::base_dir(pattern))?
+            .into_iter()
+            .filter(|name| glob::matches(pattern, name))
+            .collect();
+
+        Ok(self.load_all(names, format, progress, storage))
+    }
+
+    fn load_all<A, F>(
+        &self,
+        names: Vec<String>,
+        format: F,
+        progress: &mut ProgressCounter,
+        storage: &AssetStorage<A>,
+    ) -> Vec<(String, Handle<A>)>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+    {
+        names
+            .into_iter()
+            .map(|name| {
+                let handle = self.load(
+                    name.clone(),
+                    objekt::clone(&format),
+                    &mut *progress,
+                    storage,
+                );
+                (name, handle)
+            })
+            .collect()
+    }
+
+    /// Loads an asset with a given format from the default (directory) source,
+    /// using the given priority. Like `load`, this consults the `Manifest`.
+    ///
+    /// Loads with a higher priority are handed to the thread pool first, and their
+    /// data is processed by `AssetStorage::process` before data of lower priority loads.
+    /// All other loading methods use `LoadPriority::Normal`.
+    ///
+    /// Loading an asset which is still waiting for a thread again with a higher priority
+    /// raises the priority of the first load.
+    ///
+    /// See `load_from` for more information.
+    pub fn load_with_priority<A, F, N, P>(
+        &self,
+        name: N,
+        format: F,
+        priority: LoadPriority,
+        progress: P,
+        storage: &AssetStorage<A>,
+    ) -> Handle<A>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        N: Into<String>,
+        P: Progress,
+    {
+        match self.resolve(name.into(), format.name()) {
+            Ok((source, path)) => {
+                let options = LoadOptions {
+                    priority,
+                    ..Default::default()
+                };
+                self.load_shared(path, format, source.as_str(), options, progress, storage)
+            }
+            Err((name, e)) => self.load_failed(name, e, progress, storage),
+        }
     }
 
     /// Loads an asset with a given id and format from a custom source.
     /// The actual work is done in a worker thread, thus this method immediately returns a handle.
     ///
+    /// Loading the same asset (same storage, asset type, source, name and format) again
+    /// while the first load is still in flight, or while the loaded asset is still in the
+    /// storage and a handle to it is alive, returns the existing handle instead of loading
+    /// the asset a second time. The progress passed to such a call is still notified once
+    /// the asset finished loading. Formats are the same if they have the same type and
+    /// `Debug` output, which thus has to include all of their options; loads with formats
+    /// which aren't `Format::shareable`, like `MapFormat`, are never shared. Use
+    /// `load_unique` if you need a separate copy of the asset.
+    ///
     /// ## Parameters
     ///
     /// * `name`: this is just an identifier for the asset, most likely a file name e.g.
@@ -113,9 +510,269 @@ impl Loader {
         name: N,
         format: F,
         source: &S,
+        progress: P,
+        storage: &AssetStorage<A>,
+    ) -> Handle<A>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        N: Into<String>,
+        P: Progress,
+        S: AsRef<str> + Eq + Hash + ?Sized,
+        String: Borrow<S>,
+    {
+        self.load_shared(
+            name,
+            format,
+            source,
+            LoadOptions::default(),
+            progress,
+            storage,
+        )
+    }
+
+    /// Loads an asset with a given format from the default (directory) source, failing
+    /// it if it didn't finish after `timeout`. Like `load`, this consults the `Manifest`.
+    ///
+    /// See `set_load_timeout` and `load_from` for more information.
+    pub fn load_with_timeout<A, F, N, P>(
+        &self,
+        name: N,
+        format: F,
+        timeout: Duration,
+        progress: P,
+        storage: &AssetStorage<A>,
+    ) -> Handle<A>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        N: Into<String>,
+        P: Progress,
+    {
+        match self.resolve(name.into(), format.name()) {
+            Ok((source, path)) => {
+                let options = LoadOptions {
+                    timeout: Some(timeout),
+                    ..Default::default()
+                };
+                self.load_shared(path, format, source.as_str(), options, progress, storage)
+            }
+            Err((name, e)) => self.load_failed(name, e, progress, storage),
+        }
+    }
+
+    /// Loads an asset with a given id and format from a custom source, using the given
+    /// options instead of the defaults.
+    ///
+    /// See `load_from` for more information.
+    pub fn load_with_options<A, F, N, P, S>(
+        &self,
+        name: N,
+        format: F,
+        source: &S,
+        options: LoadOptions,
+        progress: P,
+        storage: &AssetStorage<A>,
+    ) -> Handle<A>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        N: Into<String>,
+        P: Progress,
+        S: AsRef<str> + Eq + Hash + ?Sized,
+        String: Borrow<S>,
+    {
+        self.load_shared(name, format, source, options, progress, storage)
+    }
+
+    /// Loads an asset with a given id and format from a custom source, always starting
+    /// a new load even if the same asset is already loading or loaded.
+    ///
+    /// See `load_from` for more information.
+    pub fn load_unique<A, F, N, P, S>(
+        &self,
+        name: N,
+        format: F,
+        source: &S,
+        progress: P,
+        storage: &AssetStorage<A>,
+    ) -> Handle<A>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        N: Into<String>,
+        P: Progress,
+        S: AsRef<str> + Eq + Hash + ?Sized,
+        String: Borrow<S>,
+    {
+        self.load_from_inner(
+            name,
+            format,
+            source,
+            LoadOptions::default(),
+            SharedPriority::default(),
+            progress,
+            storage,
+            None,
+        )
+    }
+
+    /// Loads an asset with a given id and format from a custom source, returning a
+    /// `LoadToken` together with the handle which can be used to abort the load.
+    ///
+    /// Once cancelled, the asset is neither read from the source nor processed if
+    /// that did not happen yet, and the tracker is notified with `Tracker::cancel`.
+    ///
+    /// See `load_from` for more information.
+    pub fn load_cancellable<A, F, N, P, S>(
+        &self,
+        name: N,
+        format: F,
+        source: &S,
+        progress: P,
+        storage: &AssetStorage<A>,
+    ) -> (Handle<A>, LoadToken)
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        N: Into<String>,
+        P: Progress,
+        S: AsRef<str> + Eq + Hash + ?Sized,
+        String: Borrow<S>,
+    {
+        let token = LoadToken::new();
+        let handle = self.load_from_inner(
+            name,
+            format,
+            source,
+            LoadOptions::default(),
+            SharedPriority::default(),
+            progress,
+            storage,
+            Some(token.clone()),
+        );
+
+        (handle, token)
+    }
+
+    fn load_shared<A, F, N, P, S>(
+        &self,
+        name: N,
+        format: F,
+        source: &S,
+        options: LoadOptions,
         mut progress: P,
         storage: &AssetStorage<A>,
     ) -> Handle<A>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        N: Into<String>,
+        P: Progress,
+        S: AsRef<str> + Eq + Hash + ?Sized,
+        String: Borrow<S>,
+    {
+        if !format.shareable() {
+            let priority = SharedPriority::new(options.priority);
+            return self.load_from_inner(
+                name, format, source, options, priority, progress, storage, None,
+            );
+        }
+
+        let name = name.into();
+        let key = SharedKey {
+            asset: TypeId::of::<A>(),
+            format: TypeId::of::<F>(),
+            name: name.clone(),
+            options: format!("{:?}", format),
+            source: source.as_ref().to_owned(),
+            storage: &*storage.processed as *const ProcessedQueue<A> as usize,
+        };
+
+        let mut shared = self.shared.lock();
+        if let Some(load) = shared.map.get(&key) {
+            let handle = load
+                .handle
+                .as_any()
+                .downcast_ref::<WeakHandle<A>>()
+                .and_then(WeakHandle::upgrade);
+            if let Some(handle) = handle {
+                match *load.state.lock() {
+                    SharedLoadState::Loading(ref mut trackers) => {
+                        debug!(
+                            "{:?}: Asset {:?} is already loading (handle id: {:?})",
+                            A::NAME,
+                            name,
+                            handle,
+                        );
+                        progress.add_assets(1);
+                        trackers.push(Box::new(progress.create_tracker()));
+                        // Jobs of the load which didn't start yet move up in their queues.
+                        if load.priority.raise(options.priority) {
+                            self.jobs.lock().reprioritize();
+                            if let Some(ref io) = *self.io.lock() {
+                                io.reprioritize();
+                            }
+                        }
+
+                        return handle;
+                    }
+                    // The asset may have been unloaded since, e.g. by `unload_all`.
+                    SharedLoadState::Loaded if storage.contains(&handle) => {
+                        debug!(
+                            "{:?}: Asset {:?} is already loaded (handle id: {:?})",
+                            A::NAME,
+                            name,
+                            handle,
+                        );
+                        progress.add_assets(1);
+                        Box::new(progress.create_tracker()).success();
+
+                        return handle;
+                    }
+                    // Give failed and unloaded loads another try.
+                    SharedLoadState::Loaded | SharedLoadState::Failed => {}
+                }
+            }
+        }
+
+        progress.add_assets(1);
+        let tracker = Box::new(progress.create_tracker()) as Box<dyn Tracker>;
+        let state = Arc::new(Mutex::new(SharedLoadState::Loading(vec![tracker])));
+        let priority = SharedPriority::new(options.priority);
+        let handle = self.load_from_inner(
+            name,
+            format,
+            source,
+            options,
+            priority.clone(),
+            SharedProgress::new(state.clone()),
+            storage,
+            None,
+        );
+        shared.insert(
+            key,
+            SharedLoad {
+                handle: Box::new(handle.downgrade()),
+                priority,
+                state,
+            },
+        );
+
+        handle
+    }
+
+    fn load_from_inner<A, F, N, P, S>(
+        &self,
+        name: N,
+        format: F,
+        source: &S,
+        options: LoadOptions,
+        priority: SharedPriority,
+        progress: P,
+        storage: &AssetStorage<A>,
+        token: Option<LoadToken>,
+    ) -> Handle<A>
     where
         A: Asset,
         F: Format<A::Data>,
@@ -126,10 +783,10 @@ impl Loader {
     {
         #[cfg(feature = "profiler")]
         profile_scope!("load_asset_from");
-        use crate::progress::Tracker;
 
         let name = name.into();
         let source = source.as_ref();
+        let source_id = source.to_owned();
 
         let format_name = format.name();
         let source_name = match source {
@@ -148,41 +805,361 @@ impl Loader {
             handle,
         );
 
+        let requested = Instant::now();
+        let sequence = storage.processed.next_sequence();
+        let mut progress = self.pending.register(
+            PendingLoad {
+                name: name.clone(),
+                asset_type_name: A::NAME,
+                source: source.to_owned(),
+                enqueued: requested,
+                priority: priority.get(),
+            },
+            progress,
+        );
         progress.add_assets(1);
-        let tracker = progress.create_tracker();
+        let tracker = Box::new(progress.create_tracker()) as Box<dyn Tracker>;
+        let tracker = match options.timeout.or(self.timeout) {
+            Some(timeout) => storage.watch_deadline(
+                &handle,
+                name.clone(),
+                requested,
+                timeout,
+                token.clone(),
+                tracker,
+            ),
+            None => tracker,
+        };
 
-        let source = self.source(source);
         let handle_clone = handle.clone();
         let processed = storage.processed.clone();
 
+        let source = match self.source(source) {
+            Ok(source) => source,
+            Err(e) => {
+                processed.push(Processed::NewAsset {
+                    data: Err(e),
+                    handle,
+                    name,
+                    tracker,
+                    token,
+                    priority: priority.get(),
+                    requested,
+                    sequence,
+                    provenance: None,
+                });
+
+                return handle_clone;
+            }
+        };
+        let retry = options.retry.unwrap_or(self.retry);
+        let source: Arc<dyn Source> = if retry.retries() {
+            Arc::new(Retrying::new(source, retry))
+        } else {
+            source
+        };
+
         let hot_reload = if self.hot_reload {
             Some(objekt::clone_box(&format) as Box<dyn Format<A::Data>>)
         } else {
             None
         };
 
-        let cl = move || {
+        let path = name.clone();
+        let metadata = hot_reload.is_some();
+        let hash = metadata && self.compare_contents;
+        let io_token = token.clone();
+        let job_priority = priority.clone();
+
+        let cl = move |source: Arc<dyn Source>| {
             #[cfg(feature = "profiler")]
             profile_scope!("load_asset_from_worker");
-            let data = format
-                .import(name.clone(), source, hot_reload)
-                .with_context(|_| Error::Format(format_name));
-            let tracker = Box::new(tracker) as Box<dyn Tracker>;
-
+            let cancelled = || token.as_ref().map(LoadToken::is_cancelled).unwrap_or(false);
+            let measured = Arc::new(Measured::new(source, name.clone(), hash));
+            let data = if cancelled() {
+                Err(Error::from(error::Error::Cancelled))
+            } else {
+                format
+                    .import(name.clone(), measured.clone(), hot_reload)
+                    .with_context(|_| error::Error::Format(format_name))
+                    .map(|value| measured.record_contents(value))
+            };
+            let provenance = Some(Arc::new(measured.provenance(source_id)));
+            // Drop the imported data right away if the load got cancelled in the meantime.
+            let data = if cancelled() {
+                Err(Error::from(error::Error::Cancelled))
+            } else {
+                data
+            };
             processed.push(Processed::NewAsset {
                 data,
                 handle,
                 name,
                 tracker,
+                token,
+                priority: priority.get(),
+                requested,
+                sequence,
+                provenance,
             });
         };
-        self.pool.spawn(cl);
+        match self.io_pool() {
+            Some(io) => {
+                let jobs = self.jobs.clone();
+                let pool = self.pool.clone();
+                io.spawn(job_priority.clone(), move || {
+                    // Cancelled loads don't need to be read, `cl` reports the cancellation.
+                    let cancelled = io_token
+                        .as_ref()
+                        .map(LoadToken::is_cancelled)
+                        .unwrap_or(false);
+                    let source: Arc<dyn Source> = if cancelled {
+                        source
+                    } else {
+                        Arc::new(Prefetched::read(source, path, metadata))
+                    };
+                    spawn(&jobs, &pool, job_priority, move || cl(source));
+                });
+            }
+            None => spawn(&self.jobs, &self.pool, job_priority, move || cl(source)),
+        }
 
         handle_clone
     }
 
-    /// Load an asset from data and return a handle.
-    pub fn load_from_data<A, P>(
+    /// Loads an asset with a streaming format from a custom source, which parses the asset
+    /// while it is read using `Source::open` instead of loading all of it into memory first.
+    ///
+    /// As reading and parsing are interleaved, the work is done on the IO threads (see
+    /// `set_io_threads`) if there are any. Streamed assets are always loaded anew and
+    /// can't be hot reloaded.
+    ///
+    /// See `load_from` for more information.
+    pub fn load_streaming<A, F, N, P, S>(
+        &self,
+        name: N,
+        format: F,
+        source: &S,
+        progress: P,
+        storage: &AssetStorage<A>,
+    ) -> Handle<A>
+    where
+        A: Asset,
+        F: StreamingFormat<A::Data>,
+        N: Into<String>,
+        P: Progress,
+        S: AsRef<str> + Eq + Hash + ?Sized,
+        String: Borrow<S>,
+    {
+        #[cfg(feature = "profiler")]
+        profile_scope!("load_asset_streaming");
+
+        let name = name.into();
+        let source = source.as_ref();
+        let format_name = format.name();
+        let handle = storage.allocate();
+
+        debug!(
+            "{:?}: Streaming asset {:?} with format {:?} from source {:?} (handle id: {:?})",
+            A::NAME,
+            name,
+            format_name,
+            source,
+            handle,
+        );
+
+        let requested = Instant::now();
+        let sequence = storage.processed.next_sequence();
+        let mut progress = self.pending.register(
+            PendingLoad {
+                name: name.clone(),
+                asset_type_name: A::NAME,
+                source: source.to_owned(),
+                enqueued: requested,
+                priority: LoadPriority::Normal,
+            },
+            progress,
+        );
+        progress.add_assets(1);
+        let tracker = Box::new(progress.create_tracker()) as Box<dyn Tracker>;
+
+        let handle_clone = handle.clone();
+        let processed = storage.processed.clone();
+        let source = self.source(source);
+
+        let cl = move || {
+            #[cfg(feature = "profiler")]
+            profile_scope!("load_asset_streaming_worker");
+            let data = source
+                .and_then(|source| source.open(&name).with_context(|_| error::Error::Source))
+                .and_then(|reader| {
+                    format
+                        .import(reader)
+                        .with_context(|_| error::Error::Format(format_name))
+                })
+                .map(FormatValue::data);
+
+            processed.push(Processed::NewAsset {
+                data,
+                handle,
+                name,
+                tracker,
+                token: None,
+                priority: LoadPriority::Normal,
+                requested,
+                sequence,
+                provenance: None,
+            });
+        };
+        match self.io_pool() {
+            Some(io) => io.spawn(LoadPriority::Normal.into(), cl),
+            None => self.spawn(LoadPriority::Normal, cl),
+        }
+
+        handle_clone
+    }
+
+    /// Loads an asset with a given id and format from an asynchronous source.
+    ///
+    /// The source is looked up in the sources added with `add_async_source` first. Sources
+    /// added with `add_source` can be used as well; their loads are run on the IO threads.
+    /// Once the bytes are there, they are imported on the `ThreadPool` and handed to
+    /// `AssetStorage::process` like for any other load. Assets loaded this way can't be
+    /// hot reloaded.
+    ///
+    /// See `load_from` for more information.
+    #[cfg(feature = "async_source")]
+    pub fn load_from_async_source<A, F, N, P, S>(
+        &self,
+        name: N,
+        format: F,
+        source: &S,
+        progress: P,
+        storage: &AssetStorage<A>,
+    ) -> Handle<A>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        N: Into<String>,
+        P: Progress,
+        S: AsRef<str> + Eq + Hash + ?Sized,
+        String: Borrow<S>,
+    {
+        #[cfg(feature = "profiler")]
+        profile_scope!("load_asset_from_async_source");
+
+        let name = name.into();
+        let source = source.as_ref();
+        let format_name = format.name();
+        let handle = storage.allocate();
+
+        debug!(
+            "{:?}: Loading asset {:?} with format {:?} from asynchronous source {:?} (handle id: {:?})",
+            A::NAME,
+            name,
+            format_name,
+            source,
+            handle,
+        );
+
+        let requested = Instant::now();
+        let sequence = storage.processed.next_sequence();
+        let mut progress = self.pending.register(
+            PendingLoad {
+                name: name.clone(),
+                asset_type_name: A::NAME,
+                source: source.to_owned(),
+                enqueued: requested,
+                priority: LoadPriority::Normal,
+            },
+            progress,
+        );
+        progress.add_assets(1);
+        let tracker = Box::new(progress.create_tracker()) as Box<dyn Tracker>;
+
+        let handle_clone = handle.clone();
+        let processed = storage.processed.clone();
+        let bytes = match self.async_source(source) {
+            Ok(source) => source.load(&name),
+            Err(e) => futures::future::ready(Err(e)).boxed(),
+        };
+
+        let jobs = self.jobs.clone();
+        let pool = self.pool.clone();
+        let future = bytes.map(move |bytes| {
+            let cl = move || {
+                #[cfg(feature = "profiler")]
+                profile_scope!("load_asset_from_async_source_worker");
+                let data = bytes
+                    .with_context(|_| error::Error::Source)
+                    .and_then(|bytes| {
+                        format
+                            .import(name.clone(), Arc::new(BytesSource::new(bytes)), None)
+                            .with_context(|_| error::Error::Format(format_name))
+                    });
+
+                processed.push(Processed::NewAsset {
+                    data,
+                    handle,
+                    name,
+                    tracker,
+                    token: None,
+                    priority: LoadPriority::Normal,
+                    requested,
+                    sequence,
+                    provenance: None,
+                });
+            };
+            spawn(&jobs, &pool, LoadPriority::Normal.into(), cl);
+        });
+        self.spawn_future(future.boxed());
+
+        handle_clone
+    }
+
+    /// Loads an asset with a given id and format from a custom source, returning a future
+    /// which resolves once the asset has been processed.
+    ///
+    /// The future resolves to the handle of the asset if it was loaded successfully, or to
+    /// the error that occurred during importing or processing otherwise. Like with `load_from`,
+    /// the actual work is done in a worker thread; the future itself does not need to be polled
+    /// for loading to make progress, but it will only resolve once `AssetStorage::process`
+    /// has handled the asset. It can be driven by any executor.
+    ///
+    /// See `load_from` for more information.
+    pub fn load_async<A, F, N, S>(
+        &self,
+        name: N,
+        format: F,
+        source: &S,
+        storage: &AssetStorage<A>,
+    ) -> impl Future<Output = Result<Handle<A>, Error>>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        N: Into<String>,
+        S: AsRef<str> + Eq + Hash + ?Sized,
+        String: Borrow<S>,
+    {
+        let (progress, completion) = progress::completion();
+        let handle = self.load_from(name, format, source, progress, storage);
+
+        LoadFuture {
+            completion,
+            handle: Some(handle),
+        }
+    }
+
+    /// Returns all loads from a source which have not completed yet.
+    ///
+    /// This is a snapshot; loads complete once `AssetStorage::process` has
+    /// processed their asset.
+    pub fn pending_loads(&self) -> Vec<PendingLoad> {
+        self.pending.snapshot()
+    }
+
+    /// Load an asset from data and return a handle.
+    pub fn load_from_data<A, P>(
         &self,
         data: A::Data,
         mut progress: P,
@@ -201,6 +1178,11 @@ impl Loader {
             handle: handle.clone(),
             name: "<Data>".into(),
             tracker,
+            token: None,
+            priority: LoadPriority::Normal,
+            requested: Instant::now(),
+            sequence: storage.processed.next_sequence(),
+            provenance: None,
         });
 
         handle
@@ -223,8 +1205,10 @@ impl Loader {
         let tracker = Box::new(tracker);
         let handle = storage.allocate();
         let processed = storage.processed.clone();
+        let requested = Instant::now();
+        let sequence = storage.processed.next_sequence();
 
-        self.pool.spawn({
+        self.spawn(LoadPriority::Normal, {
             let handle = handle.clone();
             move || {
                 processed.push(Processed::NewAsset {
@@ -232,6 +1216,11 @@ impl Loader {
                     handle: handle.clone(),
                     name: "<Data>".into(),
                     tracker,
+                    token: None,
+                    priority: LoadPriority::Normal,
+                    requested,
+                    sequence,
+                    provenance: None,
                 });
             }
         });
@@ -239,10 +1228,1156 @@ impl Loader {
         handle
     }
 
-    fn source(&self, source: &str) -> Arc<dyn Source> {
-        self.sources
-            .get(source)
-            .expect("No such source. Maybe you forgot to add it with `Loader::add_source`?")
-            .clone()
+    /// Load an asset from data, converting it into the asset on a worker thread.
+    ///
+    /// Unlike `load_from_data`, which leaves the conversion to `AssetStorage::process`,
+    /// this runs `ProcessableAsset::process` on the thread pool, so `process` only needs
+    /// to insert the finished asset. If conversion does not finish (returning
+    /// `ProcessingState::Loading`), the remaining work is left to `process` as usual.
+    ///
+    /// `name` is used to report errors and defaults to `"<Data>"`.
+    pub fn load_from_data_async_processed<A, P>(
+        &self,
+        data: A::Data,
+        name: Option<String>,
+        mut progress: P,
+        storage: &AssetStorage<A>,
+    ) -> Handle<A>
+    where
+        A: ProcessableAsset,
+        P: Progress,
+    {
+        progress.add_assets(1);
+        let tracker = progress.create_tracker();
+        let tracker = Box::new(tracker);
+        let handle = storage.allocate();
+        let processed = storage.processed.clone();
+        let name = name.unwrap_or_else(|| "<Data>".into());
+        let requested = Instant::now();
+        let sequence = storage.processed.next_sequence();
+
+        self.spawn(LoadPriority::Normal, {
+            let handle = handle.clone();
+            move || {
+                #[cfg(feature = "profiler")]
+                profile_scope!("process_asset_from_data");
+                let p = match A::process(data) {
+                    Ok(ProcessingState::Loaded(asset)) => Processed::Converted {
+                        asset,
+                        handle,
+                        name,
+                        tracker,
+                        requested,
+                        sequence,
+                    },
+                    Ok(ProcessingState::Loading(data)) | Ok(ProcessingState::Delayed(data, _)) => {
+                        Processed::NewAsset {
+                            data: Ok(FormatValue::data(data)),
+                            handle,
+                            name,
+                            tracker,
+                            token: None,
+                            priority: LoadPriority::Normal,
+                            requested,
+                            sequence,
+                            provenance: None,
+                        }
+                    }
+                    Ok(ProcessingState::Multiple(_)) => Processed::NewAsset {
+                        data: Err(format_err!(
+                            "Multiple assets can't be converted on a worker thread"
+                        )),
+                        handle,
+                        name,
+                        tracker,
+                        token: None,
+                        priority: LoadPriority::Normal,
+                        requested,
+                        sequence,
+                        provenance: None,
+                    },
+                    Err(e) => Processed::NewAsset {
+                        data: Err(e),
+                        handle,
+                        name,
+                        tracker,
+                        token: None,
+                        priority: LoadPriority::Normal,
+                        requested,
+                        sequence,
+                        provenance: None,
+                    },
+                };
+                processed.push(p);
+            }
+        });
+
+        handle
+    }
+
+    /// Imports the asset named `name` again, replacing the asset of the existing handle
+    /// `storage` knows it by (see `AssetStorage::get_handle_by_name`) like a hot reload,
+    /// so its version is bumped with the next call to `AssetStorage::process`.
+    ///
+    /// Names are resolved like `load` and `load_from` do: if `source` is the default
+    /// source `""`, the `Manifest` and the mounted sources are consulted, and the name
+    /// normalizer of `storage` is applied before looking the asset up.
+    ///
+    /// Unlike `AssetStorage::request_reload`, this also works for assets which have no
+    /// reload object attached, e.g. because hot reloading was disabled when they were
+    /// loaded. If hot reloading is enabled now, the asset keeps being watched afterwards.
+    ///
+    /// Returns `false` if there is no loaded asset named `name`.
+    pub fn reload<A, F, N, S>(
+        &self,
+        name: N,
+        format: F,
+        source: &S,
+        storage: &AssetStorage<A>,
+    ) -> bool
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        N: Into<String>,
+        S: AsRef<str> + ?Sized,
+    {
+        let format_name = format.name();
+        let (source, name) = match source.as_ref() {
+            "" => match self.resolve(name.into(), format_name) {
+                Ok(resolved) => resolved,
+                Err(_) => return false,
+            },
+            source => (source.to_owned(), name.into()),
+        };
+        let handle = match storage.get_handle_by_name(&storage.normalize_name(&name)) {
+            Some(handle) => handle,
+            None => return false,
+        };
+
+        debug!(
+            "{:?}: Reloading asset {:?} with format {:?} (handle id: {:?})",
+            A::NAME,
+            name,
+            format_name,
+            handle,
+        );
+
+        let hot_reload = if self.hot_reload {
+            Some(objekt::clone_box(&format) as Box<dyn Format<A::Data>>)
+        } else {
+            None
+        };
+        let hash = hot_reload.is_some() && self.compare_contents;
+        let source = self.source(&source);
+        let processed = storage.processed.clone();
+        let sequence = processed.next_sequence();
+        storage.reloads_in_flight.fetch_add(1, Ordering::Relaxed);
+
+        self.spawn(LoadPriority::Normal, move || {
+            #[cfg(feature = "profiler")]
+            profile_scope!("reload_asset");
+            let data = source.and_then(|source| {
+                let measured = Arc::new(Measured::new(source, name.clone(), hash));
+                format
+                    .import(name.clone(), measured.clone(), hot_reload)
+                    .with_context(|_| error::Error::Format(format_name))
+                    .map(|value| measured.record_contents(value))
+            });
+            processed.push(Processed::HotReload {
+                data,
+                handle,
+                name,
+                old_reload: None,
+                sequence,
+            });
+        });
+
+        true
+    }
+
+    /// Queues a job and spawns a task on the thread pool which runs
+    /// the queued job with the highest priority.
+    fn spawn<J>(&self, priority: LoadPriority, job: J)
+    where
+        J: FnOnce() + Send + 'static,
+    {
+        spawn(&self.jobs, &self.pool, priority.into(), job);
+    }
+
+    /// Resolves an asset name to a source id and path using the manifest.
+    fn resolve(
+        &self,
+        name: String,
+        format_name: &str,
+    ) -> Result<(String, String), (String, Error)> {
+        let manifest = self.manifest.read();
+        match manifest.entries.get(&name) {
+            Some(entry) => {
+                if let Some(ref hint) = entry.format {
+                    if !hint.eq_ignore_ascii_case(format_name) {
+                        warn!(
+                            "Asset {:?} is listed with format {:?} in the manifest, but is loaded with format {:?}",
+                            name, hint, format_name,
+                        );
+                    }
+                }
+
+                Ok(self.mounted(entry.source.clone(), entry.path.clone()))
+            }
+            None if manifest.strict => {
+                let e = Error::from(error::Error::UnknownAssetName(name.clone()));
+                Err((name, e))
+            }
+            None => Ok(self.mounted(String::new(), name)),
+        }
+    }
+
+    /// Resolves a path of the default source to the source mounted at the
+    /// longest prefix of the path and the remaining path, if there is one.
+    fn mounted(&self, source: String, path: String) -> (String, String) {
+        if !source.is_empty() {
+            return (source, path);
+        }
+
+        let mounts = self.mounts.read();
+        let mount = mounts
+            .iter()
+            .filter(|(prefix, _)| {
+                prefix.is_empty()
+                    || path == *prefix
+                    || (path.starts_with(prefix.as_str()) && path[prefix.len()..].starts_with('/'))
+            })
+            .max_by_key(|(prefix, _)| prefix.len());
+        match mount {
+            Some((prefix, id)) => {
+                let path = path[prefix.len()..].trim_start_matches('/').to_owned();
+                (id.clone(), path)
+            }
+            None => (source, path),
+        }
+    }
+
+    /// Reports a load which failed before it could be started to the tracker.
+    fn load_failed<A, P>(
+        &self,
+        name: String,
+        error: Error,
+        mut progress: P,
+        storage: &AssetStorage<A>,
+    ) -> Handle<A>
+    where
+        A: Asset,
+        P: Progress,
+    {
+        progress.add_assets(1);
+        let tracker = progress.create_tracker();
+        let tracker = Box::new(tracker);
+        let handle = storage.allocate();
+        storage.processed.push(Processed::NewAsset {
+            data: Err(error),
+            handle: handle.clone(),
+            name,
+            tracker,
+            token: None,
+            priority: LoadPriority::Normal,
+            requested: Instant::now(),
+            sequence: storage.processed.next_sequence(),
+            provenance: None,
+        });
+
+        handle
+    }
+
+    fn source(&self, source: &str) -> Result<Arc<dyn Source>, Error> {
+        match self.sources.read().get(source) {
+            Some(source) => Ok(source.clone()),
+            None => Err(Error::from(error::Error::SourceNotFound(source.to_owned()))),
+        }
+    }
+
+    /// Returns the asynchronous source with the given id, falling back to
+    /// running the loads of the blocking source with that id on the IO threads.
+    #[cfg(feature = "async_source")]
+    fn async_source(&self, source: &str) -> Result<Arc<dyn AsyncSource>, Error> {
+        if let Some(source) = self.async_sources.read().get(source) {
+            return Ok(source.clone());
+        }
+
+        let source = self.source(source)?;
+        let io = self.io_pool();
+
+        Ok(Arc::new(Blocking::new(
+            source,
+            io.as_ref().map(|io| &**io),
+            self.pool.clone(),
+        )))
+    }
+
+    /// Runs a future using the executor set with `set_executor`.
+    #[cfg(feature = "async_source")]
+    fn spawn_future(&self, future: BoxFuture<'static, ()>) {
+        match self.executor {
+            Some(ref executor) => executor(future),
+            None => {
+                let spawner: Spawner = match self.io_pool() {
+                    Some(io) => io.spawner(),
+                    None => {
+                        let pool = self.pool.clone();
+                        Arc::new(move |job: Box<dyn FnOnce() + Send>| pool.spawn(job))
+                    }
+                };
+                io::spawn_future(spawner, future);
+            }
+        }
+    }
+}
+
+/// Runs the futures of asynchronous sources, see `Loader::set_executor`.
+#[cfg(feature = "async_source")]
+type Executor = Box<dyn Fn(BoxFuture<'static, ()>) + Send + Sync>;
+
+/// Queues a job in `jobs` and spawns a task on `pool` which runs
+/// the queued job with the highest priority.
+fn spawn<J>(jobs: &Arc<Mutex<JobQueue>>, pool: &ThreadPool, priority: SharedPriority, job: J)
+where
+    J: FnOnce() + Send + 'static,
+{
+    jobs.lock().push(priority, Box::new(job));
+
+    let jobs = jobs.clone();
+    pool.spawn(move || {
+        let job = jobs.lock().pop();
+        if let Some(job) = job {
+            job();
+        }
+    });
+}
+
+/// Identifies loads which can be shared, see `Loader::load_from`.
+#[derive(Eq, Hash, PartialEq)]
+struct SharedKey {
+    asset: TypeId,
+    format: TypeId,
+    name: String,
+    /// The `Debug` output of the format, which holds its options as long as the format is
+    /// `Format::shareable`.
+    options: String,
+    source: String,
+    /// The address of the queue of the storage, which unlike its id differs between a
+    /// storage and its deep clones.
+    storage: usize,
+}
+
+/// Loads which are in flight or finished and can be shared.
+#[derive(Default)]
+struct SharedLoads {
+    map: FnvHashMap<SharedKey, SharedLoad>,
+    prune_at: usize,
+}
+
+impl SharedLoads {
+    fn insert(&mut self, key: SharedKey, load: SharedLoad) {
+        // Get rid of loads whose handles are all gone once in a while.
+        if self.map.len() >= self.prune_at {
+            self.map.retain(|_, load| !load.handle.is_dead());
+            self.prune_at = (self.map.len() * 2).max(64);
+        }
+        self.map.insert(key, load);
+    }
+}
+
+struct SharedLoad {
+    handle: Box<dyn AnyWeakHandle>,
+    priority: SharedPriority,
+    state: Arc<Mutex<SharedLoadState>>,
+}
+
+/// Type erased `WeakHandle`.
+trait AnyWeakHandle: Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn is_dead(&self) -> bool;
+}
+
+impl<A: Asset> AnyWeakHandle for WeakHandle<A> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn is_dead(&self) -> bool {
+        WeakHandle::is_dead(self)
+    }
+}
+
+/// The priority of an asset load, see `Loader::load_with_priority`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum LoadPriority {
+    /// For assets which are not needed soon, e.g. when prefetching.
+    Low = 0,
+    /// The default priority.
+    Normal = 1,
+    /// For assets which are needed soon.
+    High = 2,
+    /// For assets which are needed right now. Their data is processed even while the
+    /// storage is paused, see `AssetStorage::set_paused`.
+    Immediate = 3,
+}
+
+impl Default for LoadPriority {
+    fn default() -> Self {
+        LoadPriority::Normal
+    }
+}
+
+/// Options of an asset load, see `Loader::load_with_options`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LoadOptions {
+    /// The priority of the load, see `Loader::load_with_priority`.
+    pub priority: LoadPriority,
+    /// The policy for retrying failed reads, or `None` to use the one set with
+    /// `Loader::set_retry_policy`.
+    pub retry: Option<RetryPolicy>,
+    /// The time after which the load fails if it didn't finish, or `None` to use the
+    /// one set with `Loader::set_load_timeout`.
+    pub timeout: Option<Duration>,
+}
+
+/// The priority of a load, shared with its queued jobs so it can be raised while they
+/// wait for a thread, see `JobQueue::reprioritize`.
+#[derive(Clone, Debug)]
+pub(crate) struct SharedPriority(Arc<AtomicUsize>);
+
+impl SharedPriority {
+    pub(crate) fn new(priority: LoadPriority) -> Self {
+        SharedPriority(Arc::new(AtomicUsize::new(priority as usize)))
+    }
+
+    pub(crate) fn get(&self) -> LoadPriority {
+        match self.0.load(Ordering::Relaxed) {
+            0 => LoadPriority::Low,
+            1 => LoadPriority::Normal,
+            2 => LoadPriority::High,
+            _ => LoadPriority::Immediate,
+        }
+    }
+
+    /// Raises the priority to `priority`, returning `true` if it was lower before.
+    pub(crate) fn raise(&self, priority: LoadPriority) -> bool {
+        let mut current = self.0.load(Ordering::Relaxed);
+        while current < priority as usize {
+            match self.0.compare_exchange_weak(
+                current,
+                priority as usize,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(actual) => current = actual,
+            }
+        }
+
+        false
+    }
+}
+
+impl Default for SharedPriority {
+    fn default() -> Self {
+        SharedPriority::new(LoadPriority::default())
+    }
+}
+
+impl From<LoadPriority> for SharedPriority {
+    fn from(priority: LoadPriority) -> Self {
+        SharedPriority::new(priority)
+    }
+}
+
+/// Loading jobs waiting for a free thread, ordered by priority
+/// and in FIFO order for equal priorities.
+#[derive(Default)]
+pub(crate) struct JobQueue {
+    heap: BinaryHeap<QueuedJob>,
+    next_order: u64,
+}
+
+impl JobQueue {
+    pub(crate) fn push(&mut self, priority: SharedPriority, job: Box<dyn FnOnce() + Send>) {
+        let order = self.next_order;
+        self.next_order += 1;
+        self.heap.push(QueuedJob {
+            priority: priority.get(),
+            shared: priority,
+            order,
+            job,
+        });
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<Box<dyn FnOnce() + Send>> {
+        self.heap.pop().map(|queued| queued.job)
+    }
+
+    /// Reorders the queued jobs after the priority of some of them has been raised.
+    pub(crate) fn reprioritize(&mut self) {
+        let mut jobs = std::mem::replace(&mut self.heap, BinaryHeap::new()).into_vec();
+        for queued in &mut jobs {
+            queued.priority = queued.shared.get();
+        }
+        self.heap = jobs.into();
+    }
+}
+
+struct QueuedJob {
+    /// The priority the job is ordered by, which is only updated by `reprioritize`.
+    priority: LoadPriority,
+    shared: SharedPriority,
+    order: u64,
+    job: Box<dyn FnOnce() + Send>,
+}
+
+impl Ord for QueuedJob {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+impl PartialOrd for QueuedJob {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for QueuedJob {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == CmpOrdering::Equal
+    }
+}
+
+impl Eq for QueuedJob {}
+
+/// A token allowing to cancel an asset load started with `Loader::load_cancellable`.
+///
+/// Cancelling a load which already completed does nothing.
+#[derive(Clone, Debug, Default)]
+pub struct LoadToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl LoadToken {
+    fn new() -> Self {
+        Default::default()
+    }
+
+    /// Aborts the asset load this token belongs to.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if `cancel` has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Future returned by `Loader::load_async`.
+struct LoadFuture<A> {
+    completion: CompletionFuture,
+    handle: Option<Handle<A>>,
+}
+
+// `LoadFuture` never hands out pinned references to its fields.
+impl<A> Unpin for LoadFuture<A> {}
+
+impl<A> Future for LoadFuture<A> {
+    type Output = Result<Handle<A>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.completion).poll(cx) {
+            Poll::Ready(result) => Poll::Ready(result.map(|()| {
+                this.handle
+                    .take()
+                    .expect("`LoadFuture` polled after completion")
+            })),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Source recording how many bytes have been read for an asset, for its `Provenance`.
+struct Measured {
+    hash: Option<Mutex<Option<u64>>>,
+    path: String,
+    read: Mutex<Option<(u64, SystemTime)>>,
+    source: Arc<dyn Source>,
+}
+
+impl Measured {
+    /// Wraps `source`, also hashing the contents of `path` if `hash` is `true`.
+    fn new(source: Arc<dyn Source>, path: String, hash: bool) -> Self {
+        Measured {
+            hash: if hash { Some(Mutex::new(None)) } else { None },
+            path,
+            read: Mutex::new(None),
+            source,
+        }
+    }
+
+    fn record(&self, path: &str, bytes: &[u8]) {
+        if path == self.path {
+            *self.read.lock() = Some((bytes.len() as u64, SystemTime::now()));
+            if let Some(ref hash) = self.hash {
+                *hash.lock() = Some(content_hash(bytes));
+            }
+        }
+    }
+
+    /// Passes the hash of the contents to the reload object of the imported asset.
+    fn record_contents<D>(&self, mut value: FormatValue<D>) -> FormatValue<D> {
+        let hash = self.hash.as_ref().and_then(|hash| *hash.lock());
+        if let (Some(hash), Some(reload)) = (hash, value.reload.as_mut()) {
+            reload.record_contents(hash);
+        }
+
+        value
+    }
+
+    fn provenance(&self, source: String) -> Provenance {
+        let read = *self.read.lock();
+        Provenance {
+            source,
+            path: self
+                .source
+                .resolve(&self.path)
+                .unwrap_or_else(|| self.path.clone()),
+            len: read.map(|(len, _)| len),
+            loaded_at: read.map(|(_, at)| at).unwrap_or_else(SystemTime::now),
+        }
+    }
+}
+
+impl Source for Measured {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        self.source.modified(path)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        let bytes = self.source.load(path)?;
+        self.record(path, &bytes);
+        Ok(bytes)
+    }
+
+    fn open(&self, path: &str) -> Result<Box<dyn Read + Send>, Error> {
+        self.source.open(path)
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        self.source.list(path)
+    }
+
+    fn entries(&self, prefix: &str, recursive: bool) -> Result<Vec<SourceEntry>, Error> {
+        self.source.entries(prefix, recursive)
+    }
+
+    fn is_available(&self) -> bool {
+        self.source.is_available()
+    }
+
+    fn load_with_metadata(&self, path: &str) -> Result<(Vec<u8>, u64), Error> {
+        let (bytes, modified) = self.source.load_with_metadata(path)?;
+        self.record(path, &bytes);
+        Ok((bytes, modified))
+    }
+
+    fn resolve(&self, path: &str) -> Option<String> {
+        self.source.resolve(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use amethyst_core::ecs::prelude::VecStorage;
+    use amethyst_error::{format_err, Error};
+    use parking_lot::Mutex;
+    use rayon::{ThreadPool, ThreadPoolBuilder};
+
+    use super::{JobQueue, Loader, SharedPriority};
+    use crate::{
+        Asset, AssetStorage, ByExtension, Format, Handle, LoadPriority, Manifest, ManifestEntry,
+        MemorySource, ProcessableAsset, ProcessingState, ProgressCounter, Source,
+    };
+
+    struct Text(String);
+
+    impl Asset for Text {
+        const NAME: &'static str = "test::Text";
+        type Data = String;
+        type HandleStorage = VecStorage<Handle<Text>>;
+    }
+
+    impl ProcessableAsset for Text {
+        fn process(data: String) -> Result<ProcessingState<Text>, Error> {
+            if data.is_empty() {
+                Err(format_err!("Text is empty"))
+            } else {
+                Ok(ProcessingState::Loaded(Text(data)))
+            }
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct TextFormat {
+        uppercase: bool,
+    }
+
+    impl Format<String> for TextFormat {
+        fn name(&self) -> &'static str {
+            "TEXT"
+        }
+
+        fn import_simple(&self, bytes: Vec<u8>) -> Result<String, Error> {
+            let text = String::from_utf8(bytes)?;
+            Ok(if self.uppercase {
+                text.to_uppercase()
+            } else {
+                text
+            })
+        }
+    }
+
+    fn pool() -> Arc<ThreadPool> {
+        Arc::new(
+            ThreadPoolBuilder::new()
+                .num_threads(1)
+                .build()
+                .expect("Invalid config"),
+        )
+    }
+
+    fn loader() -> (Loader, Arc<ThreadPool>) {
+        let pool = pool();
+        let mut source = MemorySource::new();
+        source.insert("text", &b"text"[..]);
+        source.insert("text.txt", &b"text"[..]);
+        let loader = Loader::with_default_source(source, pool.clone());
+
+        (loader, pool)
+    }
+
+    fn wait_for(storage: &mut AssetStorage<Text>, pool: &ThreadPool, handles: &[&Handle<Text>]) {
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(Text(data))),
+            pool,
+            |storage| handles.iter().all(|handle| storage.contains(handle)),
+        );
+    }
+
+    #[test]
+    fn duplicate_loads_share_one_handle() {
+        let (loader, pool) = loader();
+        let mut storage = AssetStorage::<Text>::new();
+        let plain = TextFormat { uppercase: false };
+
+        let first = loader.load("text", plain.clone(), (), &storage);
+        let loading = loader.load("text", plain.clone(), (), &storage);
+        assert_eq!(first, loading);
+        wait_for(&mut storage, &pool, &[&first]);
+
+        assert_eq!(first, loader.load("text", plain.clone(), (), &storage));
+        assert_ne!(first, loader.load_unique("text", plain, "", (), &storage));
+    }
+
+    #[test]
+    fn loads_with_other_options_or_storages_are_not_shared() {
+        let (loader, pool) = loader();
+        let mut storage = AssetStorage::<Text>::new();
+        let mut other = AssetStorage::<Text>::new();
+
+        let plain = loader.load("text", TextFormat { uppercase: false }, (), &storage);
+        let upper = loader.load("text", TextFormat { uppercase: true }, (), &storage);
+        let elsewhere = loader.load("text", TextFormat { uppercase: false }, (), &other);
+        assert_ne!(plain, upper);
+        wait_for(&mut storage, &pool, &[&plain, &upper]);
+        wait_for(&mut other, &pool, &[&elsewhere]);
+
+        assert_eq!("text", storage.get(&plain).unwrap().0);
+        assert_eq!("TEXT", storage.get(&upper).unwrap().0);
+        assert_eq!("text", other.get(&elsewhere).unwrap().0);
+    }
+
+    #[test]
+    fn differently_configured_inner_formats_are_not_shared() {
+        let (loader, pool) = loader();
+        let mut storage = AssetStorage::<Text>::new();
+        let by_extension =
+            |uppercase| ByExtension::<String>::new().with("txt", TextFormat { uppercase });
+
+        let plain = loader.load("text.txt", by_extension(false), (), &storage);
+        let upper = loader.load("text.txt", by_extension(true), (), &storage);
+        assert_ne!(plain, upper);
+        assert_eq!(
+            plain,
+            loader.load("text.txt", by_extension(false), (), &storage)
+        );
+
+        let map = |format: TextFormat, suffix: &'static str| {
+            format.map(move |text: String| Ok(text + suffix))
+        };
+        let a = loader.load(
+            "text",
+            map(TextFormat { uppercase: false }, "a"),
+            (),
+            &storage,
+        );
+        let b = loader.load(
+            "text",
+            map(TextFormat { uppercase: false }, "b"),
+            (),
+            &storage,
+        );
+        assert_ne!(a, b);
+        wait_for(&mut storage, &pool, &[&plain, &upper, &a, &b]);
+
+        assert_eq!("text", storage.get(&plain).unwrap().0);
+        assert_eq!("TEXT", storage.get(&upper).unwrap().0);
+        assert_eq!("texta", storage.get(&a).unwrap().0);
+        assert_eq!("textb", storage.get(&b).unwrap().0);
+    }
+
+    #[test]
+    fn pending_loads_are_listed_until_processed() {
+        let (loader, pool) = loader();
+        let mut storage = AssetStorage::<Text>::new();
+        assert!(loader.pending_loads().is_empty());
+
+        let plain = TextFormat { uppercase: false };
+        let first = loader.load("text", plain.clone(), (), &storage);
+        let second = loader.load("text.txt", plain, (), &storage);
+        let mut pending: Vec<_> = loader
+            .pending_loads()
+            .into_iter()
+            .map(|load| (load.name, load.asset_type_name))
+            .collect();
+        pending.sort();
+        assert_eq!(
+            vec![
+                ("text".to_owned(), Text::NAME),
+                ("text.txt".to_owned(), Text::NAME)
+            ],
+            pending
+        );
+
+        wait_for(&mut storage, &pool, &[&first, &second]);
+        assert!(loader.pending_loads().is_empty());
+    }
+
+    #[test]
+    fn manifest_entries_are_resolved() {
+        let (loader, pool) = loader();
+        let mut storage = AssetStorage::<Text>::new();
+        let mut other = MemorySource::new();
+        other.insert("hello", &b"hello"[..]);
+        loader.add_source("other", other);
+
+        let mut manifest = Manifest::default();
+        let entry = |source: &str, path: &str| ManifestEntry {
+            source: source.to_owned(),
+            path: path.to_owned(),
+            format: None,
+        };
+        manifest
+            .entries
+            .insert("greeting".to_owned(), entry("other", "hello"));
+        manifest
+            .entries
+            .insert("plain".to_owned(), entry("", "text.txt"));
+        loader.set_manifest(manifest.clone());
+
+        let plain = TextFormat { uppercase: false };
+        let greeting = loader.load("greeting", plain.clone(), (), &storage);
+        let listed = loader.load("plain", plain.clone(), (), &storage);
+        let unlisted = loader.load("text", plain.clone(), (), &storage);
+        wait_for(&mut storage, &pool, &[&greeting, &listed, &unlisted]);
+        assert_eq!("hello", storage.get(&greeting).unwrap().0);
+        assert_eq!("text", storage.get(&listed).unwrap().0);
+        assert_eq!("text", storage.get(&unlisted).unwrap().0);
+
+        manifest.strict = true;
+        loader.set_manifest(manifest);
+        let mut counter = ProgressCounter::new();
+        let rejected = loader.load("text.txt", plain, &mut counter, &storage);
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(Text(data))),
+            &pool,
+            |_| counter.num_loading() == 0,
+        );
+        assert_eq!(1, counter.num_failed());
+        assert!(counter.errors()[0]
+            .error
+            .causes()
+            .any(|cause| cause.to_string().contains("is not part of the manifest")));
+        assert!(!storage.contains(&rejected));
+    }
+
+    #[test]
+    fn data_is_converted_on_the_pool() {
+        let (loader, pool) = loader();
+        let mut storage = AssetStorage::<Text>::new();
+        let mut counter = ProgressCounter::new();
+
+        let converted = loader.load_from_data_async_processed(
+            "data".to_owned(),
+            Some("converted".to_owned()),
+            &mut counter,
+            &storage,
+        );
+        let failed =
+            loader.load_from_data_async_processed(String::new(), None, &mut counter, &storage);
+        storage.process_until(
+            |_| unreachable!("The data has been converted on the pool already"),
+            &pool,
+            |_| counter.num_loading() == 0,
+        );
+
+        assert_eq!("data", storage.get(&converted).unwrap().0);
+        assert!(!storage.contains(&failed));
+        let errors = counter.errors();
+        assert_eq!(1, errors.len());
+        assert_eq!("<Data>", errors[0].asset_name);
+    }
+
+    #[test]
+    fn unloaded_assets_are_loaded_again() {
+        let (loader, pool) = loader();
+        let mut storage = AssetStorage::<Text>::new();
+        let plain = TextFormat { uppercase: false };
+
+        let first = loader.load("text", plain.clone(), (), &storage);
+        wait_for(&mut storage, &pool, &[&first]);
+        storage.unload_all();
+
+        let second = loader.load("text", plain, (), &storage);
+        assert_ne!(first, second);
+        wait_for(&mut storage, &pool, &[&second]);
+        assert_eq!("text", storage.get(&second).unwrap().0);
+    }
+
+    #[test]
+    fn io_threads_are_started_by_the_first_load() {
+        let (mut loader, pool) = loader();
+        let mut storage = AssetStorage::<Text>::new();
+        assert!(loader.io.lock().is_none());
+
+        let handle = loader.load("text", TextFormat { uppercase: false }, (), &storage);
+        assert!(loader.io.lock().is_some());
+        wait_for(&mut storage, &pool, &[&handle]);
+
+        loader.set_io_threads(0);
+        assert!(loader.io_pool().is_none());
+        let handle = loader.load("text", TextFormat { uppercase: true }, (), &storage);
+        assert!(loader.io.lock().is_none());
+        wait_for(&mut storage, &pool, &[&handle]);
+        assert_eq!("TEXT", storage.get(&handle).unwrap().0);
+    }
+
+    #[test]
+    fn raised_jobs_are_run_first() {
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        let job = |name: &'static str| {
+            let ran = ran.clone();
+            Box::new(move || ran.lock().push(name)) as Box<dyn FnOnce() + Send>
+        };
+        let mut jobs = JobQueue::default();
+        let prefetch = SharedPriority::new(LoadPriority::Low);
+        jobs.push(prefetch.clone(), job("prefetch"));
+        jobs.push(LoadPriority::Normal.into(), job("normal"));
+
+        assert!(prefetch.raise(LoadPriority::High));
+        assert!(!prefetch.raise(LoadPriority::Normal));
+        jobs.reprioritize();
+        while let Some(job) = jobs.pop() {
+            job();
+        }
+        assert_eq!(vec!["prefetch", "normal"], *ran.lock());
+    }
+
+    #[test]
+    fn loading_again_raises_the_priority() {
+        let (loader, _pool) = loader();
+        let storage = AssetStorage::<Text>::new();
+        let plain = TextFormat { uppercase: false };
+
+        let low = loader.load_with_priority("text", plain.clone(), LoadPriority::Low, (), &storage);
+        let immediate =
+            loader.load_with_priority("text", plain, LoadPriority::Immediate, (), &storage);
+        assert_eq!(low, immediate);
+        let shared = loader.shared.lock();
+        let load = shared.map.values().next().unwrap();
+        assert_eq!(LoadPriority::Immediate, load.priority.get());
+    }
+
+    #[test]
+    fn reloads_resolve_and_normalize_names_like_loads() {
+        let (loader, pool) = loader();
+        let mut storage = AssetStorage::<Text>::new();
+        storage.set_name_normalizer(|name| format!("vfs://{}", name));
+        let mut source = MemorySource::new();
+        source.insert("tank", &b"tank"[..]);
+        loader.mount("mods", source);
+
+        let tank = loader.load("mods/tank", TextFormat { uppercase: false }, (), &storage);
+        wait_for(&mut storage, &pool, &[&tank]);
+        assert_eq!(Some(tank.clone()), storage.get_handle_by_name("vfs://tank"));
+
+        assert!(loader.reload("mods/tank", TextFormat { uppercase: true }, "", &storage));
+        assert!(!loader.reload("mods/missing", TextFormat { uppercase: true }, "", &storage));
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(Text(data))),
+            &pool,
+            |storage| storage.get_version(&tank) == Some(1),
+        );
+        assert_eq!("TANK", storage.get(&tank).unwrap().0);
+    }
+
+    #[test]
+    fn mounts_resolve_longest_prefix() {
+        let (loader, _) = loader();
+        loader.mount("mods/", MemorySource::new());
+        loader.mount("mods/coolmod", MemorySource::new());
+
+        let resolve = |name: &str| loader.resolve(name.to_owned(), "RON").unwrap();
+        assert_eq!(
+            (
+                "[mount mods/coolmod]".to_owned(),
+                "units/tank.ron".to_owned()
+            ),
+            resolve("mods/coolmod/units/tank.ron"),
+        );
+        assert_eq!(
+            ("[mount mods]".to_owned(), "coolmod2/tank.ron".to_owned()),
+            resolve("mods/coolmod2/tank.ron"),
+        );
+        assert_eq!(
+            (String::new(), "base/units/tank.ron".to_owned()),
+            resolve("base/units/tank.ron"),
+        );
+
+        assert!(loader.unmount("mods/coolmod"));
+        assert!(!loader.unmount("mods/coolmod"));
+        assert!(!loader.has_source("[mount mods/coolmod]"));
+        assert_eq!(
+            (
+                "[mount mods]".to_owned(),
+                "coolmod/units/tank.ron".to_owned()
+            ),
+            resolve("mods/coolmod/units/tank.ron"),
+        );
+    }
+
+    fn listing_loader() -> (Loader, Arc<ThreadPool>) {
+        let pool = pool();
+        let mut source = MemorySource::new();
+        for name in &[
+            "dir/a.txt",
+            "dir/b.txt",
+            "dir/c.bin",
+            "dir/sub/d.txt",
+            "e.txt",
+        ] {
+            source.insert(*name, name.as_bytes());
+        }
+        let loader = Loader::with_default_source(source, pool.clone());
+
+        (loader, pool)
+    }
+
+    fn text_by_extension() -> ByExtension<String> {
+        ByExtension::new().with("txt", TextFormat { uppercase: false })
+    }
+
+    fn load_listed(
+        storage: &mut AssetStorage<Text>,
+        pool: &ThreadPool,
+        loaded: Vec<(String, Handle<Text>)>,
+    ) -> Vec<String> {
+        let handles: Vec<_> = loaded.iter().map(|(_, handle)| handle).collect();
+        wait_for(storage, pool, &handles);
+        for (name, handle) in &loaded {
+            assert_eq!(name, &storage.get(handle).unwrap().0);
+        }
+
+        loaded.into_iter().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn load_dir_loads_files_directly_inside_the_dir() {
+        let (loader, pool) = listing_loader();
+        let mut storage = AssetStorage::<Text>::new();
+        let mut progress = ProgressCounter::new();
+
+        let loaded = loader
+            .load_dir("dir/", text_by_extension(), &mut progress, &storage)
+            .unwrap();
+        assert_eq!(
+            vec!["dir/a.txt", "dir/b.txt"],
+            load_listed(&mut storage, &pool, loaded)
+        );
+
+        let loaded = loader
+            .load_dir("", TextFormat { uppercase: false }, &mut progress, &storage)
+            .unwrap();
+        assert_eq!(vec!["e.txt"], load_listed(&mut storage, &pool, loaded));
+    }
+
+    #[test]
+    fn load_dir_skips_names_outside_the_dir() {
+        struct ListingSource;
+
+        impl Source for ListingSource {
+            fn modified(&self, _: &str) -> Result<u64, Error> {
+                Ok(0)
+            }
+
+            fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+                Ok(path.as_bytes().to_vec())
+            }
+
+            fn list(&self, _: &str) -> Result<Vec<String>, Error> {
+                Ok(vec!["d".into(), "dirt/a.txt".into(), "dir/a.txt".into()])
+            }
+        }
+
+        let pool = pool();
+        let loader = Loader::with_default_source(ListingSource, pool.clone());
+        let mut storage = AssetStorage::<Text>::new();
+        let mut progress = ProgressCounter::new();
+
+        let loaded = loader
+            .load_dir("dir", text_by_extension(), &mut progress, &storage)
+            .unwrap();
+        assert_eq!(vec!["dir/a.txt"], load_listed(&mut storage, &pool, loaded));
+    }
+
+    #[test]
+    fn load_glob_loads_matching_files() {
+        let (loader, pool) = listing_loader();
+        let mut storage = AssetStorage::<Text>::new();
+        let mut progress = ProgressCounter::new();
+
+        let loaded = loader
+            .load_glob("dir/**/*.txt", text_by_extension(), &mut progress, &storage)
+            .unwrap();
+        assert_eq!(
+            vec!["dir/a.txt", "dir/b.txt", "dir/sub/d.txt"],
+            load_listed(&mut storage, &pool, loaded)
+        );
+
+        let loaded = loader
+            .load_glob("*.txt", text_by_extension(), &mut progress, &storage)
+            .unwrap();
+        assert_eq!(vec!["e.txt"], load_listed(&mut storage, &pool, loaded));
+        assert_eq!(4, progress.num_finished());
     }
 }
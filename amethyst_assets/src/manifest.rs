@@ -0,0 +1,91 @@
+//! Mapping of logical asset names to the actual location of assets.
+
+use fnv::FnvHashMap;
+use serde::{Deserialize, Serialize};
+
+use amethyst_core::ecs::prelude::{Read, ReadExpect, System, VecStorage};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use crate::{Asset, AssetStorage, Handle, Loader};
+
+/// Maps logical asset names (like `"ui/main_font"`) to the location of the
+/// actual asset, so files can be swapped without changing code.
+///
+/// Set it with `Loader::set_manifest`; it is consulted by `Loader::load`.
+/// Being an asset itself, a manifest can also be loaded from a file (e.g.
+/// using `RonFormat`) and kept up to date with the `ManifestSystem`:
+///
+/// ```ron
+/// (
+///     entries: {
+///         "ui/main_font": (path: "fonts/NotoSans-Regular.ttf", format: Some("TTF")),
+///     },
+/// )
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Manifest {
+    /// If `true`, names which are not part of the manifest are not treated
+    /// as paths, but fail to load instead.
+    #[serde(default)]
+    pub strict: bool,
+    /// The entries of the manifest, keyed by logical name.
+    #[serde(default)]
+    pub entries: FnvHashMap<String, ManifestEntry>,
+}
+
+/// The location of an asset, as listed in a `Manifest`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ManifestEntry {
+    /// The id of the source to load the asset from. Defaults to the default source.
+    #[serde(default)]
+    pub source: String,
+    /// The path of the asset inside of the source.
+    pub path: String,
+    /// The name of the format the asset is expected to be loaded with, if any.
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+impl Asset for Manifest {
+    const NAME: &'static str = "amethyst_assets::Manifest";
+    type Data = Self;
+    type HandleStorage = VecStorage<Handle<Self>>;
+}
+
+/// Sets the `Loader`'s manifest to the manifest asset of the given handle,
+/// and updates it whenever the asset gets hot reloaded.
+///
+/// Updating the manifest only affects subsequent loads; assets which were
+/// already loaded keep their handles.
+pub struct ManifestSystem {
+    handle: Handle<Manifest>,
+    version: Option<u32>,
+}
+
+impl ManifestSystem {
+    /// Creates a new `ManifestSystem` for the manifest asset of the given handle.
+    pub fn new(handle: Handle<Manifest>) -> Self {
+        ManifestSystem {
+            handle,
+            version: None,
+        }
+    }
+}
+
+impl<'a> System<'a> for ManifestSystem {
+    type SystemData = (ReadExpect<'a, Loader>, Read<'a, AssetStorage<Manifest>>);
+
+    fn run(&mut self, (loader, storage): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("manifest_system");
+
+        if let Some(&(ref manifest, version)) = storage.get_with_version(&self.handle) {
+            if self.version != Some(version) {
+                loader.set_manifest(manifest.clone());
+                self.version = Some(version);
+            }
+        }
+    }
+}
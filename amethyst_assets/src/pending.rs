@@ -0,0 +1,145 @@
+//! Bookkeeping of loads which are still in flight.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use amethyst_error::Error;
+use fnv::FnvHashMap;
+use parking_lot::RwLock;
+
+use crate::{LoadPriority, Progress, Tracker};
+
+/// Information about an asset load which has not completed yet,
+/// returned by `Loader::pending_loads`.
+#[derive(Clone, Debug)]
+pub struct PendingLoad {
+    /// The name of the asset.
+    pub name: String,
+    /// The name of the asset type, see `Asset::NAME`.
+    pub asset_type_name: &'static str,
+    /// The id of the source the asset is loaded from.
+    pub source: String,
+    /// The time the load has been issued at.
+    pub enqueued: Instant,
+    /// The priority of the load.
+    pub priority: LoadPriority,
+}
+
+/// Registry of all loads which are in flight.
+#[derive(Clone, Default)]
+pub(crate) struct PendingLoads {
+    loads: Arc<RwLock<FnvHashMap<usize, PendingLoad>>>,
+    next_id: Arc<AtomicUsize>,
+}
+
+impl PendingLoads {
+    /// Registers a load, returning a `Progress` which removes it from
+    /// the registry again once the load completed.
+    pub(crate) fn register<P>(&self, load: PendingLoad, progress: P) -> PendingProgress<P>
+    where
+        P: Progress,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.loads.write().insert(id, load);
+
+        PendingProgress {
+            id,
+            inner: progress,
+            loads: self.clone(),
+        }
+    }
+
+    /// Returns a snapshot of all loads which are in flight.
+    pub(crate) fn snapshot(&self) -> Vec<PendingLoad> {
+        self.loads.read().values().cloned().collect()
+    }
+
+    fn remove(&self, id: usize) {
+        self.loads.write().remove(&id);
+    }
+}
+
+/// Progress wrapper created by `PendingLoads::register`.
+pub(crate) struct PendingProgress<P> {
+    id: usize,
+    inner: P,
+    loads: PendingLoads,
+}
+
+impl<P> Progress for PendingProgress<P>
+where
+    P: Progress,
+{
+    type Tracker = PendingTracker<P::Tracker>;
+
+    fn add_assets(&mut self, num: usize) {
+        self.inner.add_assets(num);
+    }
+
+    fn create_tracker(self) -> Self::Tracker {
+        PendingTracker {
+            id: self.id,
+            inner: Some(self.inner.create_tracker()),
+            loads: self.loads,
+        }
+    }
+}
+
+/// Tracker removing its load from the `PendingLoads` once done.
+pub(crate) struct PendingTracker<T> {
+    id: usize,
+    // Only `None` while forwarding to the inner tracker.
+    inner: Option<T>,
+    loads: PendingLoads,
+}
+
+impl<T> PendingTracker<T> {
+    fn take(&mut self) -> Box<T> {
+        Box::new(
+            self.inner
+                .take()
+                .expect("Tracker has already been notified"),
+        )
+    }
+}
+
+impl<T> Tracker for PendingTracker<T>
+where
+    T: Tracker,
+{
+    fn success(mut self: Box<Self>) {
+        self.take().success();
+    }
+
+    fn fail(
+        mut self: Box<Self>,
+        handle_id: u32,
+        asset_type_name: &'static str,
+        asset_name: String,
+        error: Error,
+    ) {
+        self.take()
+            .fail(handle_id, asset_type_name, asset_name, error);
+    }
+
+    fn cancel(
+        mut self: Box<Self>,
+        handle_id: u32,
+        asset_type_name: &'static str,
+        asset_name: String,
+    ) {
+        self.take().cancel(handle_id, asset_type_name, asset_name);
+    }
+}
+
+impl<T> Drop for PendingTracker<T> {
+    fn drop(&mut self) {
+        // Also covers trackers which get dropped without being notified.
+        self.loads.remove(self.id);
+    }
+}
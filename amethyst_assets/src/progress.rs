@@ -1,12 +1,20 @@
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc,
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, Waker},
+    time::{Duration, SystemTime},
 };
 
-use amethyst_error::Error;
+use amethyst_error::{format_err, Error};
 use log::error;
 use parking_lot::Mutex;
 
+use crate::error::ErrorLocation;
+
 /// Completion status, returned by `ProgressCounter::complete`.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Completion {
@@ -43,10 +51,14 @@ impl Progress for () {
 
 /// A progress tracker which is passed to the `Loader`
 /// in order to check how many assets are loaded.
-#[derive(Default)]
+///
+/// Clones share their state, so a clone can be passed to each of many loads
+/// while the original reports the aggregate status of all of them.
+#[derive(Clone, Default)]
 pub struct ProgressCounter {
     errors: Arc<Mutex<Vec<AssetErrorMeta>>>,
-    num_assets: usize,
+    num_assets: Arc<AtomicUsize>,
+    num_cancelled: Arc<AtomicUsize>,
     num_failed: Arc<AtomicUsize>,
     num_loading: Arc<AtomicUsize>,
 }
@@ -65,7 +77,12 @@ impl ProgressCounter {
 
     /// Returns the number of assets this struct is tracking.
     pub fn num_assets(&self) -> usize {
-        self.num_assets
+        self.num_assets.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of assets whose loading has been cancelled.
+    pub fn num_cancelled(&self) -> usize {
+        self.num_cancelled.load(Ordering::Relaxed)
     }
 
     /// Returns the number of assets that have failed.
@@ -80,10 +97,12 @@ impl ProgressCounter {
 
     /// Returns the number of assets that have successfully loaded.
     pub fn num_finished(&self) -> usize {
-        self.num_assets - self.num_loading() - self.num_failed()
+        self.num_assets()
+            .saturating_sub(self.num_loading() + self.num_failed() + self.num_cancelled())
     }
 
     /// Returns `Completion::Complete` if all tracked assets are finished.
+    /// Cancelled assets count as finished.
     pub fn complete(&self) -> Completion {
         match (
             self.num_failed.load(Ordering::Relaxed),
@@ -105,17 +124,31 @@ impl<'a> Progress for &'a mut ProgressCounter {
     type Tracker = ProgressCounterTracker;
 
     fn add_assets(&mut self, num: usize) {
-        self.num_assets += num;
+        (**self).add_assets(num);
+    }
+
+    fn create_tracker(self) -> Self::Tracker {
+        self.clone().create_tracker()
+    }
+}
+
+impl Progress for ProgressCounter {
+    type Tracker = ProgressCounterTracker;
+
+    fn add_assets(&mut self, num: usize) {
+        self.num_assets.fetch_add(num, Ordering::Relaxed);
     }
 
     fn create_tracker(self) -> Self::Tracker {
         let errors = self.errors.clone();
+        let num_cancelled = self.num_cancelled.clone();
         let num_failed = self.num_failed.clone();
         let num_loading = self.num_loading.clone();
         num_loading.fetch_add(1, Ordering::Relaxed);
 
         ProgressCounterTracker {
             errors,
+            num_cancelled,
             num_failed,
             num_loading,
         }
@@ -126,6 +159,7 @@ impl<'a> Progress for &'a mut ProgressCounter {
 #[derive(Default)]
 pub struct ProgressCounterTracker {
     errors: Arc<Mutex<Vec<AssetErrorMeta>>>,
+    num_cancelled: Arc<AtomicUsize>,
     num_failed: Arc<AtomicUsize>,
     num_loading: Arc<AtomicUsize>,
 }
@@ -155,6 +189,40 @@ impl Tracker for ProgressCounterTracker {
         // the assets that are still loading.
         self.num_loading.fetch_sub(1, Ordering::Relaxed);
     }
+
+    fn cancel(self: Box<Self>, _: u32, _: &'static str, _: String) {
+        self.num_cancelled.fetch_add(1, Ordering::Relaxed);
+        self.num_loading.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Record of a finished asset load, passed to the sink installed with
+/// `AssetStorage::set_progress_sink`.
+#[derive(Clone, Debug)]
+pub struct AssetProgressEvent {
+    /// The id of the asset's handle.
+    pub handle_id: u32,
+    /// The name of the asset type, `Asset::NAME`.
+    pub asset_type_name: &'static str,
+    /// The name the asset was loaded with.
+    pub asset_name: String,
+    /// The time between requesting the load and the asset being processed.
+    pub duration: Duration,
+    /// How the load ended.
+    pub outcome: AssetProgressOutcome,
+    /// When the load ended.
+    pub timestamp: SystemTime,
+}
+
+/// How an asset load ended, see `AssetProgressEvent`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AssetProgressOutcome {
+    /// The asset has been loaded successfully.
+    Loaded,
+    /// Loading failed, with the error message.
+    Failed(String),
+    /// The load has been cancelled.
+    Cancelled,
 }
 
 #[derive(Debug)]
@@ -165,6 +233,209 @@ pub struct AssetErrorMeta {
     pub asset_name: String,
 }
 
+impl AssetErrorMeta {
+    /// Returns where in the data of the asset decoding failed, if the format told.
+    pub fn location(&self) -> Option<ErrorLocation> {
+        ErrorLocation::find(&self.error)
+    }
+}
+
+/// Shared state between a `CompletionTracker` and its `CompletionFuture`.
+#[derive(Default)]
+struct CompletionState {
+    result: Option<Result<(), Error>>,
+    waker: Option<Waker>,
+}
+
+impl CompletionState {
+    fn complete(&mut self, result: Result<(), Error>) {
+        if self.result.is_none() {
+            self.result = Some(result);
+        }
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Creates a `Progress` tracking exactly one asset, together with a future
+/// which resolves once that asset has been processed.
+pub(crate) fn completion() -> (CompletionProgress, CompletionFuture) {
+    let state = Arc::new(Mutex::new(CompletionState::default()));
+
+    (
+        CompletionProgress {
+            state: state.clone(),
+        },
+        CompletionFuture { state },
+    )
+}
+
+/// Progress for a single asset load, see `completion`.
+pub(crate) struct CompletionProgress {
+    state: Arc<Mutex<CompletionState>>,
+}
+
+impl Progress for CompletionProgress {
+    type Tracker = CompletionTracker;
+
+    fn add_assets(&mut self, _: usize) {}
+
+    fn create_tracker(self) -> Self::Tracker {
+        CompletionTracker {
+            state: self.state,
+            done: false,
+        }
+    }
+}
+
+/// Tracker which wakes up the task awaiting the corresponding `CompletionFuture`.
+pub(crate) struct CompletionTracker {
+    state: Arc<Mutex<CompletionState>>,
+    done: bool,
+}
+
+impl Tracker for CompletionTracker {
+    fn success(mut self: Box<Self>) {
+        self.done = true;
+        self.state.lock().complete(Ok(()));
+    }
+
+    fn fail(
+        mut self: Box<Self>,
+        handle_id: u32,
+        asset_type_name: &'static str,
+        asset_name: String,
+        error: Error,
+    ) {
+        show_error(handle_id, asset_type_name, &asset_name, &error);
+        self.done = true;
+        self.state.lock().complete(Err(error));
+    }
+
+    fn cancel(mut self: Box<Self>, _: u32, _: &'static str, _: String) {
+        self.done = true;
+        self.state
+            .lock()
+            .complete(Err(Error::from(crate::error::Error::Cancelled)));
+    }
+}
+
+impl Drop for CompletionTracker {
+    fn drop(&mut self) {
+        // The tracker can get dropped without being notified, e.g. if the storage
+        // goes away while the asset is still queued. Don't leave the future hanging.
+        if !self.done {
+            self.state.lock().complete(Err(format_err!(
+                "Asset load was abandoned before completion"
+            )));
+        }
+    }
+}
+
+/// Future resolving once the asset tracked by the corresponding `CompletionTracker`
+/// succeeded or failed.
+pub(crate) struct CompletionFuture {
+    state: Arc<Mutex<CompletionState>>,
+}
+
+impl Future for CompletionFuture {
+    type Output = Result<(), Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// State of a load which may be shared by multiple callers, see `Loader::load_from`.
+pub(crate) enum SharedLoadState {
+    /// Still loading, holding the trackers of everyone waiting for the asset.
+    Loading(Vec<Box<dyn Tracker>>),
+    /// The asset has been loaded successfully.
+    Loaded,
+    /// The asset failed to load or its loading was cancelled.
+    Failed,
+}
+
+/// Progress for a load which may be shared by multiple callers.
+pub(crate) struct SharedProgress {
+    state: Arc<Mutex<SharedLoadState>>,
+}
+
+impl SharedProgress {
+    pub(crate) fn new(state: Arc<Mutex<SharedLoadState>>) -> Self {
+        SharedProgress { state }
+    }
+}
+
+impl Progress for SharedProgress {
+    type Tracker = SharedTracker;
+
+    fn add_assets(&mut self, _: usize) {}
+
+    fn create_tracker(self) -> Self::Tracker {
+        SharedTracker { state: self.state }
+    }
+}
+
+/// Tracker which forwards to the trackers of all callers sharing a load.
+pub(crate) struct SharedTracker {
+    state: Arc<Mutex<SharedLoadState>>,
+}
+
+impl SharedTracker {
+    fn finish(&self, state: SharedLoadState) -> Vec<Box<dyn Tracker>> {
+        match std::mem::replace(&mut *self.state.lock(), state) {
+            SharedLoadState::Loading(trackers) => trackers,
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Tracker for SharedTracker {
+    fn success(self: Box<Self>) {
+        for tracker in self.finish(SharedLoadState::Loaded) {
+            tracker.success();
+        }
+    }
+
+    fn fail(
+        self: Box<Self>,
+        handle_id: u32,
+        asset_type_name: &'static str,
+        asset_name: String,
+        error: Error,
+    ) {
+        let mut trackers = self.finish(SharedLoadState::Failed).into_iter();
+        let first = trackers.next();
+        // `Error` can't be cloned, so only the first tracker gets the original error.
+        for tracker in trackers {
+            tracker.fail(
+                handle_id,
+                asset_type_name,
+                asset_name.clone(),
+                format_err!("{}", error),
+            );
+        }
+        if let Some(tracker) = first {
+            tracker.fail(handle_id, asset_type_name, asset_name, error);
+        }
+    }
+
+    fn cancel(self: Box<Self>, handle_id: u32, asset_type_name: &'static str, asset_name: String) {
+        for tracker in self.finish(SharedLoadState::Failed) {
+            tracker.cancel(handle_id, asset_type_name, asset_name.clone());
+        }
+    }
+}
+
 /// The `Tracker` trait which will be used by the loader to report
 /// back to `Progress`.
 pub trait Tracker: Send + 'static {
@@ -179,6 +450,15 @@ pub trait Tracker: Send + 'static {
         asset_name: String,
         error: Error,
     );
+    /// Called if the asset load has been cancelled using a `LoadToken`.
+    /// Does nothing by default.
+    fn cancel(
+        self: Box<Self>,
+        _handle_id: u32,
+        _asset_type_name: &'static str,
+        _asset_name: String,
+    ) {
+    }
 }
 
 impl Tracker for () {
@@ -209,8 +489,15 @@ fn show_error(handle_id: u32, asset_type_name: &'static str, asset_name: &str, e
 #[cfg(test)]
 mod tests {
     use amethyst_error::Error;
+    use futures::executor::block_on;
+
+    use std::sync::Arc;
 
-    use super::{Completion, Progress, ProgressCounter, Tracker};
+    use parking_lot::Mutex;
+
+    use super::{
+        completion, Completion, Progress, ProgressCounter, SharedLoadState, SharedProgress, Tracker,
+    };
 
     #[test]
     fn progress_counter_complete_returns_correct_completion_status_when_loading_or_complete() {
@@ -235,6 +522,29 @@ mod tests {
         assert!(progress.is_complete());
     }
 
+    #[test]
+    fn progress_counter_clones_share_status() {
+        let progress_counter = ProgressCounter::new();
+        let mut trackers = Vec::new();
+        for _ in 0..3 {
+            let mut progress = progress_counter.clone();
+            progress.add_assets(1);
+            trackers.push(Box::new(progress.create_tracker()));
+        }
+        assert_eq!(3, progress_counter.num_assets());
+        assert_eq!(3, progress_counter.num_loading());
+
+        trackers.pop().unwrap().success();
+        trackers
+            .pop()
+            .unwrap()
+            .fail(0, "", String::new(), Error::from_string(""));
+        assert_eq!(1, progress_counter.num_finished());
+        assert_eq!(1, progress_counter.num_failed());
+        assert_eq!(1, progress_counter.num_loading());
+        assert_eq!(Completion::Failed, progress_counter.complete());
+    }
+
     #[test]
     fn progress_counter_complete_returns_failed_when_any_assets_failed() {
         let mut progress_counter = ProgressCounter::new();
@@ -288,4 +598,66 @@ mod tests {
         tracker_2.success();
         assert_eq!(2, progress.num_finished());
     }
+
+    #[test]
+    fn completion_future_resolves_on_success_and_failure() {
+        let (progress, future) = completion();
+        Box::new(progress.create_tracker()).success();
+        assert!(block_on(future).is_ok());
+
+        let (progress, future) = completion();
+        Box::new(progress.create_tracker()).fail(
+            1,
+            "AssetType",
+            String::from("test.asset"),
+            Error::from_string(""),
+        );
+        assert!(block_on(future).is_err());
+    }
+
+    #[test]
+    fn completion_future_fails_when_tracker_is_dropped() {
+        let (progress, future) = completion();
+        drop(progress.create_tracker());
+        assert!(block_on(future).is_err());
+    }
+
+    #[test]
+    fn progress_counter_counts_cancelled_assets_as_done() {
+        let mut progress_counter = ProgressCounter::new();
+        let mut progress = &mut progress_counter;
+        progress.add_assets(2);
+        let tracker_0 = Box::new(progress.create_tracker());
+        let tracker_1 = Box::new(progress.create_tracker());
+
+        // 1 cancelled, 1 loading
+        tracker_0.cancel(1, "AssetType", String::from("test.asset"));
+        assert_eq!(Completion::Loading, progress.complete());
+        assert_eq!(1, progress.num_cancelled());
+
+        // 1 cancelled, 1 success
+        tracker_1.success();
+        assert_eq!(Completion::Complete, progress.complete());
+        assert_eq!(1, progress.num_finished());
+    }
+
+    #[test]
+    fn shared_tracker_notifies_all_attached_trackers() {
+        let mut progress_counter_0 = ProgressCounter::new();
+        let mut progress_counter_1 = ProgressCounter::new();
+        let mut progress_0 = &mut progress_counter_0;
+        let mut progress_1 = &mut progress_counter_1;
+        progress_0.add_assets(1);
+        progress_1.add_assets(1);
+        let trackers: Vec<Box<dyn Tracker>> = vec![
+            Box::new(progress_0.create_tracker()),
+            Box::new(progress_1.create_tracker()),
+        ];
+        let state = Arc::new(Mutex::new(SharedLoadState::Loading(trackers)));
+        let shared_tracker = Box::new(SharedProgress::new(state).create_tracker());
+
+        shared_tracker.success();
+        assert!(progress_counter_0.is_complete());
+        assert!(progress_counter_1.is_complete());
+    }
 }
@@ -1,35 +1,91 @@
 //! Defines the `Reload` trait.
 
-use std::{sync::Arc, time::Instant};
+use std::{
+    hash::Hasher,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use amethyst_core::{
     ecs::prelude::{DispatcherBuilder, Read, Resources, System, Write},
     SystemBundle, Time,
 };
-use amethyst_error::Error;
+use amethyst_error::{format_err, Error, ResultExt};
+use fnv::{FnvHashSet, FnvHasher};
+use log::{debug, info, warn};
 
 #[cfg(feature = "profiler")]
 use thread_profiler::profile_scope;
 
-use crate::{Format, FormatValue, Loader, Source};
+#[cfg(feature = "file_watcher")]
+use crate::FileWatcher;
+use crate::{io::Prefetched, Asset, Format, FormatValue, Loader, Source, WeakHandle};
+
+type AddSystem = Box<dyn FnOnce(&mut DispatcherBuilder<'_, '_>)>;
 
 /// This bundle activates hot reload for the `Loader`,
 /// adds a `HotReloadStrategy` and the `HotReloadSystem`.
 #[derive(Default)]
 pub struct HotReloadBundle {
+    overrides: Vec<AddSystem>,
     strategy: HotReloadStrategy,
+    #[cfg(feature = "file_watcher")]
+    watcher: Option<FileWatcher>,
 }
 
 impl HotReloadBundle {
     /// Creates a new bundle.
     pub fn new(strategy: HotReloadStrategy) -> Self {
-        HotReloadBundle { strategy }
+        HotReloadBundle {
+            overrides: Vec::new(),
+            strategy,
+            #[cfg(feature = "file_watcher")]
+            watcher: None,
+        }
+    }
+
+    /// Hot reloads assets of type `A` using `strategy` instead of the global one, adding
+    /// a `HotReloadStrategyFor<A>` and the `HotReloadForSystem<A>` updating it.
+    pub fn with_strategy_for<A: Asset>(mut self, strategy: HotReloadStrategy) -> Self {
+        self.overrides.push(Box::new(
+            move |dispatcher: &mut DispatcherBuilder<'_, '_>| {
+                dispatcher.add(
+                    HotReloadForSystem::<A>::new(strategy),
+                    &format!("hot_reload_{}", A::NAME),
+                    &[],
+                );
+            },
+        ));
+        self
+    }
+
+    /// Uses `watcher` to find the assets which need a reload, see
+    /// `HotReloadStrategy::when_files_change`. The directories of the `Directory` sources
+    /// the `Loader` has once the system is set up are watched automatically.
+    #[cfg(feature = "file_watcher")]
+    pub fn with_watcher(mut self, watcher: FileWatcher) -> Self {
+        self.watcher = Some(watcher);
+        self
     }
 }
 
 impl<'a, 'b> SystemBundle<'a, 'b> for HotReloadBundle {
     fn build(self, dispatcher: &mut DispatcherBuilder<'a, 'b>) -> Result<(), Error> {
-        dispatcher.add(HotReloadSystem::new(self.strategy), "hot_reload", &[]);
+        #[cfg_attr(not(feature = "file_watcher"), allow(unused_mut))]
+        let mut system = HotReloadSystem::new(self.strategy);
+        #[cfg(feature = "file_watcher")]
+        {
+            system.watcher = self.watcher;
+        }
+        dispatcher.add(system, "hot_reload", &[]);
+        for add in self.overrides {
+            add(dispatcher);
+        }
         Ok(())
     }
 }
@@ -50,7 +106,9 @@ impl<'a, 'b> SystemBundle<'a, 'b> for HotReloadBundle {
 /// ```
 #[derive(Clone)]
 pub struct HotReloadStrategy {
+    compare_contents: bool,
     inner: HotReloadStrategyInner,
+    quiet_period: Duration,
 }
 
 impl HotReloadStrategy {
@@ -59,11 +117,13 @@ impl HotReloadStrategy {
         use std::u64::MAX;
 
         HotReloadStrategy {
+            compare_contents: false,
             inner: HotReloadStrategyInner::Every {
                 interval: n,
                 last: Instant::now(),
                 frame_number: MAX,
             },
+            quiet_period: Duration::from_secs(0),
         }
     }
 
@@ -72,17 +132,41 @@ impl HotReloadStrategy {
         use std::u64::MAX;
 
         HotReloadStrategy {
+            compare_contents: false,
             inner: HotReloadStrategyInner::Trigger {
                 triggered: false,
                 frame_number: MAX,
             },
+            quiet_period: Duration::from_secs(0),
+        }
+    }
+
+    /// Reloads assets once the `FileWatcher` passed to `HotReloadBundle::with_watcher`
+    /// reports that their files changed, instead of polling the modification times of
+    /// all reloadable assets. Without a watcher, nothing is ever reloaded.
+    ///
+    /// Unlike with `every`, only the assets whose files changed are looked at, and
+    /// changes within the same second are not missed.
+    #[cfg(feature = "file_watcher")]
+    pub fn when_files_change() -> Self {
+        use std::u64::MAX;
+
+        HotReloadStrategy {
+            compare_contents: false,
+            inner: HotReloadStrategyInner::Watch {
+                changed: FnvHashSet::default(),
+                frame_number: MAX,
+            },
+            quiet_period: Duration::from_secs(0),
         }
     }
 
     /// Never do any hot-reloading.
     pub fn never() -> Self {
         HotReloadStrategy {
+            compare_contents: false,
             inner: HotReloadStrategyInner::Never,
+            quiet_period: Duration::from_secs(0),
         }
     }
 
@@ -97,6 +181,48 @@ impl HotReloadStrategy {
         }
     }
 
+    /// If `compare` is `true`, the contents of changed assets are compared to the ones
+    /// they have been loaded from before reloading them, skipping the reload if they
+    /// are the same (e.g. because a file was only touched, or saved without changes).
+    ///
+    /// This requires hashing the contents of assets when they are loaded and reading
+    /// changed assets one more time, which is why it is disabled by default. The
+    /// `HotReloadBundle` enables hashing with `Loader::set_content_comparison`; assets
+    /// loaded before that are always reloaded once they changed.
+    pub fn with_content_comparison(mut self, compare: bool) -> Self {
+        self.compare_contents = compare;
+        self
+    }
+
+    /// Waits until the files of a changed asset stopped changing for `quiet` before
+    /// reloading it, so an asset which is written several times in a row (e.g. by an
+    /// exporter) is only reloaded once, with its final contents. A changed asset is
+    /// reloaded `quiet` after its last change was noticed at the earliest. Until then,
+    /// its modification time is checked again with every frame, without polling the
+    /// other assets.
+    ///
+    /// This only applies to polling strategies like `every`. Changes reported by a
+    /// `FileWatcher` are debounced by the watcher instead, see `FileWatcher::with_delay`.
+    pub fn with_quiet_period(mut self, quiet: Duration) -> Self {
+        self.quiet_period = quiet;
+        self
+    }
+
+    /// Returns for how long the files of changed assets must be left alone before they
+    /// are reloaded, see `with_quiet_period`.
+    pub(crate) fn quiet_period(&self) -> Option<Duration> {
+        match self.quiet_period {
+            quiet if quiet == Duration::from_secs(0) => None,
+            quiet => Some(quiet),
+        }
+    }
+
+    /// Returns `true` if unchanged contents should not cause a reload,
+    /// see `with_content_comparison`.
+    pub(crate) fn compares_contents(&self) -> bool {
+        self.compare_contents
+    }
+
     /// Crate-internal method to check if reload is necessary.
     /// `reload_counter` is a per-storage value which is only used
     /// for and by this method.
@@ -104,9 +230,53 @@ impl HotReloadStrategy {
         match self.inner {
             HotReloadStrategyInner::Every { frame_number, .. } => frame_number == current_frame,
             HotReloadStrategyInner::Trigger { frame_number, .. } => frame_number == current_frame,
+            #[cfg(feature = "file_watcher")]
+            HotReloadStrategyInner::Watch { frame_number, .. } => frame_number == current_frame,
             HotReloadStrategyInner::Never => false,
         }
     }
+
+    /// Schedules the reloads of strategies which are due after some time or when
+    /// triggered, called once per frame by the hot reload systems.
+    fn advance(&mut self, current_frame: u64) {
+        match self.inner {
+            HotReloadStrategyInner::Trigger {
+                ref mut triggered,
+                ref mut frame_number,
+            } => {
+                if *triggered {
+                    *frame_number = current_frame + 1;
+                }
+                *triggered = false;
+            }
+            HotReloadStrategyInner::Every {
+                interval,
+                ref mut last,
+                ref mut frame_number,
+            } => {
+                if last.elapsed().as_secs() > u64::from(interval) {
+                    *frame_number = current_frame + 1;
+                    *last = Instant::now();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the files a `FileWatcher` reported as changed if reloads are due in
+    /// `current_frame` because of them, or `None` if reloads are found by polling
+    /// `Reload::needs_reload`.
+    #[cfg_attr(not(feature = "file_watcher"), allow(unused_variables))]
+    pub(crate) fn changed_files(&self, current_frame: u64) -> Option<&FnvHashSet<String>> {
+        match self.inner {
+            #[cfg(feature = "file_watcher")]
+            HotReloadStrategyInner::Watch {
+                ref changed,
+                frame_number,
+            } if frame_number == current_frame => Some(changed),
+            _ => None,
+        }
+    }
 }
 
 impl Default for HotReloadStrategy {
@@ -126,12 +296,19 @@ enum HotReloadStrategyInner {
         triggered: bool,
         frame_number: u64,
     },
+    #[cfg(feature = "file_watcher")]
+    Watch {
+        changed: FnvHashSet<String>,
+        frame_number: u64,
+    },
     Never,
 }
 
 /// System for updating `HotReloadStrategy`.
 pub struct HotReloadSystem {
     initial_strategy: HotReloadStrategy,
+    #[cfg(feature = "file_watcher")]
+    watcher: Option<FileWatcher>,
 }
 
 impl HotReloadSystem {
@@ -139,8 +316,19 @@ impl HotReloadSystem {
     pub fn new(strategy: HotReloadStrategy) -> Self {
         HotReloadSystem {
             initial_strategy: strategy,
+            #[cfg(feature = "file_watcher")]
+            watcher: None,
         }
     }
+
+    /// Uses `watcher` to find the assets which need a reload, see
+    /// `HotReloadStrategy::when_files_change`. The directories of the `Directory` sources
+    /// the `Loader` has once the system is set up are watched automatically.
+    #[cfg(feature = "file_watcher")]
+    pub fn with_watcher(mut self, watcher: FileWatcher) -> Self {
+        self.watcher = Some(watcher);
+        self
+    }
 }
 
 impl<'a> System<'a> for HotReloadSystem {
@@ -150,48 +338,212 @@ impl<'a> System<'a> for HotReloadSystem {
         #[cfg(feature = "profiler")]
         profile_scope!("hot_reload_system");
 
-        match strategy.inner {
-            HotReloadStrategyInner::Trigger {
-                ref mut triggered,
+        strategy.advance(time.frame_number());
+        #[cfg(feature = "file_watcher")]
+        {
+            if let HotReloadStrategyInner::Watch {
+                ref mut changed,
                 ref mut frame_number,
-            } => {
-                if *triggered {
-                    *frame_number = time.frame_number() + 1;
+            } = strategy.inner
+            {
+                // Changes reported before may still be due in this frame.
+                let due = *frame_number != std::u64::MAX && *frame_number >= time.frame_number();
+                match self.watcher {
+                    Some(ref mut watcher) if !due => {
+                        let files = watcher.changed_files();
+                        if !files.is_empty() {
+                            *changed = files;
+                            *frame_number = time.frame_number() + 1;
+                        }
+                    }
+                    _ => {}
                 }
-                *triggered = false;
             }
-            HotReloadStrategyInner::Every {
-                interval,
-                ref mut last,
-                ref mut frame_number,
-            } => {
-                if last.elapsed().as_secs() > u64::from(interval) {
-                    *frame_number = time.frame_number() + 1;
-                    *last = Instant::now();
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        use amethyst_core::ecs::prelude::SystemData;
+        Self::SystemData::setup(res);
+        res.insert(self.initial_strategy.clone());
+        let mut loader = res.fetch_mut::<Loader>();
+        loader.set_hot_reload(true);
+        if self.initial_strategy.compares_contents() {
+            loader.set_content_comparison(true);
+        }
+        #[cfg(feature = "file_watcher")]
+        {
+            if let Some(ref mut watcher) = self.watcher {
+                if let Err(e) = watcher.watch_loader(&loader) {
+                    warn!("Failed to watch the asset directories for changes: {}", e);
                 }
             }
-            HotReloadStrategyInner::Never => {}
         }
     }
+}
+
+/// An ECS resource holding the `HotReloadStrategy` for assets of type `A`, which the
+/// processors of `A` use instead of the global `HotReloadStrategy` if it is present.
+///
+/// This allows e.g. to reload prefabs every second while textures are only reloaded on
+/// demand. Add it using `HotReloadBundle::with_strategy_for`. Strategies created with
+/// `when_files_change` only get notified of changed files as the global strategy.
+pub struct HotReloadStrategyFor<A> {
+    strategy: HotReloadStrategy,
+    marker: PhantomData<A>,
+}
+
+impl<A> HotReloadStrategyFor<A> {
+    /// Creates a strategy for assets of type `A`.
+    pub fn new(strategy: HotReloadStrategy) -> Self {
+        HotReloadStrategyFor {
+            strategy,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the strategy assets of type `A` are reloaded with: `overridden` if it is
+    /// present, `global` otherwise.
+    pub fn select<'s>(
+        overridden: Option<&'s HotReloadStrategyFor<A>>,
+        global: Option<&'s HotReloadStrategy>,
+    ) -> Option<&'s HotReloadStrategy> {
+        overridden.map(|o| &o.strategy).or(global)
+    }
+}
+
+impl<A> Default for HotReloadStrategyFor<A> {
+    fn default() -> Self {
+        HotReloadStrategyFor::new(HotReloadStrategy::default())
+    }
+}
+
+impl<A> Deref for HotReloadStrategyFor<A> {
+    type Target = HotReloadStrategy;
+
+    fn deref(&self) -> &HotReloadStrategy {
+        &self.strategy
+    }
+}
+
+impl<A> DerefMut for HotReloadStrategyFor<A> {
+    fn deref_mut(&mut self) -> &mut HotReloadStrategy {
+        &mut self.strategy
+    }
+}
+
+/// System for updating the `HotReloadStrategyFor<A>`, like `HotReloadSystem` does for
+/// the global strategy.
+pub struct HotReloadForSystem<A> {
+    initial_strategy: HotReloadStrategy,
+    marker: PhantomData<A>,
+}
+
+impl<A> HotReloadForSystem<A> {
+    /// Creates a system which adds and updates a `HotReloadStrategyFor<A>`.
+    pub fn new(strategy: HotReloadStrategy) -> Self {
+        HotReloadForSystem {
+            initial_strategy: strategy,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, A: Asset> System<'a> for HotReloadForSystem<A> {
+    type SystemData = (Read<'a, Time>, Write<'a, HotReloadStrategyFor<A>>);
+
+    fn run(&mut self, (time, mut strategy): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("hot_reload_for_system");
+
+        strategy.advance(time.frame_number());
+    }
 
     fn setup(&mut self, res: &mut Resources) {
         use amethyst_core::ecs::prelude::SystemData;
         Self::SystemData::setup(res);
-        res.insert(self.initial_strategy.clone());
-        res.fetch_mut::<Loader>().set_hot_reload(true);
+        res.insert(HotReloadStrategyFor::<A>::new(
+            self.initial_strategy.clone(),
+        ));
+        let mut loader = res.fetch_mut::<Loader>();
+        loader.set_hot_reload(true);
+        if self.initial_strategy.compares_contents() {
+            loader.set_content_comparison(true);
+        }
     }
 }
 
+/// Published to the channel of `AssetStorage::reload_events_mut` whenever a hot reload
+/// of an asset is done, successfully or not.
+pub struct HotReloadEvent<A> {
+    /// The handle of the reloaded asset.
+    pub handle: WeakHandle<A>,
+    /// The name of the reloaded asset.
+    pub name: String,
+    /// The version of the asset after the reload, which only changed if it was replaced.
+    pub new_version: u32,
+    /// How the reload ended.
+    pub outcome: ReloadOutcome,
+}
+
+/// How a hot reload ended, see `HotReloadEvent`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReloadOutcome {
+    /// The asset has been replaced by the reloaded one.
+    Reloaded,
+    /// The asset didn't change and has been kept, see
+    /// `HotReloadStrategy::with_content_comparison` and
+    /// `AssetStorage::set_skip_identical_reloads`.
+    Unchanged,
+    /// The reload failed, with the error message. The old asset has been kept.
+    Failed(String),
+}
+
 /// The `Reload` trait provides a method which checks if an asset needs to be reloaded.
 pub trait Reload<D>: ReloadClone<D> + Send + Sync + 'static {
     /// Checks if a reload is necessary.
     fn needs_reload(&self) -> bool;
     /// Returns the asset name.
     fn name(&self) -> String;
+    /// Returns the names of the files the asset is read from, which are matched against
+    /// the files reported by a `FileWatcher`. Defaults to just `name`.
+    fn files(&self) -> Vec<String> {
+        vec![self.name()]
+    }
+    /// Returns the latest modification time of the files of the asset, which is used to
+    /// wait for them to settle, see `HotReloadStrategy::with_quiet_period`. Defaults to
+    /// `None`, which reloads the asset as soon as `needs_reload` returns `true`.
+    fn last_modified(&self) -> Option<u64> {
+        None
+    }
     /// Returns the format name.
     fn format(&self) -> &'static str;
+    /// Called by the `Loader` with the `content_hash` of the file the asset has been
+    /// loaded from if it compares contents, see `Loader::set_content_comparison`.
+    /// Defaults to ignoring it.
+    fn record_contents(&mut self, _hash: u64) {}
     /// Reloads the asset.
     fn reload(self: Box<Self>) -> Result<FormatValue<D>, Error>;
+    /// Reloads the asset unless its contents are the same as the ones it has been
+    /// loaded from, in which case a reload object to keep watching the asset with
+    /// is returned instead. Defaults to always reloading.
+    fn reload_if_changed(self: Box<Self>) -> Result<Reloaded<D>, Error> {
+        self.reload().map(Reloaded::Changed)
+    }
+    /// Returns `false` if the asset can't ever be reloaded anymore, e.g. because
+    /// its source has been removed from the `Loader`. The reload object is dropped then.
+    /// Defaults to `true`.
+    fn is_available(&self) -> bool {
+        true
+    }
+}
+
+/// The result of `Reload::reload_if_changed`.
+pub enum Reloaded<D> {
+    /// The contents changed and the asset has been reloaded.
+    Changed(FormatValue<D>),
+    /// The contents didn't change, the asset should be kept as it is.
+    Unchanged(Box<dyn Reload<D>>),
 }
 
 pub trait ReloadClone<D> {
@@ -215,8 +567,13 @@ impl<D: 'static> Clone for Box<dyn Reload<D>> {
 
 /// An implementation of `Reload` which just stores the modification time
 /// and the path of the file.
+///
+/// If the file disappears, a warning is logged once and the asset is kept as it is.
+/// Once the file is back, it is reloaded.
 pub struct SingleFile<D> {
     format: Box<dyn Format<D>>,
+    hash: Option<u64>,
+    missing: AtomicBool,
     modified: u64,
     path: String,
     source: Arc<dyn Source>,
@@ -232,17 +589,35 @@ impl<D: 'static> SingleFile<D> {
     ) -> Self {
         SingleFile {
             format,
+            hash: None,
+            missing: AtomicBool::new(false),
             modified,
             path,
             source,
         }
     }
+
+    /// Records the contents the asset has been loaded from, allowing
+    /// `reload_if_changed` to skip reloads if they didn't change.
+    pub fn with_contents(mut self, bytes: &[u8]) -> Self {
+        self.hash = Some(content_hash(bytes));
+        self
+    }
+}
+
+/// Hashes the contents of a file, to tell whether they changed since it has been loaded.
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(bytes);
+    hasher.finish()
 }
 
 impl<D: 'static> Clone for SingleFile<D> {
     fn clone(&self) -> Self {
         SingleFile {
             format: self.format.clone(),
+            hash: self.hash,
+            missing: AtomicBool::new(self.missing.load(Ordering::Relaxed)),
             modified: self.modified,
             path: self.path.clone(),
             source: self.source.clone(),
@@ -252,17 +627,49 @@ impl<D: 'static> Clone for SingleFile<D> {
 
 impl<D: 'static> Reload<D> for SingleFile<D> {
     fn needs_reload(&self) -> bool {
-        self.modified != 0 && (self.source.modified(&self.path).unwrap_or(0) > self.modified)
+        if self.modified == 0 {
+            return false;
+        }
+
+        match self.source.modified(&self.path) {
+            // The file may have been restored with an older modification time.
+            Ok(_) if self.missing.swap(false, Ordering::Relaxed) => {
+                info!("{:?} is available again, reloading it", self.path);
+                true
+            }
+            Ok(modified) => modified > self.modified,
+            Err(e) => {
+                if !self.missing.swap(true, Ordering::Relaxed) {
+                    warn!(
+                        "{:?} can't be found anymore, keeping the loaded asset until it reappears: {}",
+                        self.path, e,
+                    );
+                }
+                false
+            }
+        }
     }
 
     fn name(&self) -> String {
         self.path.clone()
     }
 
+    fn last_modified(&self) -> Option<u64> {
+        self.source.modified(&self.path).ok()
+    }
+
     fn format(&self) -> &'static str {
         self.format.name()
     }
 
+    fn record_contents(&mut self, hash: u64) {
+        self.hash = Some(hash);
+    }
+
+    fn is_available(&self) -> bool {
+        self.source.is_available()
+    }
+
     fn reload(self: Box<Self>) -> Result<FormatValue<D>, Error> {
         #[cfg(feature = "profiler")]
         profile_scope!("reload_single_file");
@@ -277,4 +684,350 @@ impl<D: 'static> Reload<D> for SingleFile<D> {
 
         format.import(path, source, Some(objekt::clone(&format)))
     }
+
+    fn reload_if_changed(mut self: Box<Self>) -> Result<Reloaded<D>, Error> {
+        let hash = match self.hash {
+            Some(hash) => hash,
+            None => return self.reload().map(Reloaded::Changed),
+        };
+
+        let (bytes, modified) = self
+            .source
+            .load_with_metadata(&self.path)
+            .with_context(|_| crate::error::Error::Source)?;
+        let new_hash = content_hash(&bytes);
+        if new_hash == hash {
+            debug!("{:?} did not change, skipping its reload", self.path);
+            self.modified = modified;
+            return Ok(Reloaded::Unchanged(self));
+        }
+
+        // Import the bytes which have just been read instead of reading them again.
+        let SingleFile {
+            format,
+            path,
+            source,
+            ..
+        } = *self;
+        let prefetched = Arc::new(Prefetched::new(
+            source.clone(),
+            path.clone(),
+            bytes,
+            modified,
+        ));
+        let mut value = format.import(path.clone(), prefetched, Some(objekt::clone(&format)))?;
+        // Keep watching the file through its source, instead of the one wrapping the bytes.
+        if value.reload.is_some() {
+            let reload = SingleFile {
+                format,
+                hash: Some(new_hash),
+                missing: AtomicBool::new(false),
+                modified,
+                path,
+                source,
+            };
+            value.reload = Some(Box::new(reload));
+        }
+
+        Ok(Reloaded::Changed(value))
+    }
+}
+
+/// Reads the files an asset is made of through a `Source`, recording them so that
+/// the asset is hot reloaded when any of them changes.
+///
+/// This is meant for `Format::import` implementations which read more than one file:
+///
+/// ```rust,ignore
+/// fn import(
+///     &self,
+///     name: String,
+///     source: Arc<dyn Source>,
+///     create_reload: Option<Box<dyn Format<TerrainData>>>,
+/// ) -> Result<FormatValue<TerrainData>, Error> {
+///     let mut reader = MultiFileReader::new(source);
+///     let level = parse_level(&reader.load(&name)?)?;
+///     let heights = reader.load(&level.heightmap)?;
+///     let splat = reader.load(&level.splat)?;
+///
+///     Ok(FormatValue {
+///         data: TerrainData::new(level, heights, splat),
+///         reload: reader.into_reload(create_reload),
+///     })
+/// }
+/// ```
+pub struct MultiFileReader {
+    files: Vec<(String, u64)>,
+    source: Arc<dyn Source>,
+}
+
+impl MultiFileReader {
+    /// Creates a reader which hasn't read any files yet.
+    pub fn new(source: Arc<dyn Source>) -> Self {
+        MultiFileReader {
+            files: Vec::new(),
+            source,
+        }
+    }
+
+    /// Loads the bytes of `path`, recording it as a file of the asset. The first file
+    /// loaded is the one the asset is named after when it is reloaded.
+    pub fn load(&mut self, path: &str) -> Result<Vec<u8>, Error> {
+        let (bytes, modified) = self
+            .source
+            .load_with_metadata(path)
+            .with_context(|_| format_err!("Failed to read file {:?} of the asset", path))?;
+        self.files.push((path.to_owned(), modified));
+
+        Ok(bytes)
+    }
+
+    /// Returns the paths which have been loaded so far.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(|&(ref path, _)| path.as_str())
+    }
+
+    /// Creates a `MultiFile` reload object covering all loaded files, if `create_reload`
+    /// is `Some` and any file has been loaded.
+    pub fn into_reload<D: 'static>(
+        self,
+        create_reload: Option<Box<dyn Format<D>>>,
+    ) -> Option<Box<dyn Reload<D>>> {
+        match create_reload {
+            Some(format) if !self.files.is_empty() => Some(Box::new(MultiFile {
+                format,
+                files: self.files,
+                missing: AtomicBool::new(false),
+                source: self.source,
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// An implementation of `Reload` which watches all of the files an asset has been read
+/// from, see `MultiFileReader`. The asset is reloaded if any of them changes.
+///
+/// Reloads import the first file with the format again, which reads the other files
+/// through the same source. If a file disappears, a warning is logged once and the
+/// asset is kept as it is until the file is back.
+pub struct MultiFile<D> {
+    format: Box<dyn Format<D>>,
+    files: Vec<(String, u64)>,
+    missing: AtomicBool,
+    source: Arc<dyn Source>,
+}
+
+impl<D: 'static> MultiFile<D> {
+    /// Returns the paths of the watched files, starting with the one the asset is
+    /// named after.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(|&(ref path, _)| path.as_str())
+    }
+}
+
+impl<D: 'static> Clone for MultiFile<D> {
+    fn clone(&self) -> Self {
+        MultiFile {
+            format: self.format.clone(),
+            files: self.files.clone(),
+            missing: AtomicBool::new(self.missing.load(Ordering::Relaxed)),
+            source: self.source.clone(),
+        }
+    }
+}
+
+impl<D: 'static> Reload<D> for MultiFile<D> {
+    fn needs_reload(&self) -> bool {
+        let mut changed = false;
+        for &(ref path, modified) in &self.files {
+            match self.source.modified(path) {
+                Ok(now) => changed |= modified != 0 && now > modified,
+                Err(e) => {
+                    if !self.missing.swap(true, Ordering::Relaxed) {
+                        warn!(
+                            "{:?} of {:?} can't be found anymore, keeping the loaded asset until it reappears: {}",
+                            path, self.files[0].0, e,
+                        );
+                    }
+                    return false;
+                }
+            }
+        }
+
+        // The files may have been restored with older modification times.
+        if self.missing.swap(false, Ordering::Relaxed) {
+            info!("{:?} is available again, reloading it", self.files[0].0);
+            return true;
+        }
+
+        changed
+    }
+
+    fn name(&self) -> String {
+        self.files[0].0.clone()
+    }
+
+    fn files(&self) -> Vec<String> {
+        self.paths().map(str::to_owned).collect()
+    }
+
+    fn last_modified(&self) -> Option<u64> {
+        self.paths()
+            .filter_map(|path| self.source.modified(path).ok())
+            .max()
+    }
+
+    fn format(&self) -> &'static str {
+        self.format.name()
+    }
+
+    fn is_available(&self) -> bool {
+        self.source.is_available()
+    }
+
+    fn reload(self: Box<Self>) -> Result<FormatValue<D>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("reload_multi_file");
+
+        let this: MultiFile<D> = *self;
+        let MultiFile {
+            format,
+            mut files,
+            source,
+            ..
+        } = this;
+
+        let (path, _) = files.swap_remove(0);
+        format.import(path, source, Some(objekt::clone(&format)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use amethyst_error::{format_err, Error};
+    use parking_lot::Mutex;
+
+    use crate::{Format, RonFormat, Source};
+
+    use super::{
+        HotReloadStrategy, HotReloadStrategyFor, MultiFileReader, Reload, Reloaded, SingleFile,
+    };
+
+    /// Source with a single file, which can be removed and restored.
+    #[derive(Default)]
+    struct FlakySource {
+        loads: AtomicUsize,
+        modified: Mutex<Option<u64>>,
+    }
+
+    impl Source for FlakySource {
+        fn modified(&self, path: &str) -> Result<u64, Error> {
+            let modified = *self.modified.lock();
+            modified.ok_or_else(|| format_err!("{:?} not found", path))
+        }
+
+        fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+            self.loads.fetch_add(1, Ordering::Relaxed);
+            self.modified(path).map(|_| b"()".to_vec())
+        }
+    }
+
+    #[test]
+    fn missing_files_are_reloaded_once_they_reappear() {
+        let source = Arc::new(FlakySource::default());
+        *source.modified.lock() = Some(2);
+        let format = Box::new(RonFormat) as Box<dyn Format<()>>;
+        let reload = SingleFile::new(format, 2, "file".into(), source.clone());
+        assert!(!reload.needs_reload());
+
+        *source.modified.lock() = None;
+        assert!(!reload.needs_reload());
+        assert!(!reload.needs_reload());
+
+        // Restored with an older modification time.
+        *source.modified.lock() = Some(1);
+        assert!(reload.needs_reload());
+    }
+
+    #[test]
+    fn unchanged_contents_are_not_reloaded() {
+        let source = Arc::new(FlakySource::default());
+        *source.modified.lock() = Some(1);
+        let format = Box::new(RonFormat) as Box<dyn Format<()>>;
+        let reload =
+            SingleFile::new(format.clone(), 1, "file".into(), source.clone()).with_contents(b"()");
+
+        *source.modified.lock() = Some(2);
+        assert!(reload.needs_reload());
+        let reload = match Box::new(reload).reload_if_changed().unwrap() {
+            Reloaded::Unchanged(reload) => reload,
+            Reloaded::Changed(_) => panic!("unchanged contents were reloaded"),
+        };
+        assert!(!reload.needs_reload());
+
+        let reload =
+            SingleFile::new(format, 1, "file".into(), source.clone()).with_contents(b"( )");
+        let loads = source.loads.load(Ordering::Relaxed);
+        let reload = match Box::new(reload).reload_if_changed().unwrap() {
+            Reloaded::Changed(value) => value.reload.expect("reload object was dropped"),
+            Reloaded::Unchanged(_) => panic!("changed contents were not reloaded"),
+        };
+        // The changed file is only read once, and compared to its new contents afterwards.
+        assert_eq!(loads + 1, source.loads.load(Ordering::Relaxed));
+        match reload.reload_if_changed().unwrap() {
+            Reloaded::Unchanged(_) => {}
+            Reloaded::Changed(_) => panic!("contents of the reload were not recorded"),
+        }
+    }
+
+    #[test]
+    fn changed_dependencies_are_reloaded() {
+        use crate::MemorySource;
+
+        let mut source = MemorySource::new();
+        source.insert("level", &b"()"[..]);
+        let source = Arc::new(source);
+        let format = Box::new(RonFormat) as Box<dyn Format<()>>;
+
+        let mut reader = MultiFileReader::new(source.clone());
+        reader.load("level").unwrap();
+        let e = reader.load("level.heights").unwrap_err();
+        assert!(e.to_string().contains("\"level.heights\""), "{}", e);
+        assert_eq!(vec!["level"], reader.paths().collect::<Vec<_>>());
+        assert!(MultiFileReader::new(source)
+            .into_reload(Some(format.clone()))
+            .is_none());
+
+        let source = Arc::new(FlakySource::default());
+        *source.modified.lock() = Some(1);
+        let mut reader = MultiFileReader::new(source.clone());
+        reader.load("level").unwrap();
+        reader.load("level.heights").unwrap();
+        let reload = reader.into_reload(Some(format)).unwrap();
+        assert_eq!("level", reload.name());
+        assert!(!reload.needs_reload());
+
+        *source.modified.lock() = Some(2);
+        assert!(reload.needs_reload());
+        assert!(reload.reload().is_ok());
+    }
+
+    #[test]
+    fn type_strategies_take_precedence() {
+        let global = HotReloadStrategy::never();
+        let mut scripts = HotReloadStrategyFor::<()>::new(HotReloadStrategy::when_triggered());
+        scripts.trigger();
+        scripts.advance(1);
+
+        let selected = HotReloadStrategyFor::select(Some(&scripts), Some(&global)).unwrap();
+        assert!(selected.needs_reload(2));
+        let selected = HotReloadStrategyFor::<()>::select(None, Some(&global)).unwrap();
+        assert!(!selected.needs_reload(2));
+    }
 }
@@ -0,0 +1,216 @@
+use std::{fmt, io::Read, sync::Arc, thread, time::Duration};
+
+use amethyst_error::{format_err, Error, ResultExt};
+use log::warn;
+
+use crate::source::{Source, SourceEntry};
+
+/// Describes how often failed reads from a source are retried before a load fails,
+/// see `Loader::set_retry_policy` and `LoadOptions`.
+///
+/// Only errors of the source are retried, errors of the format are final as
+/// decoding the same bytes again would fail again.
+#[derive(Clone, Copy)]
+pub struct RetryPolicy {
+    /// The number of times a read is attempted, including the first attempt.
+    /// `1`, the default, disables retrying.
+    pub max_attempts: u32,
+    /// The time to wait before the second attempt, which is doubled after every
+    /// further attempt. Defaults to 100 milliseconds.
+    pub backoff: Duration,
+    /// Returns `true` if a read failing with the given error should be retried.
+    /// Defaults to retrying all errors.
+    pub retry_if: fn(&Error) -> bool,
+}
+
+impl RetryPolicy {
+    /// Creates a policy retrying all errors until `max_attempts` attempts are made.
+    pub fn new(max_attempts: u32, backoff: Duration) -> Self {
+        RetryPolicy {
+            max_attempts,
+            backoff,
+            retry_if: retry_all,
+        }
+    }
+
+    /// Returns `true` if this policy may retry reads at all.
+    pub(crate) fn retries(&self) -> bool {
+        self.max_attempts > 1
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(1, Duration::from_millis(100))
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("backoff", &self.backoff)
+            .finish()
+    }
+}
+
+fn retry_all(_: &Error) -> bool {
+    true
+}
+
+/// Wraps a source, retrying failed reads according to a `RetryPolicy`.
+pub(crate) struct Retrying {
+    policy: RetryPolicy,
+    source: Arc<dyn Source>,
+}
+
+impl Retrying {
+    pub(crate) fn new(source: Arc<dyn Source>, policy: RetryPolicy) -> Self {
+        Retrying { policy, source }
+    }
+
+    fn retry<T, F>(&self, path: &str, mut f: F) -> Result<T, Error>
+    where
+        F: FnMut(&dyn Source) -> Result<T, Error>,
+    {
+        let mut attempts = 1;
+        let mut backoff = self.policy.backoff;
+        loop {
+            let e = match f(&*self.source) {
+                Ok(value) => return Ok(value),
+                Err(e) => e,
+            };
+            if attempts >= self.policy.max_attempts || !(self.policy.retry_if)(&e) {
+                return match attempts {
+                    1 => Err(e),
+                    _ => Err(e).with_context(|_| {
+                        format_err!("Reading {:?} failed after {} attempts", path, attempts)
+                    }),
+                };
+            }
+
+            warn!(
+                "Reading {:?} failed (attempt {} of {}), retrying in {:?}: {}",
+                path, attempts, self.policy.max_attempts, backoff, e,
+            );
+            thread::sleep(backoff);
+            backoff *= 2;
+            attempts += 1;
+        }
+    }
+}
+
+impl Source for Retrying {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        self.source.modified(path)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        self.retry(path, |source| source.load(path))
+    }
+
+    fn open(&self, path: &str) -> Result<Box<dyn Read + Send>, Error> {
+        self.retry(path, |source| source.open(path))
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        self.source.list(path)
+    }
+
+    fn entries(&self, prefix: &str, recursive: bool) -> Result<Vec<SourceEntry>, Error> {
+        self.source.entries(prefix, recursive)
+    }
+
+    fn is_available(&self) -> bool {
+        self.source.is_available()
+    }
+
+    fn load_with_metadata(&self, path: &str) -> Result<(Vec<u8>, u64), Error> {
+        self.retry(path, |source| source.load_with_metadata(path))
+    }
+
+    fn resolve(&self, path: &str) -> Option<String> {
+        self.source.resolve(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use amethyst_error::{format_err, Error};
+
+    use super::{RetryPolicy, Retrying};
+    use crate::source::Source;
+
+    /// Source failing the given number of times before succeeding.
+    struct FlakySource {
+        failures: usize,
+        loads: AtomicUsize,
+    }
+
+    impl Source for FlakySource {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        fn load(&self, _: &str) -> Result<Vec<u8>, Error> {
+            if self.loads.fetch_add(1, Ordering::Relaxed) < self.failures {
+                Err(format_err!("Connection reset"))
+            } else {
+                Ok(b"data".to_vec())
+            }
+        }
+    }
+
+    fn flaky(failures: usize) -> Arc<FlakySource> {
+        Arc::new(FlakySource {
+            failures,
+            loads: AtomicUsize::new(0),
+        })
+    }
+
+    #[test]
+    fn retries_until_success() {
+        let source = flaky(2);
+        let retrying = Retrying::new(
+            source.clone(),
+            RetryPolicy::new(3, Duration::from_millis(1)),
+        );
+
+        assert_eq!(b"data".to_vec(), retrying.load("asset").unwrap());
+        assert_eq!(3, source.loads.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn reports_attempts_when_giving_up() {
+        let source = flaky(5);
+        let retrying = Retrying::new(
+            source.clone(),
+            RetryPolicy::new(3, Duration::from_millis(1)),
+        );
+
+        let e = retrying.load("asset").unwrap_err();
+        assert!(e.to_string().contains("after 3 attempts"));
+        assert_eq!(3, source.loads.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn only_retries_matching_errors() {
+        let source = flaky(5);
+        let policy = RetryPolicy {
+            retry_if: |_| false,
+            ..RetryPolicy::new(3, Duration::from_millis(1))
+        };
+        let retrying = Retrying::new(source.clone(), policy);
+
+        assert!(retrying.load("asset").is_err());
+        assert_eq!(1, source.loads.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,76 @@
+use std::{ffi::CString, io::Read};
+
+use ndk::asset::AssetManager;
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use amethyst_error::{format_err, Error, ResultExt};
+
+use crate::{error, source::Source};
+
+/// Source for the assets bundled into an Android APK.
+///
+/// These assets are only reachable through the `AAssetManager` of the activity,
+/// which can be obtained using `ndk_glue`, and registered with the `Loader` like
+/// any other source:
+///
+/// ```rust,ignore
+/// let manager = ndk_glue::native_activity().asset_manager();
+/// world
+///     .read_resource::<Loader>()
+///     .add_source("apk", ApkSource::new(manager));
+/// ```
+///
+/// The contents of an APK never change at runtime, so `modified` always
+/// returns `0`, which disables hot reloading for this source.
+pub struct ApkSource {
+    manager: AssetManager,
+}
+
+// The functions of `AAssetManager` are thread-safe, only the `AAsset`s opened
+// from it must not be shared, which `ApkSource` never does.
+unsafe impl Send for ApkSource {}
+unsafe impl Sync for ApkSource {}
+
+impl ApkSource {
+    /// Creates a source for the assets of the given manager.
+    pub fn new(manager: AssetManager) -> Self {
+        ApkSource { manager }
+    }
+
+    fn path(path: &str) -> Result<CString, Error> {
+        CString::new(path)
+            .with_context(|_| format_err!("Invalid asset path {:?}", path))
+            .with_context(|_| error::Error::Source)
+    }
+}
+
+impl Source for ApkSource {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        match self.manager.open(&Self::path(path)?) {
+            Some(_) => Ok(0),
+            None => Err(format_err!("No asset {:?} in the APK", path))
+                .with_context(|_| error::Error::Source),
+        }
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("apk_load_asset");
+
+        let mut asset = self
+            .manager
+            .open(&Self::path(path)?)
+            .ok_or_else(|| format_err!("No asset {:?} in the APK", path))
+            .with_context(|_| error::Error::Source)?;
+
+        let mut v = Vec::with_capacity(asset.get_length());
+        asset
+            .read_to_end(&mut v)
+            .with_context(|_| format_err!("Failed to read asset {:?}", path))
+            .with_context(|_| error::Error::Source)?;
+
+        Ok(v)
+    }
+}
@@ -0,0 +1,79 @@
+use std::path::{Path, PathBuf};
+
+use core_foundation::bundle::CFBundle;
+
+use amethyst_error::Error;
+
+use crate::source::{Directory, Source, SourceEntry};
+
+/// Source for the assets inside the `Resources` directory of a macOS or iOS
+/// app bundle.
+///
+/// Packaged apps aren't started from the directory containing their assets,
+/// so relative `Directory` paths which work with `cargo run` break inside a
+/// bundle. This source resolves the bundle's resource directory when it is
+/// created and otherwise behaves like a `Directory`, including hot reloading.
+/// When not running from an app bundle, it falls back to the given relative
+/// directory, so the same code works in both situations.
+#[derive(Debug)]
+pub struct BundleSource {
+    dir: Directory,
+    root: PathBuf,
+}
+
+impl BundleSource {
+    /// Creates a source for the directory `loc` inside the bundle's resources,
+    /// or relative to the working directory if the app is not bundled.
+    pub fn new<P>(loc: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let root = match resources_dir() {
+            Some(resources) => resources.join(loc),
+            None => loc.as_ref().to_owned(),
+        };
+
+        BundleSource {
+            dir: Directory::new(root.clone()),
+            root,
+        }
+    }
+
+    /// Returns the directory the assets are loaded from.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+/// Returns the resource directory of the main bundle, if the app is bundled.
+fn resources_dir() -> Option<PathBuf> {
+    let bundle = CFBundle::main_bundle();
+    let is_app = bundle
+        .bundle_url()
+        .and_then(|url| url.to_path())
+        .map(|path| path.extension().map_or(false, |ext| ext == "app"))
+        .unwrap_or(false);
+    if !is_app {
+        return None;
+    }
+
+    bundle.bundle_resources_url().and_then(|url| url.to_path())
+}
+
+impl Source for BundleSource {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        self.dir.modified(path)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        self.dir.load(path)
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        self.dir.list(path)
+    }
+
+    fn entries(&self, prefix: &str, recursive: bool) -> Result<Vec<SourceEntry>, Error> {
+        self.dir.entries(prefix, recursive)
+    }
+}
@@ -0,0 +1,25 @@
+use amethyst_error::Error;
+
+use crate::source::Source;
+
+/// A source which returns the same bytes for any path, used to
+/// import assets from data that is already in memory.
+pub(crate) struct BytesSource {
+    bytes: Vec<u8>,
+}
+
+impl BytesSource {
+    pub(crate) fn new(bytes: Vec<u8>) -> Self {
+        BytesSource { bytes }
+    }
+}
+
+impl Source for BytesSource {
+    fn modified(&self, _: &str) -> Result<u64, Error> {
+        Ok(0)
+    }
+
+    fn load(&self, _: &str) -> Result<Vec<u8>, Error> {
+        Ok(self.bytes.clone())
+    }
+}
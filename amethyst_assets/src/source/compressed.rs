@@ -0,0 +1,179 @@
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use amethyst_error::{format_err, Error, ResultExt};
+
+use crate::{error, source::Source};
+
+/// Compression formats supported by `CompressedSource`.
+#[derive(Clone, Copy, Debug)]
+enum Compression {
+    #[cfg(feature = "zstd_compression")]
+    Zstd,
+    #[cfg(feature = "lz4_compression")]
+    Lz4,
+}
+
+const COMPRESSIONS: &[Compression] = &[
+    #[cfg(feature = "zstd_compression")]
+    Compression::Zstd,
+    #[cfg(feature = "lz4_compression")]
+    Compression::Lz4,
+];
+
+impl Compression {
+    fn extension(self) -> &'static str {
+        match self {
+            #[cfg(feature = "zstd_compression")]
+            Compression::Zstd => ".zst",
+            #[cfg(feature = "lz4_compression")]
+            Compression::Lz4 => ".lz4",
+        }
+    }
+
+    fn decompress(self, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        let v = match self {
+            #[cfg(feature = "zstd_compression")]
+            Compression::Zstd => zstd::stream::decode_all(bytes)?,
+            #[cfg(feature = "lz4_compression")]
+            Compression::Lz4 => {
+                use std::io::Read;
+
+                let mut v = Vec::new();
+                lz4::Decoder::new(bytes)?.read_to_end(&mut v)?;
+                v
+            }
+        };
+
+        Ok(v)
+    }
+}
+
+/// Source which transparently decompresses the assets of another source.
+///
+/// If an asset can't be loaded from the inner source, the same path with a `.zst`
+/// (zstd, requires the `zstd_compression` feature) or `.lz4` (lz4 frame format, requires
+/// the `lz4_compression` feature) extension appended is tried and decompressed.
+/// `modified` reports the newest modification time of the plain and compressed files,
+/// so replacing a compressed asset with a plain one triggers a hot reload.
+#[derive(Debug)]
+pub struct CompressedSource<S> {
+    source: S,
+}
+
+impl<S> CompressedSource<S>
+where
+    S: Source,
+{
+    /// Creates a source decompressing the assets of `source`.
+    pub fn new(source: S) -> Self {
+        CompressedSource { source }
+    }
+}
+
+impl<S> Source for CompressedSource<S>
+where
+    S: Source,
+{
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("compressed_modified_asset");
+
+        let plain = self.source.modified(path);
+        let newest = COMPRESSIONS
+            .iter()
+            .filter_map(|c| {
+                self.source
+                    .modified(&format!("{}{}", path, c.extension()))
+                    .ok()
+            })
+            .max();
+
+        match (plain, newest) {
+            (Ok(plain), Some(newest)) => Ok(plain.max(newest)),
+            (Ok(plain), None) => Ok(plain),
+            (Err(_), Some(newest)) => Ok(newest),
+            (Err(e), None) => Err(e),
+        }
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("compressed_load_asset");
+
+        let e = match self.source.load(path) {
+            Ok(bytes) => return Ok(bytes),
+            Err(e) => e,
+        };
+
+        for compression in COMPRESSIONS {
+            let compressed_path = format!("{}{}", path, compression.extension());
+            if let Ok(bytes) = self.source.load(&compressed_path) {
+                return compression
+                    .decompress(&bytes)
+                    .with_context(|_| format_err!("Failed to decompress {:?}", compressed_path))
+                    .with_context(|_| error::Error::Source);
+            }
+        }
+
+        Err(e)
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        let mut entries: Vec<_> = self
+            .source
+            .list(path)?
+            .into_iter()
+            .map(|entry| {
+                let stripped = COMPRESSIONS
+                    .iter()
+                    .find(|c| entry.ends_with(c.extension()))
+                    .map(|c| entry[..entry.len() - c.extension().len()].to_owned());
+                stripped.unwrap_or(entry)
+            })
+            .collect();
+        entries.sort();
+        entries.dedup();
+
+        Ok(entries)
+    }
+
+    fn is_available(&self) -> bool {
+        self.source.is_available()
+    }
+}
+
+#[cfg(all(test, feature = "zstd_compression"))]
+mod test {
+    use crate::{source::Source, MemorySource};
+
+    use super::CompressedSource;
+
+    #[test]
+    fn loads_compressed_assets_transparently() {
+        let mut inner = MemorySource::new();
+        inner.insert("plain.ron", &b"plain"[..]);
+        inner.insert(
+            "maps/e1m1.ron.zst",
+            zstd::stream::encode_all(&b"compressed"[..], 0).unwrap(),
+        );
+        inner.insert("broken.ron.zst", &b"not zstd"[..]);
+        let source = CompressedSource::new(inner);
+
+        assert_eq!(b"plain".to_vec(), source.load("plain.ron").unwrap());
+        assert_eq!(
+            b"compressed".to_vec(),
+            source.load("maps/e1m1.ron").unwrap()
+        );
+        assert!(source.load("broken.ron").is_err());
+        assert!(source.load("missing.ron").is_err());
+        assert_eq!(
+            vec![
+                String::from("broken.ron"),
+                String::from("maps/e1m1.ron"),
+                String::from("plain.ron")
+            ],
+            source.list("").unwrap()
+        );
+    }
+}
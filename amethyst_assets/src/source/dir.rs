@@ -1,6 +1,7 @@
 use std::{
-    fs::File,
-    path::{Path, PathBuf},
+    fs::{read_dir, File},
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
     time::UNIX_EPOCH,
 };
 
@@ -9,7 +10,10 @@ use thread_profiler::profile_scope;
 
 use amethyst_error::{format_err, Error, ResultExt};
 
-use crate::{error, source::Source};
+use crate::{
+    error,
+    source::{Source, SourceEntry},
+};
 
 /// Directory source.
 ///
@@ -17,9 +21,14 @@ use crate::{error, source::Source};
 /// inside the `Loader`, which is automatically used when you call
 /// `load`. In case you want another, second, directory for assets,
 /// you can instantiate one yourself, too. Please use `Loader::load_from` then.
+///
+/// Paths may use both `/` and `\` as separators, but must be relative to the directory
+/// and must not leave it using `..`. If a file can't be found, the error suggests a file whose path only
+/// differs in case, if there is one.
 #[derive(Debug)]
 pub struct Directory {
     loc: PathBuf,
+    strict_case: bool,
 }
 
 impl Directory {
@@ -28,14 +37,131 @@ impl Directory {
     where
         P: Into<PathBuf>,
     {
-        Directory { loc: loc.into() }
+        Directory {
+            loc: loc.into(),
+            strict_case: false,
+        }
+    }
+
+    /// If set to `true`, paths whose case doesn't match the files exactly fail to load,
+    /// even on case-insensitive file systems. This helps to find paths that would break
+    /// on other platforms, but requires reading the directories of every path.
+    pub fn with_strict_case(mut self, strict_case: bool) -> Self {
+        self.strict_case = strict_case;
+        self
+    }
+
+    /// Returns the directory the assets are read from.
+    pub fn root(&self) -> &Path {
+        &self.loc
     }
 
-    fn path(&self, s_path: &str) -> PathBuf {
+    fn path(&self, s_path: &str) -> Result<PathBuf, Error> {
+        let components = components(s_path)?;
+        if self.strict_case {
+            if let Some(found) = self.find_ignoring_case(&components) {
+                if found.iter().zip(&components).any(|(f, c)| f != c) {
+                    return Err(format_err!(
+                        "Path {:?} does not match the case of {:?}",
+                        s_path,
+                        found.join("/")
+                    ))
+                    .with_context(|_| error::Error::Source);
+                }
+            }
+        }
+
         let mut path = self.loc.clone();
-        path.extend(Path::new(s_path).iter());
+        path.extend(components);
+
+        Ok(path)
+    }
+
+    /// Returns the names of the path components as they are on disk, preferring exact
+    /// matches over matches ignoring case, or `None` if there is no such file.
+    fn find_ignoring_case(&self, components: &[&str]) -> Option<Vec<String>> {
+        let mut dir = self.loc.clone();
+        let mut found = Vec::with_capacity(components.len());
+        for component in components {
+            let names: Vec<String> = read_dir(&dir)
+                .ok()?
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                .collect();
+            let lowercase = component.to_lowercase();
+            let name = names
+                .iter()
+                .find(|name| name == component)
+                .or_else(|| names.iter().find(|name| name.to_lowercase() == lowercase))?
+                .clone();
+            dir.push(&name);
+            found.push(name);
+        }
 
-        path
+        Some(found)
+    }
+
+    /// Creates the error for a file which failed to open, suggesting a file
+    /// which only differs in case if the file was not found.
+    fn open_error(&self, s_path: &str, path: &Path, e: io::Error) -> Error {
+        let suggestion = match e.kind() {
+            io::ErrorKind::NotFound => components(s_path)
+                .ok()
+                .and_then(|components| self.find_ignoring_case(&components))
+                .map(|found| found.join("/")),
+            _ => None,
+        };
+        let context = match suggestion {
+            Some(suggestion) => {
+                format_err!("File {:?} not found; did you mean {:?}?", path, suggestion)
+            }
+            None => format_err!("Failed to open file {:?}", path),
+        };
+
+        context.with_source(e)
+    }
+}
+
+/// Splits a path into its components, accepting both `/` and `\` as separators.
+fn components(s_path: &str) -> Result<Vec<&str>, Error> {
+    let is_separator = |c: char| c == '/' || c == '\\';
+    // Absolute paths, including Windows drives, would be read from outside of the directory.
+    if s_path.starts_with(is_separator)
+        || s_path
+            .split(is_separator)
+            .any(|component| Path::new(component).components().any(is_root))
+    {
+        return Err(format_err!(
+            "Path {:?} is absolute, but must be relative to the asset directory",
+            s_path
+        ))
+        .with_context(|_| error::Error::Source);
+    }
+
+    let mut components = Vec::new();
+    for component in s_path.split(is_separator) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                if components.pop().is_none() {
+                    return Err(format_err!(
+                        "Path {:?} points outside of the asset directory",
+                        s_path
+                    ))
+                    .with_context(|_| error::Error::Source);
+                }
+            }
+            component => components.push(component),
+        }
+    }
+
+    Ok(components)
+}
+
+/// Returns `true` for path components which start an absolute path, like Windows drives.
+fn is_root(component: Component<'_>) -> bool {
+    match component {
+        Component::Prefix(_) | Component::RootDir => true,
+        _ => false,
     }
 }
 
@@ -45,9 +171,11 @@ impl Source for Directory {
         profile_scope!("dir_modified_asset");
         use std::fs::metadata;
 
-        let path = self.path(path);
+        let s_path = path;
+        let path = self.path(s_path)?;
 
         metadata(&path)
+            .map_err(|e| self.open_error(s_path, &path, e))
             .with_context(|_| format_err!("Failed to fetch metadata for {:?}", path))?
             .modified()
             .with_context(|_| format_err!("Could not get modification time"))?
@@ -58,16 +186,79 @@ impl Source for Directory {
             .map(|d| d.as_secs())
     }
 
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("dir_list_assets");
+
+        Ok(self
+            .entries(path, true)?
+            .into_iter()
+            .filter(|entry| !entry.is_dir)
+            .map(|entry| entry.path)
+            .collect())
+    }
+
+    fn entries(&self, prefix: &str, recursive: bool) -> Result<Vec<SourceEntry>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("dir_list_entries");
+        let mut entries = Vec::new();
+        let mut dirs = vec![prefix.trim_end_matches('/').to_owned()];
+        while let Some(dir) = dirs.pop() {
+            let full_path = self.path(&dir)?;
+            let read = read_dir(&full_path)
+                .with_context(|_| format_err!("Failed to read directory {:?}", full_path))
+                .with_context(|_| error::Error::Source)?;
+            for entry in read {
+                let entry = entry
+                    .with_context(|_| format_err!("Failed to read directory {:?}", full_path))
+                    .with_context(|_| error::Error::Source)?;
+                let name = match entry.file_name().into_string() {
+                    Ok(name) => name,
+                    // Can't be loaded using a `&str` path anyways.
+                    Err(_) => continue,
+                };
+                let entry_path = if dir.is_empty() {
+                    name
+                } else {
+                    format!("{}/{}", dir, name)
+                };
+                let metadata = entry
+                    .metadata()
+                    .with_context(|_| format_err!("Failed to fetch metadata for {:?}", entry_path))
+                    .with_context(|_| error::Error::Source)?;
+                let modified = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                let is_dir = metadata.is_dir();
+                if is_dir && recursive {
+                    dirs.push(entry_path.clone());
+                }
+                entries.push(SourceEntry {
+                    path: entry_path,
+                    is_dir,
+                    size: if is_dir { 0 } else { metadata.len() },
+                    modified,
+                });
+            }
+        }
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(entries)
+    }
+
     fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
         #[cfg(feature = "profiler")]
         profile_scope!("dir_load_asset");
-        use std::io::Read;
 
-        let path = self.path(path);
+        let s_path = path;
+        let path = self.path(s_path)?;
 
         let mut v = Vec::new();
         let mut file = File::open(&path)
-            .with_context(|_| format_err!("Failed to open file {:?}", path))
+            .map_err(|e| self.open_error(s_path, &path, e))
             .with_context(|_| error::Error::Source)?;
         file.read_to_end(&mut v)
             .with_context(|_| format_err!("Failed to read file {:?}", path))
@@ -75,6 +266,21 @@ impl Source for Directory {
 
         Ok(v)
     }
+
+    fn open(&self, path: &str) -> Result<Box<dyn Read + Send>, Error> {
+        let s_path = path;
+        let path = self.path(s_path)?;
+
+        let file = File::open(&path)
+            .map_err(|e| self.open_error(s_path, &path, e))
+            .with_context(|_| error::Error::Source)?;
+
+        Ok(Box::new(file))
+    }
+
+    fn resolve(&self, path: &str) -> Option<String> {
+        self.path(path).ok().map(|path| path.display().to_string())
+    }
 }
 
 #[cfg(test)]
@@ -98,6 +304,73 @@ mod test {
         );
     }
 
+    #[test]
+    fn lists_assets_recursively() {
+        let test_assets_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/assets");
+        let directory = Directory::new(test_assets_dir);
+
+        assert_eq!(
+            vec![String::from("subdir/asset")],
+            directory.list("").expect("Failed to list tests/assets")
+        );
+    }
+
+    #[test]
+    fn lists_entries_with_and_without_recursion() {
+        let test_assets_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/assets");
+        let directory = Directory::new(test_assets_dir);
+
+        let entries = directory
+            .entries("", false)
+            .expect("Failed to list tests/assets");
+        assert_eq!(1, entries.len());
+        assert_eq!("subdir", entries[0].path);
+        assert!(entries[0].is_dir);
+
+        let entries = directory
+            .entries("", true)
+            .expect("Failed to list tests/assets");
+        assert_eq!(2, entries.len());
+        assert_eq!("subdir/asset", entries[1].path);
+        assert_eq!(4, entries[1].size);
+    }
+
+    #[test]
+    fn normalizes_paths_and_rejects_escaping_paths() {
+        let test_assets_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/assets");
+        let directory = Directory::new(test_assets_dir);
+
+        assert_eq!(b"data".to_vec(), directory.load("subdir\\asset").unwrap());
+        assert_eq!(
+            b"data".to_vec(),
+            directory.load("./subdir/../subdir/asset").unwrap()
+        );
+        assert!(directory.load("../assets/subdir/asset").is_err());
+    }
+
+    #[test]
+    fn rejects_absolute_paths() {
+        let test_assets_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/assets");
+        let absolute = test_assets_dir.join("subdir").join("asset");
+        let directory = Directory::new(test_assets_dir);
+
+        assert!(directory.load("/subdir/asset").is_err());
+        assert!(directory.load("\\subdir\\asset").is_err());
+        assert!(directory.load(absolute.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn strict_case_rejects_mismatching_case() {
+        let test_assets_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/assets");
+        let directory = Directory::new(test_assets_dir).with_strict_case(true);
+
+        let err = directory.load("SubDir/asset").unwrap_err();
+        assert!(err
+            .causes()
+            .any(|cause| cause.to_string().contains("\"subdir/asset\"")));
+        assert_eq!(b"data".to_vec(), directory.load("subdir/asset").unwrap());
+    }
+
     #[cfg(windows)]
     #[test]
     fn tolerates_backslashed_location_with_forward_slashed_asset_paths() {
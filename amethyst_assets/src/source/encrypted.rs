@@ -0,0 +1,267 @@
+use std::{fs, hash::Hasher, path::Path};
+
+use fnv::FnvHasher;
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use amethyst_error::{format_err, Error, ResultExt};
+
+use crate::{
+    error,
+    source::{Directory, Source, SourceEntry},
+};
+
+/// A symmetric cipher used by `EncryptedSource`.
+pub trait Cipher: Send + Sync + 'static {
+    /// Encrypts the contents of an asset.
+    fn encrypt(&self, bytes: Vec<u8>) -> Result<Vec<u8>, Error>;
+
+    /// Decrypts the contents of an asset, failing if they have been tampered with.
+    fn decrypt(&self, bytes: Vec<u8>) -> Result<Vec<u8>, Error>;
+}
+
+/// Source which decrypts the assets loaded from another source.
+///
+/// The cipher has to be chosen explicitly: `AesGcmCipher` (requires the `aes_encryption` feature)
+/// actually encrypts the assets, while `XorObfuscation` only hides them from casual
+/// inspection. Use `encrypt_directory` to create the encrypted assets.
+#[derive(Debug)]
+pub struct EncryptedSource<S, C> {
+    cipher: C,
+    source: S,
+}
+
+impl<S, C> EncryptedSource<S, C>
+where
+    S: Source,
+    C: Cipher,
+{
+    /// Creates a source decrypting the assets of `source` with `cipher`.
+    pub fn new(source: S, cipher: C) -> Self {
+        EncryptedSource { cipher, source }
+    }
+
+    fn decrypt(&self, path: &str, bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+        self.cipher
+            .decrypt(bytes)
+            .with_context(|_| format_err!("Failed to decrypt {:?}", path))
+            .with_context(|_| error::Error::Source)
+    }
+}
+
+impl<S, C> Source for EncryptedSource<S, C>
+where
+    S: Source,
+    C: Cipher,
+{
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        self.source.modified(path)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("encrypted_load_asset");
+
+        let bytes = self.source.load(path)?;
+        self.decrypt(path, bytes)
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        self.source.list(path)
+    }
+
+    // The sizes are the ones of the encrypted data.
+    fn entries(&self, prefix: &str, recursive: bool) -> Result<Vec<SourceEntry>, Error> {
+        self.source.entries(prefix, recursive)
+    }
+
+    fn is_available(&self) -> bool {
+        self.source.is_available()
+    }
+
+    fn load_with_metadata(&self, path: &str) -> Result<(Vec<u8>, u64), Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("encrypted_load_asset_with_metadata");
+
+        let (bytes, modified) = self.source.load_with_metadata(path)?;
+        Ok((self.decrypt(path, bytes)?, modified))
+    }
+
+    fn resolve(&self, path: &str) -> Option<String> {
+        self.source.resolve(path)
+    }
+}
+
+/// Encrypts all files below the directory `src` with `cipher`, writing them to the
+/// same relative paths below `dst`. Returns the number of encrypted files.
+pub fn encrypt_directory<C, P, Q>(cipher: &C, src: P, dst: Q) -> Result<usize, Error>
+where
+    C: Cipher,
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let src = Directory::new(src.as_ref());
+    let dst = dst.as_ref();
+
+    let paths = src.list("")?;
+    for path in &paths {
+        let bytes = cipher
+            .encrypt(src.load(path)?)
+            .with_context(|_| format_err!("Failed to encrypt {:?}", path))?;
+        let target = dst.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|_| format_err!("Failed to create directory {:?}", parent))?;
+        }
+        fs::write(&target, bytes)
+            .with_context(|_| format_err!("Failed to write file {:?}", target))?;
+    }
+
+    Ok(paths.len())
+}
+
+/// Obfuscates the assets by XOR-ing them with a repeating key.
+///
+/// This is no encryption: the key is easily recovered from the assets, so don't use it
+/// to protect anything which has to stay secret. A checksum of the contents is appended,
+/// so modified assets are detected.
+#[derive(Clone, Debug)]
+pub struct XorObfuscation {
+    key: Vec<u8>,
+}
+
+impl XorObfuscation {
+    /// Creates an obfuscation with the given key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is empty.
+    pub fn new<K>(key: K) -> Self
+    where
+        K: Into<Vec<u8>>,
+    {
+        let key = key.into();
+        assert!(!key.is_empty(), "XOR key must not be empty");
+
+        XorObfuscation { key }
+    }
+
+    fn apply(&self, bytes: &mut [u8]) {
+        for (byte, key) in bytes.iter_mut().zip(self.key.iter().cycle()) {
+            *byte ^= key;
+        }
+    }
+}
+
+fn checksum(bytes: &[u8]) -> [u8; 8] {
+    let mut hasher = FnvHasher::default();
+    hasher.write(bytes);
+
+    hasher.finish().to_le_bytes()
+}
+
+impl Cipher for XorObfuscation {
+    fn encrypt(&self, mut bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let checksum = checksum(&bytes);
+        bytes.extend_from_slice(&checksum);
+        self.apply(&mut bytes);
+
+        Ok(bytes)
+    }
+
+    fn decrypt(&self, mut bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+        if bytes.len() < 8 {
+            return Err(format_err!("Data is too short to contain a checksum"));
+        }
+        self.apply(&mut bytes);
+        let data_len = bytes.len() - 8;
+        if bytes[data_len..] != checksum(&bytes[..data_len]) {
+            return Err(format_err!("Checksum mismatch, the data has been modified"));
+        }
+        bytes.truncate(data_len);
+
+        Ok(bytes)
+    }
+}
+
+/// AES-256-GCM cipher, storing a random nonce in front of every asset.
+#[cfg(feature = "aes_encryption")]
+#[derive(Clone)]
+pub struct AesGcmCipher {
+    cipher: aes_gcm::Aes256Gcm,
+}
+
+#[cfg(feature = "aes_encryption")]
+impl AesGcmCipher {
+    /// Creates a cipher with the given 256-bit key.
+    pub fn new(key: &[u8; 32]) -> Self {
+        use aes_gcm::aead::{generic_array::GenericArray, NewAead};
+
+        AesGcmCipher {
+            cipher: aes_gcm::Aes256Gcm::new(GenericArray::from_slice(key)),
+        }
+    }
+}
+
+#[cfg(feature = "aes_encryption")]
+impl Cipher for AesGcmCipher {
+    fn encrypt(&self, bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+        use aes_gcm::aead::{generic_array::GenericArray, Aead};
+
+        let nonce: [u8; 12] = rand::random();
+        let encrypted = self
+            .cipher
+            .encrypt(GenericArray::from_slice(&nonce), bytes.as_ref())
+            .map_err(|_| format_err!("Encryption failed"))?;
+
+        let mut v = Vec::with_capacity(nonce.len() + encrypted.len());
+        v.extend_from_slice(&nonce);
+        v.extend(encrypted);
+
+        Ok(v)
+    }
+
+    fn decrypt(&self, bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+        use aes_gcm::aead::{generic_array::GenericArray, Aead};
+
+        if bytes.len() < 12 {
+            return Err(format_err!("Data is too short to contain a nonce"));
+        }
+        let (nonce, encrypted) = bytes.split_at(12);
+        self.cipher
+            .decrypt(GenericArray::from_slice(nonce), encrypted)
+            .map_err(|_| format_err!("Decryption failed, the data has been modified"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{source::Source, MemorySource};
+
+    use super::{Cipher, EncryptedSource, XorObfuscation};
+
+    #[test]
+    fn decrypts_assets_of_inner_source() {
+        let cipher = XorObfuscation::new(&b"key"[..]);
+        let mut inner = MemorySource::new();
+        inner.insert("asset", cipher.encrypt(b"data".to_vec()).unwrap());
+
+        let source = EncryptedSource::new(inner, cipher);
+        assert_eq!(b"data".to_vec(), source.load("asset").unwrap());
+    }
+
+    #[test]
+    fn tampered_assets_fail_to_load() {
+        let cipher = XorObfuscation::new(&b"key"[..]);
+        let mut encrypted = cipher.encrypt(b"data".to_vec()).unwrap();
+        encrypted[1] ^= 1;
+        let mut inner = MemorySource::new();
+        inner.insert("asset", encrypted);
+        inner.insert("short", &b"abc"[..]);
+
+        let source = EncryptedSource::new(inner, cipher);
+        assert!(source.load("asset").is_err());
+        assert!(source.load("short").is_err());
+    }
+}
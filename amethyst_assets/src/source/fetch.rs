@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use fnv::FnvHashMap;
+use js_sys::Uint8Array;
+use parking_lot::RwLock;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Response;
+
+use amethyst_error::{format_err, Error, ResultExt};
+
+use crate::{error, source::Source};
+
+/// Source loading assets with the browser's `fetch()`, for `wasm32` targets.
+///
+/// `Source::load` is synchronous, while `fetch()` isn't, so assets have to be fetched
+/// with `prefetch` before they are loaded through the `Loader`. `load` serves
+/// prefetched assets from memory and fails for all others.
+///
+/// Clones share the prefetched assets, so a clone kept around after adding the source
+/// to the `Loader` can be used to prefetch more assets later on.
+///
+/// Assets are never hot reloaded, `modified` always returns `0`.
+#[derive(Clone, Debug)]
+pub struct FetchSource {
+    base_url: String,
+    fetched: Arc<RwLock<FnvHashMap<String, Vec<u8>>>>,
+}
+
+impl FetchSource {
+    /// Creates a source fetching assets relative to `base_url`.
+    pub fn new<S>(base_url: S) -> Self
+    where
+        S: Into<String>,
+    {
+        FetchSource {
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            fetched: Default::default(),
+        }
+    }
+
+    /// Fetches the given paths, making them available to `load` once the returned
+    /// future completes successfully.
+    ///
+    /// The future has to be driven by the browser's event loop, e.g. using
+    /// `wasm_bindgen_futures::spawn_local`.
+    pub async fn prefetch<I, S>(&self, paths: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for path in paths {
+            let path = path.into();
+            let bytes = self
+                .fetch(&path)
+                .await
+                .with_context(|_| format_err!("Failed to fetch {:?}", path))
+                .with_context(|_| error::Error::Source)?;
+            self.fetched.write().insert(path, bytes);
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if `path` has been prefetched.
+    pub fn is_fetched(&self, path: &str) -> bool {
+        self.fetched.read().contains_key(path)
+    }
+
+    async fn fetch(&self, path: &str) -> Result<Vec<u8>, Error> {
+        let url = format!("{}/{}", self.base_url, path.trim_start_matches('/'));
+        let window = web_sys::window().ok_or_else(|| format_err!("No window available"))?;
+        let response = JsFuture::from(window.fetch_with_str(&url))
+            .await
+            .map_err(|e| format_err!("Request for {} failed: {:?}", url, e))?
+            .dyn_into::<Response>()
+            .map_err(|e| format_err!("Unexpected response for {}: {:?}", url, e))?;
+        if !response.ok() {
+            return Err(format_err!(
+                "Unexpected status {} for {}",
+                response.status(),
+                url
+            ));
+        }
+
+        let buffer = response
+            .array_buffer()
+            .map_err(|e| format_err!("Failed to read response for {}: {:?}", url, e))?;
+        let buffer = JsFuture::from(buffer)
+            .await
+            .map_err(|e| format_err!("Failed to read response for {}: {:?}", url, e))?;
+
+        Ok(Uint8Array::new(&buffer).to_vec())
+    }
+
+    fn get<T, F>(&self, path: &str, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&[u8]) -> T,
+    {
+        self.fetched
+            .read()
+            .get(path)
+            .map(|bytes| f(bytes))
+            .ok_or_else(|| format_err!("Asset {:?} has not been prefetched", path))
+            .with_context(|_| error::Error::Source)
+    }
+}
+
+impl Source for FetchSource {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        self.get(path, |_| 0)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        self.get(path, <[u8]>::to_vec)
+    }
+}
@@ -0,0 +1,317 @@
+#[cfg(feature = "async_source")]
+use std::panic::AssertUnwindSafe;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use err_derive::Error;
+use fnv::FnvHashMap;
+#[cfg(feature = "async_source")]
+use futures::future::{BoxFuture, FutureExt};
+use parking_lot::Mutex;
+use reqwest::{
+    blocking::{Client, RequestBuilder, Response},
+    header::{AUTHORIZATION, ETAG, LAST_MODIFIED},
+    StatusCode,
+};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+#[cfg(feature = "async_source")]
+use amethyst_error::format_err;
+use amethyst_error::{Error, ResultExt};
+
+#[cfg(feature = "async_source")]
+use crate::source::AsyncSource;
+use crate::{error, source::Source};
+
+/// Timeout used for requests unless configured otherwise.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors of an `HttpSource`, which are the source of the `Error` returned by
+/// the failing method.
+#[derive(Debug, Error)]
+pub enum HttpError {
+    /// The server responded with `404 Not Found`.
+    #[error(display = "{} was not found", _0)]
+    NotFound(String),
+    /// The server responded with a server error (`5xx`).
+    #[error(display = "Server error {} for {}", _0, _1)]
+    Server(u16, String),
+    /// The server responded with another status than `200 OK`.
+    #[error(display = "Unexpected status {} for {}", _0, _1)]
+    Status(u16, String),
+    /// The request timed out.
+    #[error(display = "Request for {} timed out", _0)]
+    Timeout(String),
+    /// The request could not be performed, e.g. because the server could not be reached.
+    #[error(display = "Request for {} failed: {}", _0, _1)]
+    Request(String, String),
+}
+
+/// Source loading assets over HTTP(S).
+///
+/// The path of an asset is appended to the base url, so with a base url of
+/// `https://cdn.example.com`, `cards/dragon.png` is loaded from
+/// `https://cdn.example.com/cards/dragon.png`. Requests are blocking and
+/// performed on the thread calling the source, which is a worker of the
+/// loader's thread pool. The blocking client is only created by the first
+/// request, as creating it inside a Tokio runtime panics; use `with_client`
+/// to share a client between sources.
+///
+/// With the `async_source` feature it is an `AsyncSource` as well, which doesn't
+/// block threads while waiting for responses. The futures it returns need a Tokio
+/// runtime, which the default executor of the `Loader` doesn't provide, so loading
+/// from it asynchronously requires `Loader::set_executor` spawning the futures on
+/// such a runtime. Futures polled elsewhere fail the load with an error.
+///
+/// `modified` performs a `HEAD` request and derives the modification time
+/// from the `Last-Modified` header, or from changes of the `ETag` header.
+/// If the server sends neither, `0` is returned, which disables hot reloading
+/// for the asset.
+#[derive(Debug)]
+pub struct HttpSource {
+    auth: Option<String>,
+    base_url: String,
+    #[cfg(feature = "async_source")]
+    async_client: reqwest::Client,
+    client: Mutex<Option<Client>>,
+    etags: Mutex<FnvHashMap<String, (String, u64)>>,
+    timeout: Duration,
+}
+
+impl HttpSource {
+    /// Creates a new source for the given base url, with a timeout of 30 seconds.
+    pub fn new<S>(base_url: S) -> Self
+    where
+        S: Into<String>,
+    {
+        HttpSource {
+            auth: None,
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            #[cfg(feature = "async_source")]
+            async_client: reqwest::Client::new(),
+            client: Mutex::new(None),
+            etags: Default::default(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets the timeout for each request.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Uses the given client for blocking requests instead of creating one.
+    pub fn with_client(self, client: Client) -> Self {
+        *self.client.lock() = Some(client);
+        self
+    }
+
+    /// Uses the given client for asynchronous requests instead of a default one.
+    #[cfg(feature = "async_source")]
+    pub fn with_async_client(mut self, client: reqwest::Client) -> Self {
+        self.async_client = client;
+        self
+    }
+
+    /// Sends the given value as `Authorization` header with each request.
+    pub fn with_auth_header<S>(mut self, value: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.auth = Some(value.into());
+        self
+    }
+
+    /// Returns the blocking client, creating it on first use.
+    fn client(&self) -> Client {
+        self.client.lock().get_or_insert_with(Client::new).clone()
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    fn send(&self, request: RequestBuilder, url: &str) -> Result<Response, Error> {
+        let request = match self.auth {
+            Some(ref auth) => request.header(AUTHORIZATION, auth.as_str()),
+            None => request,
+        };
+        let response = request
+            .timeout(self.timeout)
+            .send()
+            .map_err(|e| request_error(e, url))
+            .with_context(|_| error::Error::Source)?;
+
+        match status_error(response.status(), url) {
+            Some(e) => Err(e).with_context(|_| error::Error::Source),
+            None => Ok(response),
+        }
+    }
+
+    /// Turns an `ETag` into a modification time which increases whenever the tag changes.
+    fn etag_modified(&self, path: &str, etag: &str) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(1);
+        let mut etags = self.etags.lock();
+        match etags.get_mut(path) {
+            Some(entry) => {
+                if entry.0 != etag {
+                    *entry = (etag.to_owned(), now.max(entry.1 + 1));
+                }
+                entry.1
+            }
+            None => {
+                etags.insert(path.to_owned(), (etag.to_owned(), now));
+                now
+            }
+        }
+    }
+}
+
+impl Source for HttpSource {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("http_modified_asset");
+
+        let url = self.url(path);
+        let response = self.send(self.client().head(&url), &url)?;
+        let headers = response.headers();
+
+        let last_modified = headers
+            .get(LAST_MODIFIED)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| httpdate::parse_http_date(value).ok())
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok());
+        if let Some(last_modified) = last_modified {
+            return Ok(last_modified.as_secs());
+        }
+
+        Ok(headers
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(|etag| self.etag_modified(path, etag))
+            .unwrap_or(0))
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("http_load_asset");
+
+        let url = self.url(path);
+        let response = self.send(self.client().get(&url), &url)?;
+        let bytes = response
+            .bytes()
+            .map_err(|e| request_error(e, &url))
+            .with_context(|_| error::Error::Source)?;
+
+        Ok(bytes.to_vec())
+    }
+}
+
+#[cfg(feature = "async_source")]
+impl AsyncSource for HttpSource {
+    fn load(&self, path: &str) -> BoxFuture<'static, Result<Vec<u8>, Error>> {
+        let url = self.url(path);
+        let request = self.async_client.get(&url).timeout(self.timeout);
+        let request = match self.auth {
+            Some(ref auth) => request.header(AUTHORIZATION, auth.as_str()),
+            None => request,
+        };
+
+        let response = async move {
+            let response = request
+                .send()
+                .await
+                .map_err(|e| request_error(e, &url))
+                .with_context(|_| error::Error::Source)?;
+            if let Some(e) = status_error(response.status(), &url) {
+                return Err(e).with_context(|_| error::Error::Source);
+            }
+            let bytes = response
+                .bytes()
+                .await
+                .map_err(|e| request_error(e, &url))
+                .with_context(|_| error::Error::Source)?;
+
+            Ok::<_, Error>(bytes.to_vec())
+        };
+
+        // Polling outside of a Tokio runtime panics, which would take down the thread
+        // of the executor, so it is turned into an error of the load instead.
+        AssertUnwindSafe(response)
+            .catch_unwind()
+            .map(|result| {
+                result.unwrap_or_else(|_| {
+                    Err(format_err!(
+                        "HttpSource futures must run on a Tokio runtime, see `Loader::set_executor`"
+                    ))
+                    .with_context(|_| error::Error::Source)
+                })
+            })
+            .boxed()
+    }
+}
+
+fn request_error(e: reqwest::Error, url: &str) -> HttpError {
+    if e.is_timeout() {
+        HttpError::Timeout(url.to_owned())
+    } else {
+        HttpError::Request(url.to_owned(), e.to_string())
+    }
+}
+
+fn status_error(status: StatusCode, url: &str) -> Option<HttpError> {
+    match status {
+        StatusCode::OK => None,
+        StatusCode::NOT_FOUND => Some(HttpError::NotFound(url.to_owned())),
+        s if s.is_server_error() => Some(HttpError::Server(s.as_u16(), url.to_owned())),
+        s => Some(HttpError::Status(s.as_u16(), url.to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use reqwest::StatusCode;
+
+    use super::{status_error, HttpError, HttpSource};
+
+    #[test]
+    fn creates_the_client_on_first_use() {
+        let source = HttpSource::new("https://cdn.example.com");
+        assert!(source.client.lock().is_none());
+    }
+
+    #[test]
+    fn joins_paths_to_base_url() {
+        let source = HttpSource::new("https://cdn.example.com/");
+        assert_eq!(
+            "https://cdn.example.com/cards/dragon.png",
+            source.url("cards/dragon.png")
+        );
+    }
+
+    #[test]
+    fn maps_statuses_to_errors() {
+        assert!(status_error(StatusCode::OK, "").is_none());
+        match status_error(StatusCode::NOT_FOUND, "") {
+            Some(HttpError::NotFound(_)) => {}
+            other => panic!("Expected `NotFound`, got {:?}", other),
+        }
+        match status_error(StatusCode::BAD_GATEWAY, "") {
+            Some(HttpError::Server(502, _)) => {}
+            other => panic!("Expected `Server`, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn etag_changes_increase_modification_time() {
+        let source = HttpSource::new("https://cdn.example.com");
+        let first = source.etag_modified("asset", "\"a\"");
+        assert_eq!(first, source.etag_modified("asset", "\"a\""));
+        assert!(source.etag_modified("asset", "\"b\"") > first);
+    }
+}
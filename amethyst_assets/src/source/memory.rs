@@ -0,0 +1,160 @@
+use std::borrow::Cow;
+
+use fnv::FnvHashMap;
+
+use amethyst_error::{format_err, Error, ResultExt};
+
+use crate::{error, source::Source};
+
+/// Source for assets which are kept in memory, e.g. because they
+/// have been embedded into the binary using `include_bytes!`.
+///
+/// The assets never change, so they are never hot reloaded.
+/// Use the `memory_source!` macro to create one conveniently.
+///
+/// ```rust
+/// # use amethyst_assets::MemorySource;
+/// let mut source = MemorySource::new();
+/// source.insert("text/hello.txt", &b"Hello World!"[..]);
+/// source.insert("text/generated.txt", b"Hello again!".to_vec());
+/// ```
+#[derive(Debug, Default)]
+pub struct MemorySource {
+    assets: FnvHashMap<String, Cow<'static, [u8]>>,
+}
+
+impl MemorySource {
+    /// Creates a new, empty memory source.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds an asset with the given path and contents, replacing any asset
+    /// previously added with the same path.
+    pub fn insert<P, B>(&mut self, path: P, bytes: B)
+    where
+        P: Into<String>,
+        B: Into<Cow<'static, [u8]>>,
+    {
+        self.assets.insert(path.into(), bytes.into());
+    }
+
+    fn get(&self, path: &str) -> Result<&[u8], Error> {
+        self.assets
+            .get(path)
+            .map(|bytes| &**bytes)
+            .ok_or_else(|| format_err!("No asset {:?} in memory source", path))
+            .with_context(|_| error::Error::Source)
+    }
+}
+
+impl Source for MemorySource {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        self.get(path).map(|_| 0)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        self.get(path).map(<[u8]>::to_vec)
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        let dir = path.trim_end_matches('/');
+        let mut entries: Vec<_> = self
+            .assets
+            .keys()
+            .filter(|name| {
+                dir.is_empty() || (name.starts_with(dir) && name[dir.len()..].starts_with('/'))
+            })
+            .cloned()
+            .collect();
+        entries.sort();
+
+        Ok(entries)
+    }
+}
+
+/// Creates a `MemorySource` from a list of paths and their contents.
+///
+/// ```rust
+/// # use amethyst_assets::memory_source;
+/// let source = memory_source! {
+///     "text/hello.txt" => b"Hello World!",
+///     // "mesh.ron" => include_bytes!("../assets/mesh.ron"),
+/// };
+/// ```
+#[macro_export]
+macro_rules! memory_source {
+    ($($path:expr => $bytes:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut source = $crate::MemorySource::new();
+        $(
+            source.insert($path, $bytes as &'static [u8]);
+        )*
+        source
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use crate::source::Source;
+
+    use super::MemorySource;
+
+    #[test]
+    fn loads_inserted_assets() {
+        let source = memory_source! {
+            "a/one" => b"one",
+            "a/b/two" => b"two",
+            "three" => b"three",
+        };
+
+        assert_eq!(b"two".to_vec(), source.load("a/b/two").unwrap());
+        assert_eq!(0, source.modified("three").unwrap());
+        assert!(source.load("missing").is_err());
+        assert_eq!(
+            vec![String::from("a/b/two"), String::from("a/one")],
+            source.list("a").unwrap()
+        );
+    }
+
+    #[test]
+    fn lists_entries_derived_from_the_paths() {
+        let source = memory_source! {
+            "a/one" => b"one",
+            "a/b/two" => b"two",
+            "three" => b"three",
+        };
+
+        let entries = |prefix, recursive| -> Vec<(String, bool)> {
+            source
+                .entries(prefix, recursive)
+                .unwrap()
+                .into_iter()
+                .map(|entry| (entry.path, entry.is_dir))
+                .collect()
+        };
+        assert_eq!(
+            vec![("a/b".to_owned(), true), ("a/one".to_owned(), false)],
+            entries("a", false)
+        );
+        assert_eq!(
+            vec![
+                ("a".to_owned(), true),
+                ("a/b".to_owned(), true),
+                ("a/b/two".to_owned(), false),
+                ("a/one".to_owned(), false),
+                ("three".to_owned(), false),
+            ],
+            entries("", true)
+        );
+    }
+
+    #[test]
+    fn replaces_assets_with_the_same_path() {
+        let mut source = MemorySource::new();
+        source.insert("asset", &b"old"[..]);
+        source.insert("asset", b"new".to_vec());
+
+        assert_eq!(b"new".to_vec(), source.load("asset").unwrap());
+    }
+}
@@ -1,11 +1,75 @@
-use amethyst_error::Error;
+use std::{
+    collections::BTreeSet,
+    io::{Cursor, Read},
+};
 
-pub use self::dir::Directory;
+use amethyst_error::{format_err, Error, ResultExt};
+#[cfg(feature = "async_source")]
+use futures::future::BoxFuture;
+
+use crate::error;
+
+#[cfg(all(target_os = "android", feature = "android_source"))]
+pub use self::apk::ApkSource;
+#[cfg(all(any(target_os = "macos", target_os = "ios"), feature = "bundle_source"))]
+pub use self::bundle::BundleSource;
+pub(crate) use self::bytes::BytesSource;
+#[cfg(any(feature = "zstd_compression", feature = "lz4_compression"))]
+pub use self::compressed::CompressedSource;
+#[cfg(feature = "aes_encryption")]
+pub use self::encrypted::AesGcmCipher;
+#[cfg(all(target_arch = "wasm32", feature = "wasm_source"))]
+pub use self::fetch::FetchSource;
+#[cfg(feature = "http_source")]
+pub use self::http::{HttpError, HttpSource};
+pub(crate) use self::mounted::MountedSource;
+#[cfg(feature = "tar_source")]
+pub use self::tar::TarSource;
+#[cfg(feature = "zip_source")]
+pub use self::zip::ZipSource;
+pub use self::{
+    dir::Directory,
+    encrypted::{encrypt_directory, Cipher, EncryptedSource, XorObfuscation},
+    memory::MemorySource,
+    overlay::OverlaySource,
+};
 
 #[cfg(feature = "profiler")]
 use thread_profiler::profile_scope;
 
+#[cfg(all(target_os = "android", feature = "android_source"))]
+mod apk;
+#[cfg(all(any(target_os = "macos", target_os = "ios"), feature = "bundle_source"))]
+mod bundle;
+mod bytes;
+#[cfg(any(feature = "zstd_compression", feature = "lz4_compression"))]
+mod compressed;
 mod dir;
+mod encrypted;
+#[cfg(all(target_arch = "wasm32", feature = "wasm_source"))]
+mod fetch;
+#[cfg(feature = "http_source")]
+mod http;
+mod memory;
+mod mounted;
+mod overlay;
+#[cfg(feature = "tar_source")]
+mod tar;
+#[cfg(feature = "zip_source")]
+mod zip;
+
+/// An entry of a source, returned by `Source::entries`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceEntry {
+    /// The path of the entry, relative to the root of the source.
+    pub path: String,
+    /// `true` if the entry is a directory.
+    pub is_dir: bool,
+    /// The size of the entry in bytes, `0` for directories.
+    pub size: u64,
+    /// The modification time as seconds since `UNIX_EPOCH`.
+    pub modified: u64,
+}
 
 /// A trait for asset sources, which provides
 /// methods for loading bytes.
@@ -20,6 +84,85 @@ pub trait Source: Send + Sync + 'static {
     /// The id should always use `/` as separator in paths.
     fn load(&self, path: &str) -> Result<Vec<u8>, Error>;
 
+    /// Opens a reader streaming the bytes of the given path, so big assets don't have to
+    /// be buffered entirely before they can be parsed.
+    ///
+    /// The default implementation wraps the result of `load` and thus still buffers it.
+    fn open(&self, path: &str) -> Result<Box<dyn Read + Send>, Error> {
+        Ok(Box::new(Cursor::new(self.load(path)?)))
+    }
+
+    /// Lists all entries below the directory `path`, recursively.
+    ///
+    /// The returned paths are relative to the root of the source (so they can
+    /// be passed to `load`) and use `/` as separator. Directories themselves are
+    /// not part of the list. Sources which can't enumerate their contents return
+    /// an `Unsupported` error, which is what the default implementation does.
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        Err(format_err!("Source does not support listing {:?}", path))
+            .with_context(|_| error::Error::Unsupported)
+    }
+
+    /// Returns the entries below the directory `prefix`, including directories.
+    ///
+    /// If `recursive` is `false`, only the direct children of `prefix` are returned.
+    /// The paths are relative to the root of the source (so they can be passed to
+    /// `load`) and use `/` as separator.
+    ///
+    /// The default implementation is built on `list`: directories are derived from the
+    /// listed paths, files get their modification time from `modified` and a size of
+    /// `0`, as it is unknown without loading them.
+    fn entries(&self, prefix: &str, recursive: bool) -> Result<Vec<SourceEntry>, Error> {
+        let prefix = prefix.trim_end_matches('/');
+        let mut dirs = BTreeSet::new();
+        let mut entries = Vec::new();
+        for path in self.list(prefix)? {
+            let relative = match prefix {
+                "" => &path[..],
+                prefix => path[prefix.len()..].trim_start_matches('/'),
+            };
+            let start = path.len() - relative.len();
+            let depth = relative.matches('/').count();
+            let shown = if recursive { depth } else { 1 };
+            for (i, _) in relative.match_indices('/').take(shown) {
+                dirs.insert(path[..start + i].to_owned());
+            }
+            if recursive || depth == 0 {
+                let modified = self.modified(&path).unwrap_or(0);
+                entries.push(SourceEntry {
+                    path,
+                    is_dir: false,
+                    size: 0,
+                    modified,
+                });
+            }
+        }
+        entries.extend(dirs.into_iter().map(|path| SourceEntry {
+            path,
+            is_dir: true,
+            size: 0,
+            modified: 0,
+        }));
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(entries)
+    }
+
+    /// Returns `false` if this source can't be used anymore, e.g. because it
+    /// has been removed from the `Loader`. Defaults to `true`.
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    /// Returns where the bytes of `path` are actually read from, e.g. the file a
+    /// `Directory` reads, for diagnostics like `AssetStorage::provenance`.
+    ///
+    /// Defaults to `None`, meaning the source can't tell.
+    fn resolve(&self, path: &str) -> Option<String> {
+        let _ = path;
+        None
+    }
+
     /// Returns both the result of `load` and `modified` as a tuple.
     /// There's a default implementation which just calls both methods,
     /// but you may be able to provide a more optimized version yourself.
@@ -33,3 +176,16 @@ pub trait Source: Send + Sync + 'static {
         Ok((b, m))
     }
 }
+
+/// A source which loads bytes asynchronously, e.g. over the network.
+///
+/// Unlike a `Source`, it doesn't occupy a thread while waiting for the bytes, so
+/// the number of loads in flight isn't limited by the number of threads. Use it
+/// with `Loader::add_async_source` and `Loader::load_from_async_source`.
+#[cfg(feature = "async_source")]
+pub trait AsyncSource: Send + Sync + 'static {
+    /// Returns a future resolving to the bytes of the given path.
+    ///
+    /// The id should always use `/` as separator in paths.
+    fn load(&self, path: &str) -> BoxFuture<'static, Result<Vec<u8>, Error>>;
+}
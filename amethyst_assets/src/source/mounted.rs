@@ -0,0 +1,78 @@
+use std::{
+    any::Any,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use amethyst_error::Error;
+
+use crate::source::{Directory, Source, SourceEntry};
+
+/// A source registered with the `Loader`, which is marked as
+/// unavailable once it gets removed from the `Loader` again.
+pub(crate) struct MountedSource {
+    mounted: AtomicBool,
+    /// The root of the source if it is a `Directory`, see `Loader::directories`.
+    root: Option<PathBuf>,
+    source: Box<dyn Source>,
+}
+
+impl MountedSource {
+    pub(crate) fn new<S>(source: S) -> Self
+    where
+        S: Source,
+    {
+        let root = (&source as &dyn Any)
+            .downcast_ref::<Directory>()
+            .map(|dir| dir.root().to_owned());
+
+        MountedSource {
+            mounted: AtomicBool::new(true),
+            root,
+            source: Box::new(source),
+        }
+    }
+
+    pub(crate) fn root(&self) -> Option<&Path> {
+        self.root.as_ref().map(PathBuf::as_path)
+    }
+
+    pub(crate) fn unmount(&self) {
+        self.mounted.store(false, Ordering::Relaxed);
+    }
+}
+
+impl Source for MountedSource {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        self.source.modified(path)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        self.source.load(path)
+    }
+
+    fn open(&self, path: &str) -> Result<Box<dyn Read + Send>, Error> {
+        self.source.open(path)
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        self.source.list(path)
+    }
+
+    fn entries(&self, prefix: &str, recursive: bool) -> Result<Vec<SourceEntry>, Error> {
+        self.source.entries(prefix, recursive)
+    }
+
+    fn load_with_metadata(&self, path: &str) -> Result<(Vec<u8>, u64), Error> {
+        self.source.load_with_metadata(path)
+    }
+
+    fn is_available(&self) -> bool {
+        self.mounted.load(Ordering::Relaxed) && self.source.is_available()
+    }
+
+    fn resolve(&self, path: &str) -> Option<String> {
+        self.source.resolve(path)
+    }
+}
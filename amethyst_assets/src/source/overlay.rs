@@ -0,0 +1,253 @@
+use std::{io::Read, sync::Arc};
+
+use parking_lot::RwLock;
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use amethyst_error::{format_err, Error};
+
+use crate::{error, source::Source};
+
+/// Source which chains multiple named layers, e.g. a mod directory on top of the
+/// base game's assets.
+///
+/// `load` returns the asset from the first layer which has it. `modified` reports
+/// the newest modification time of all layers having the asset, so an asset
+/// overridden by a higher layer is hot reloaded.
+///
+/// Clones share their layers, so a clone kept around after adding the source to the
+/// `Loader` can be used to add, remove or reorder layers at runtime.
+///
+/// ```rust
+/// # use amethyst_assets::{Directory, OverlaySource};
+/// let source = OverlaySource::new()
+///     .with_layer("mod", Directory::new("mods/my_mod"))
+///     .with_layer("base", Directory::new("assets"));
+/// ```
+#[derive(Clone, Default)]
+pub struct OverlaySource {
+    layers: Arc<RwLock<Vec<(String, Arc<dyn Source>)>>>,
+}
+
+impl OverlaySource {
+    /// Creates a new overlay without any layers.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a layer below all existing layers.
+    pub fn with_layer<N, S>(self, name: N, source: S) -> Self
+    where
+        N: Into<String>,
+        S: Source,
+    {
+        let index = self.layers.read().len();
+        self.insert_layer(index, name, source);
+        self
+    }
+
+    /// Inserts a layer at `index`, where index `0` is tried first.
+    /// A layer with the same name is replaced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of layers (without the replaced one).
+    pub fn insert_layer<N, S>(&self, index: usize, name: N, source: S)
+    where
+        N: Into<String>,
+        S: Source,
+    {
+        let name = name.into();
+        let mut layers = self.layers.write();
+        layers.retain(|&(ref n, _)| *n != name);
+        layers.insert(index, (name, Arc::new(source)));
+    }
+
+    /// Removes the layer with the given name, returning `false` if there was none.
+    pub fn remove_layer(&self, name: &str) -> bool {
+        let mut layers = self.layers.write();
+        let len = layers.len();
+        layers.retain(|&(ref n, _)| n != name);
+
+        layers.len() != len
+    }
+
+    /// Moves the layer with the given name to `index`, returning `false` if there is
+    /// no such layer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn move_layer(&self, name: &str, index: usize) -> bool {
+        let mut layers = self.layers.write();
+        match layers.iter().position(|&(ref n, _)| n == name) {
+            Some(pos) => {
+                let layer = layers.remove(pos);
+                layers.insert(index, layer);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the names of all layers, in the order they are tried.
+    pub fn layers(&self) -> Vec<String> {
+        self.layers.read().iter().map(|(n, _)| n.clone()).collect()
+    }
+
+    /// Tries `f` with all layers in order, returning the first success.
+    fn first<T, F>(&self, path: &str, mut f: F) -> Result<T, Error>
+    where
+        F: FnMut(&dyn Source) -> Result<T, Error>,
+    {
+        let layers = self.layers.read();
+        let mut last_err = None;
+        for (_, source) in layers.iter() {
+            match f(&**source) {
+                Ok(value) => return Ok(value),
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        Err(self.not_found(path, &layers, last_err))
+    }
+
+    fn not_found(
+        &self,
+        path: &str,
+        layers: &[(String, Arc<dyn Source>)],
+        last_err: Option<Error>,
+    ) -> Error {
+        let names: Vec<_> = layers.iter().map(|(n, _)| n.as_str()).collect();
+        let e = format_err!("No layer could provide {:?} (tried {:?})", path, names);
+        let e = match last_err {
+            Some(last_err) => e.with_source(last_err),
+            None => e,
+        };
+
+        Error::from(error::Error::Source).with_source(e)
+    }
+}
+
+impl Source for OverlaySource {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("overlay_modified_asset");
+
+        let layers = self.layers.read();
+        let mut newest = None;
+        let mut last_err = None;
+        for (_, source) in layers.iter() {
+            match source.modified(path) {
+                Ok(modified) => newest = newest.max(Some(modified)),
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        newest.ok_or_else(|| self.not_found(path, &layers, last_err))
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("overlay_load_asset");
+
+        self.first(path, |source| source.load(path))
+    }
+
+    fn open(&self, path: &str) -> Result<Box<dyn Read + Send>, Error> {
+        self.first(path, |source| source.open(path))
+    }
+
+    fn load_with_metadata(&self, path: &str) -> Result<(Vec<u8>, u64), Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("overlay_load_asset_with_metadata");
+
+        let bytes = self.load(path)?;
+        let modified = self.modified(path)?;
+
+        Ok((bytes, modified))
+    }
+
+    fn resolve(&self, path: &str) -> Option<String> {
+        let layers = self.layers.read();
+        let (name, source) = layers
+            .iter()
+            .find(|(_, source)| source.modified(path).is_ok())?;
+        let resolved = source.resolve(path).unwrap_or_else(|| path.to_owned());
+
+        Some(format!("{} (layer {:?})", resolved, name))
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        let layers = self.layers.read();
+        let mut entries = Vec::new();
+        let mut listed = false;
+        let mut last_err = None;
+        for (_, source) in layers.iter() {
+            match source.list(path) {
+                Ok(list) => {
+                    listed = true;
+                    entries.extend(list);
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        if !listed {
+            return Err(self.not_found(path, &layers, last_err));
+        }
+        entries.sort();
+        entries.dedup();
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{source::Source, MemorySource};
+
+    use super::OverlaySource;
+
+    fn layer(path: &str, bytes: &'static [u8]) -> MemorySource {
+        let mut source = MemorySource::new();
+        source.insert(path, bytes);
+        source
+    }
+
+    #[test]
+    fn loads_from_first_layer_having_the_asset() {
+        let mut base = layer("shared", b"base");
+        base.insert("base_only", &b"base"[..]);
+        let overlay = OverlaySource::new()
+            .with_layer("mod", layer("shared", b"mod"))
+            .with_layer("base", base);
+
+        assert_eq!(b"mod".to_vec(), overlay.load("shared").unwrap());
+        assert_eq!(b"base".to_vec(), overlay.load("base_only").unwrap());
+        assert_eq!(
+            vec![String::from("base_only"), String::from("shared")],
+            overlay.list("").unwrap()
+        );
+
+        let err = overlay.load("missing").unwrap_err();
+        assert!(err
+            .causes()
+            .any(|cause| cause.to_string().contains("[\"mod\", \"base\"]")));
+    }
+
+    #[test]
+    fn layers_can_be_reordered_at_runtime() {
+        let overlay = OverlaySource::new()
+            .with_layer("mod", layer("shared", b"mod"))
+            .with_layer("base", layer("shared", b"base"));
+        let handle = overlay.clone();
+
+        assert!(handle.move_layer("base", 0));
+        assert_eq!(b"base".to_vec(), overlay.load("shared").unwrap());
+        assert!(handle.remove_layer("base"));
+        assert!(!handle.remove_layer("base"));
+        assert_eq!(vec![String::from("mod")], overlay.layers());
+        assert_eq!(b"mod".to_vec(), overlay.load("shared").unwrap());
+    }
+}
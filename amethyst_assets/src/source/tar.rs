@@ -0,0 +1,346 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use ::tar::Archive;
+use flate2::read::GzDecoder;
+use fnv::FnvHashMap;
+use parking_lot::Mutex;
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use amethyst_error::{format_err, Error, ResultExt};
+
+use crate::{error, source::Source};
+
+/// Number of decompressed entries of a gzipped archive which are kept around by default.
+const DEFAULT_CACHE_CAPACITY: usize = 16;
+
+/// Tar archive source, supporting both plain `.tar` and gzipped `.tar.gz` archives.
+///
+/// The archive is indexed once when the source is created (and again whenever
+/// the archive changes on disk). Entries of a plain tar are read by seeking to
+/// their offset, whereas a gzipped tar has to be decompressed up to the
+/// requested entry; the most recently loaded entries of such an archive are
+/// kept in a bounded cache. `modified` reports the modification time of the
+/// archive for all entries.
+#[derive(Debug)]
+pub struct TarSource {
+    loc: PathBuf,
+    gzipped: bool,
+    index: Mutex<Index>,
+    cache: Mutex<VecDeque<(String, Vec<u8>)>>,
+    cache_capacity: usize,
+}
+
+#[derive(Debug)]
+struct Index {
+    entries: FnvHashMap<String, Entry>,
+    modified: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    offset: u64,
+    len: u64,
+    is_dir: bool,
+}
+
+impl TarSource {
+    /// Opens the tar archive at `loc` and indexes its entries.
+    ///
+    /// Whether the archive is gzipped is detected from its contents.
+    pub fn new<P>(loc: P) -> Result<Self, Error>
+    where
+        P: Into<PathBuf>,
+    {
+        Self::with_cache_capacity(loc, DEFAULT_CACHE_CAPACITY)
+    }
+
+    /// Like `new`, but keeps up to `capacity` decompressed entries of a gzipped
+    /// archive in memory.
+    pub fn with_cache_capacity<P>(loc: P, capacity: usize) -> Result<Self, Error>
+    where
+        P: Into<PathBuf>,
+    {
+        let loc = loc.into();
+        let mut magic = [0; 2];
+        let gzipped = open(&loc)?.read_exact(&mut magic).is_ok() && magic == [0x1f, 0x8b];
+        let index = Index::read(&loc, gzipped)?;
+
+        Ok(TarSource {
+            loc,
+            gzipped,
+            index: Mutex::new(index),
+            cache: Mutex::new(VecDeque::with_capacity(capacity)),
+            cache_capacity: capacity,
+        })
+    }
+
+    fn entry(&self, path: &str) -> Result<Entry, Error> {
+        let mut index = self.index.lock();
+        if modified(&self.loc)? != index.modified {
+            *index = Index::read(&self.loc, self.gzipped)?;
+            self.cache.lock().clear();
+        }
+
+        match index.entries.get(path) {
+            Some(entry) if entry.is_dir => Err(format_err!(
+                "{:?} in archive {:?} is a directory",
+                path,
+                self.loc
+            ))
+            .with_context(|_| error::Error::Source),
+            Some(&entry) => Ok(entry),
+            None => Err(format_err!("No entry {:?} in archive {:?}", path, self.loc))
+                .with_context(|_| error::Error::Source),
+        }
+    }
+
+    fn read_gzipped(&self, path: &str, entry: Entry) -> Result<Vec<u8>, Error> {
+        {
+            let mut cache = self.cache.lock();
+            if let Some(pos) = cache.iter().position(|(name, _)| name == path) {
+                let cached = cache.remove(pos).expect("Position was just found");
+                let bytes = cached.1.clone();
+                cache.push_front(cached);
+                return Ok(bytes);
+            }
+        }
+
+        let mut decoder = GzDecoder::new(open(&self.loc)?);
+        io::copy(&mut (&mut decoder).take(entry.offset), &mut io::sink())
+            .with_context(|_| format_err!("Failed to decompress archive {:?}", self.loc))
+            .with_context(|_| error::Error::Source)?;
+        let bytes = read_entry(decoder, path, entry)?;
+
+        if self.cache_capacity > 0 {
+            let mut cache = self.cache.lock();
+            cache.truncate(self.cache_capacity - 1);
+            cache.push_front((path.to_owned(), bytes.clone()));
+        }
+
+        Ok(bytes)
+    }
+}
+
+impl Index {
+    fn read(loc: &Path, gzipped: bool) -> Result<Self, Error> {
+        let modified = modified(loc)?;
+        let file = open(loc)?;
+        let entries = if gzipped {
+            Self::entries(Archive::new(GzDecoder::new(file)), loc)?
+        } else {
+            Self::entries(Archive::new(file), loc)?
+        };
+
+        Ok(Index { entries, modified })
+    }
+
+    fn entries<R: Read>(
+        mut archive: Archive<R>,
+        loc: &Path,
+    ) -> Result<FnvHashMap<String, Entry>, Error> {
+        let mut entries = FnvHashMap::default();
+        let read = archive
+            .entries()
+            .with_context(|_| format_err!("Failed to read archive {:?}", loc))
+            .with_context(|_| error::Error::Source)?;
+        for entry in read {
+            let entry = entry
+                .with_context(|_| format_err!("Failed to read entry of {:?}", loc))
+                .with_context(|_| error::Error::Source)?;
+            let name = match entry.path() {
+                Ok(path) => match path.to_str() {
+                    Some(name) => normalize(name),
+                    // Can't be loaded using a `&str` path anyways.
+                    None => continue,
+                },
+                Err(_) => continue,
+            };
+            if name.is_empty() {
+                continue;
+            }
+            entries.insert(
+                name,
+                Entry {
+                    offset: entry.raw_file_position(),
+                    len: entry.size(),
+                    is_dir: entry.header().entry_type().is_dir(),
+                },
+            );
+        }
+
+        Ok(entries)
+    }
+}
+
+impl Source for TarSource {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("tar_modified_asset");
+
+        if !self.index.lock().entries.contains_key(path) {
+            return Err(format_err!("No entry {:?} in archive {:?}", path, self.loc))
+                .with_context(|_| error::Error::Source);
+        }
+
+        modified(&self.loc)
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("tar_list_assets");
+
+        let dir = path.trim_end_matches('/');
+        let index = self.index.lock();
+        let mut entries: Vec<String> = index
+            .entries
+            .iter()
+            .filter(|(_, entry)| !entry.is_dir)
+            .map(|(name, _)| name)
+            .filter(|name| {
+                dir.is_empty() || (name.starts_with(dir) && name[dir.len()..].starts_with('/'))
+            })
+            .cloned()
+            .collect();
+        entries.sort();
+
+        Ok(entries)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("tar_load_asset");
+
+        let entry = self.entry(path)?;
+        if self.gzipped {
+            return self.read_gzipped(path, entry);
+        }
+
+        let mut file = open(&self.loc)?;
+        file.seek(SeekFrom::Start(entry.offset))
+            .with_context(|_| format_err!("Failed to seek in archive {:?}", self.loc))
+            .with_context(|_| error::Error::Source)?;
+
+        read_entry(file, path, entry)
+    }
+}
+
+fn open(loc: &Path) -> Result<File, Error> {
+    File::open(loc)
+        .with_context(|_| format_err!("Failed to open archive {:?}", loc))
+        .with_context(|_| error::Error::Source)
+}
+
+fn read_entry<R: Read>(reader: R, path: &str, entry: Entry) -> Result<Vec<u8>, Error> {
+    let mut v = Vec::with_capacity(entry.len as usize);
+    reader
+        .take(entry.len)
+        .read_to_end(&mut v)
+        .with_context(|_| format_err!("Failed to read entry {:?}", path))
+        .with_context(|_| error::Error::Source)?;
+
+    if v.len() as u64 != entry.len {
+        return Err(format_err!("Entry {:?} is truncated", path))
+            .with_context(|_| error::Error::Source);
+    }
+
+    Ok(v)
+}
+
+fn modified(loc: &Path) -> Result<u64, Error> {
+    use std::fs::metadata;
+
+    metadata(loc)
+        .with_context(|_| format_err!("Failed to fetch metadata for {:?}", loc))?
+        .modified()
+        .with_context(|_| format_err!("Could not get modification time"))?
+        .duration_since(UNIX_EPOCH)
+        .with_context(|_| {
+            format_err!("Anomalies with the system clock caused `duration_since` to fail")
+        })
+        .map(|d| d.as_secs())
+}
+
+/// Tar entries are commonly stored as `./path`, directories with a trailing `/`.
+fn normalize(name: &str) -> String {
+    let name = name.replace('\\', "/");
+    let name = name.trim_start_matches("./").trim_matches('/');
+
+    name.to_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs::File, io::Write, path::Path};
+
+    use ::tar::{Builder, EntryType, Header};
+    use flate2::{write::GzEncoder, Compression};
+
+    use crate::source::Source;
+
+    use super::TarSource;
+
+    fn build<W: Write>(writer: W) -> W {
+        let mut builder = Builder::new(writer);
+
+        let mut header = Header::new_gnu();
+        header.set_entry_type(EntryType::Directory);
+        header.set_size(0);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "subdir/", &[][..])
+            .unwrap();
+
+        for (path, data) in &[
+            ("subdir/first", &b"first"[..]),
+            ("subdir/second", b"second"),
+        ] {
+            let mut header = Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_cksum();
+            builder.append_data(&mut header, path, *data).unwrap();
+        }
+
+        builder.into_inner().unwrap()
+    }
+
+    fn check(path: &Path) {
+        let source = TarSource::new(path).expect("Failed to open archive");
+        assert_eq!(b"second".to_vec(), source.load("subdir/second").unwrap());
+        assert_eq!(b"first".to_vec(), source.load("subdir/first").unwrap());
+        assert_eq!(
+            vec![String::from("subdir/first"), String::from("subdir/second")],
+            source.list("").unwrap()
+        );
+        let err = source.load("subdir").unwrap_err();
+        assert!(err
+            .causes()
+            .any(|cause| cause.to_string().contains("is a directory")));
+    }
+
+    #[test]
+    fn loads_from_plain_and_gzipped_archives() {
+        let plain = std::env::temp_dir().join("amethyst_assets_tar_source_test.tar");
+        build(File::create(&plain).unwrap());
+        check(&plain);
+
+        let gzipped = std::env::temp_dir().join("amethyst_assets_tar_source_test.tar.gz");
+        build(GzEncoder::new(
+            File::create(&gzipped).unwrap(),
+            Compression::default(),
+        ))
+        .finish()
+        .unwrap();
+        check(&gzipped);
+
+        let _ = std::fs::remove_file(&plain);
+        let _ = std::fs::remove_file(&gzipped);
+    }
+}
@@ -0,0 +1,216 @@
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use ::zip::ZipArchive;
+use fnv::FnvHashMap;
+use parking_lot::Mutex;
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use amethyst_error::{format_err, Error, ResultExt};
+
+use crate::{error, source::Source};
+
+/// Zip archive source.
+///
+/// The archive is opened once and its entries are indexed up front; every
+/// `load` decompresses the requested entry. Since the whole archive is a single
+/// file, `modified` reports the modification time of the archive for all
+/// entries, so hot reloading works at the granularity of the pack. If the
+/// archive has been replaced on disk, it is re-opened on the next `load`.
+#[derive(Debug)]
+pub struct ZipSource {
+    loc: PathBuf,
+    archive: Mutex<Archive>,
+}
+
+#[derive(Debug)]
+struct Archive {
+    zip: ZipArchive<File>,
+    entries: FnvHashMap<String, usize>,
+    modified: u64,
+}
+
+impl ZipSource {
+    /// Opens the zip archive at `loc` and builds an index of its entries.
+    pub fn new<P>(loc: P) -> Result<Self, Error>
+    where
+        P: Into<PathBuf>,
+    {
+        let loc = loc.into();
+        let archive = Archive::open(&loc)?;
+
+        Ok(ZipSource {
+            loc,
+            archive: Mutex::new(archive),
+        })
+    }
+}
+
+impl Archive {
+    fn open(loc: &Path) -> Result<Self, Error> {
+        let modified = modified(loc)?;
+        let file = File::open(loc)
+            .with_context(|_| format_err!("Failed to open archive {:?}", loc))
+            .with_context(|_| error::Error::Source)?;
+        let mut zip = ZipArchive::new(file)
+            .with_context(|_| format_err!("Failed to read archive {:?}", loc))
+            .with_context(|_| error::Error::Source)?;
+
+        let mut entries = FnvHashMap::default();
+        for i in 0..zip.len() {
+            let entry = zip
+                .by_index(i)
+                .with_context(|_| format_err!("Failed to read entry {} of {:?}", i, loc))
+                .with_context(|_| error::Error::Source)?;
+            if entry.name().ends_with('/') || entry.name().ends_with('\\') {
+                continue;
+            }
+            entries.insert(normalize(entry.name()), i);
+        }
+
+        Ok(Archive {
+            zip,
+            entries,
+            modified,
+        })
+    }
+}
+
+impl Source for ZipSource {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("zip_modified_asset");
+
+        if !self.archive.lock().entries.contains_key(path) {
+            return Err(format_err!("No entry {:?} in archive {:?}", path, self.loc))
+                .with_context(|_| error::Error::Source);
+        }
+
+        modified(&self.loc)
+    }
+
+    fn list(&self, path: &str) -> Result<Vec<String>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("zip_list_assets");
+
+        let dir = path.trim_end_matches('/');
+        let archive = self.archive.lock();
+        let mut entries: Vec<String> = archive
+            .entries
+            .keys()
+            .filter(|entry| {
+                dir.is_empty() || (entry.starts_with(dir) && entry[dir.len()..].starts_with('/'))
+            })
+            .cloned()
+            .collect();
+        entries.sort();
+
+        Ok(entries)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("zip_load_asset");
+
+        let mut archive = self.archive.lock();
+        if modified(&self.loc)? != archive.modified {
+            *archive = Archive::open(&self.loc)?;
+        }
+
+        let index = match archive.entries.get(path) {
+            Some(&index) => index,
+            None => {
+                return Err(format_err!("No entry {:?} in archive {:?}", path, self.loc))
+                    .with_context(|_| error::Error::Source);
+            }
+        };
+        let mut entry = archive
+            .zip
+            .by_index(index)
+            .with_context(|_| format_err!("Failed to read entry {:?} of {:?}", path, self.loc))
+            .with_context(|_| error::Error::Source)?;
+
+        let mut v = Vec::with_capacity(entry.size() as usize);
+        entry
+            .read_to_end(&mut v)
+            .with_context(|_| format_err!("Failed to decompress entry {:?}", path))
+            .with_context(|_| error::Error::Source)?;
+
+        Ok(v)
+    }
+}
+
+fn modified(loc: &Path) -> Result<u64, Error> {
+    use std::fs::metadata;
+
+    metadata(loc)
+        .with_context(|_| format_err!("Failed to fetch metadata for {:?}", loc))?
+        .modified()
+        .with_context(|_| format_err!("Could not get modification time"))?
+        .duration_since(UNIX_EPOCH)
+        .with_context(|_| {
+            format_err!("Anomalies with the system clock caused `duration_since` to fail")
+        })
+        .map(|d| d.as_secs())
+}
+
+/// Archives created on Windows may use `\` as separator.
+fn normalize(name: &str) -> String {
+    name.replace('\\', "/").trim_start_matches('/').to_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use ::zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+    use crate::source::Source;
+
+    use super::ZipSource;
+
+    #[test]
+    fn loads_stored_and_deflated_entries() {
+        let path = std::env::temp_dir().join("amethyst_assets_zip_source_test.zip");
+        {
+            let file = std::fs::File::create(&path).expect("Failed to create archive");
+            let mut zip = ZipWriter::new(file);
+            zip.start_file(
+                "subdir/stored",
+                FileOptions::default().compression_method(CompressionMethod::Stored),
+            )
+            .unwrap();
+            zip.write_all(b"stored").unwrap();
+            zip.start_file(
+                "subdir\\deflated",
+                FileOptions::default().compression_method(CompressionMethod::Deflated),
+            )
+            .unwrap();
+            zip.write_all(b"deflated").unwrap();
+            zip.finish().unwrap();
+        }
+
+        let source = ZipSource::new(&path).expect("Failed to open archive");
+        assert_eq!(b"stored".to_vec(), source.load("subdir/stored").unwrap());
+        assert_eq!(
+            b"deflated".to_vec(),
+            source.load("subdir/deflated").unwrap()
+        );
+        assert_eq!(
+            vec![
+                String::from("subdir/deflated"),
+                String::from("subdir/stored")
+            ],
+            source.list("subdir").unwrap()
+        );
+        assert!(source.load("missing").is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,34 +1,45 @@
 use std::{
+    fmt,
     marker::PhantomData,
+    panic::{self, AssertUnwindSafe},
     sync::{
-        atomic::{AtomicUsize, Ordering},
+        atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc, Weak,
     },
+    time::{Duration, Instant, SystemTime},
 };
 
 use crossbeam_queue::SegQueue;
 use derivative::Derivative;
-use hibitset::BitSet;
+use fnv::{FnvHashMap, FnvHashSet};
+use hibitset::{BitSet, BitSetLike};
 use log::{debug, error, trace, warn};
+use parking_lot::Mutex;
 use rayon::ThreadPool;
 
 use amethyst_core::{
     ecs::{
-        prelude::{Component, Read, ReadExpect, System, VecStorage, Write},
+        prelude::{Component, Read, ReadExpect, Resources, System, VecStorage, Write},
         storage::UnprotectedStorage,
     },
+    shrev::EventChannel,
     Time,
 };
-use amethyst_error::{Error, ResultExt};
+use amethyst_error::{format_err, Error, ResultExt};
 
 #[cfg(feature = "profiler")]
 use thread_profiler::profile_scope;
 
 use crate::{
-    asset::{Asset, FormatValue, ProcessableAsset},
-    error,
-    progress::Tracker,
-    reload::{HotReloadStrategy, Reload},
+    asset::{Asset, Format, FormatValue, ProcessableAsset},
+    error::{self, ErrorLocation, GetError, LoadStage, ProcessingError},
+    io::IoPool,
+    loader::{LoadPriority, LoadToken, Loader},
+    progress::{AssetProgressEvent, AssetProgressOutcome, Tracker},
+    reload::{
+        HotReloadEvent, HotReloadStrategy, HotReloadStrategyFor, Reload, ReloadOutcome, Reloaded,
+    },
+    source::BytesSource,
 };
 
 /// An `Allocator`, holding a counter for producing unique IDs.
@@ -49,13 +60,145 @@ impl Allocator {
 pub struct AssetStorage<A: Asset> {
     assets: VecStorage<(A, u32)>,
     bitset: BitSet,
+    catch_panics: bool,
+    changed_files: Option<FnvHashSet<String>>,
+    deadlines: Mutex<FnvHashMap<u32, Deadline>>,
+    delayed: Vec<(u64, Processed<A>)>,
+    delays: FnvHashMap<u32, u64>,
+    epoch: AtomicU64,
+    forced_reloads: FnvHashSet<u32>,
+    frame_number: u64,
     handles: Vec<Handle<A>>,
     handle_alloc: Allocator,
-    pub(crate) processed: Arc<SegQueue<Processed<A>>>,
+    paused: bool,
+    held: Vec<Processed<A>>,
+    idle_gc: bool,
+    io_pool: Option<Arc<IoPool>>,
+    load_started: FnvHashMap<u32, Instant>,
+    load_times: FnvHashMap<u32, Duration>,
+    max_concurrent_reloads: Option<usize>,
+    name_normalizer: Option<NameNormalizer>,
+    names: FnvHashMap<String, WeakHandle<A>>,
+    ordered: bool,
+    pending: Mutex<FnvHashSet<u32>>,
+    pending_stages: FnvHashMap<u32, usize>,
+    pinned: FnvHashMap<u32, Handle<A>>,
+    processing_failed: Option<ProcessingFailed>,
+    provenances: FnvHashMap<u32, Arc<Provenance>>,
+    pub(crate) processed: Arc<ProcessedQueue<A>>,
+    progress_sink: Option<ProgressSink>,
+    quiet_since: FnvHashMap<u32, QuietPeriod>,
+    reload_events: Option<EventChannel<HotReloadEvent<A>>>,
+    reload_failures: FnvHashMap<u32, ReloadFailure>,
+    reload_finished: Option<ReloadFinished>,
+    reload_pool: Option<Arc<ThreadPool>>,
+    recycle_policy: RecyclePolicy,
+    recycling: Vec<(u64, u32)>,
+    reload_started: Option<ReloadStarted>,
     reloads: Vec<(WeakHandle<A>, Box<dyn Reload<A::Data>>)>,
+    reloads_deferred: bool,
+    pub(crate) reloads_in_flight: AtomicUsize,
+    skip_identical: Option<fn(&A, &A) -> bool>,
+    storage_id: u64,
+    timed_out: FnvHashSet<u32>,
+    transient_loads: bool,
+    unchanged_reloads: Arc<SegQueue<(WeakHandle<A>, Box<dyn Reload<A::Data>>)>>,
     unused_handles: SegQueue<Handle<A>>,
 }
 
+/// The id of the next storage, which tags its handles in debug builds. `0` means untagged.
+static NEXT_STORAGE_ID: AtomicU64 = AtomicU64::new(1);
+
+type NameNormalizer = Box<dyn Fn(&str) -> String + Send + Sync>;
+type ProcessingFailed = Box<dyn FnMut(&ProcessingError) + Send + Sync>;
+type ProgressSink = Box<dyn FnMut(AssetProgressEvent) + Send + Sync>;
+type TrackerSlot = Arc<Mutex<Option<Box<dyn Tracker>>>>;
+type ReloadFinished = Box<dyn FnMut(u32, bool) + Send + Sync>;
+type ReloadStarted = Box<dyn FnMut(u32) + Send + Sync>;
+
+/// Describes how an asset will be hot reloaded, see `AssetStorage::reload_info`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReloadInfo {
+    /// The name of the asset which will be reloaded.
+    pub name: String,
+    /// The name of the format the asset will be reloaded with.
+    pub format: String,
+}
+
+/// Where the data of a loaded asset came from, see `AssetStorage::provenance`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Provenance {
+    /// The name of the source the asset was read from, `""` for the default source.
+    pub source: String,
+    /// The path the source resolved the name of the asset to, see `Source::resolve`,
+    /// or the name itself if the source can't tell.
+    pub path: String,
+    /// The number of bytes read for the asset, `None` if the format didn't read it
+    /// with `Source::load`.
+    pub len: Option<u64>,
+    /// When the data of the asset was read.
+    pub loaded_at: SystemTime,
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match self.source.as_str() {
+            "" => "[default source]",
+            other => other,
+        };
+        write!(f, "{} (source {:?}", self.path, source)?;
+        if let Some(len) = self.len {
+            write!(f, ", {} bytes", len)?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// An asset which currently fails to hot reload, see `AssetStorage::reload_failures`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReloadFailure {
+    /// The name of the asset.
+    pub name: String,
+    /// The error of the last failed reload, followed by its causes.
+    pub error: String,
+    /// When the first of the failed reloads since the last successful one finished.
+    pub first_failed_at: SystemTime,
+    /// The number of reloads which failed in a row.
+    pub attempts: u32,
+}
+
+/// The state of the asset of a handle, see `AssetStorage::status`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HandleStatus {
+    /// The asset is in the storage.
+    Loaded,
+    /// The asset is still being loaded or waits to be added by `process`.
+    Pending,
+    /// The asset failed to load, was cancelled or has been removed.
+    Absent,
+}
+
+/// Decides when the ids of removed assets are reused for new handles,
+/// see `AssetStorage::set_recycle_policy`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RecyclePolicy {
+    /// Ids are reused right away, which keeps the storage dense. This is the default.
+    Immediate,
+    /// Ids are never reused, so stale ids (e.g. from `Handle::from_raw`) can never
+    /// point to another asset. The storage grows with every asset ever added.
+    Never,
+    /// Ids are reused once `process` has been called with a frame number that many
+    /// frames after the removal, giving stale ids a grace period to notice the asset
+    /// is gone.
+    DelayFrames(u32),
+}
+
+impl Default for RecyclePolicy {
+    fn default() -> Self {
+        RecyclePolicy::Immediate
+    }
+}
+
 /// Returned by processor systems, describes the loading state of the asset.
 pub enum ProcessingState<A>
 where
@@ -63,8 +206,47 @@ where
 {
     /// Asset is not fully loaded yet, need to wait longer
     Loading(A::Data),
+    /// Asset is not fully loaded yet and won't be for at least the given number of frames,
+    /// e.g. because it waits for a GPU fence. Unlike with `Loading`, `process` holds the
+    /// asset back until the frame number passed to it advanced by that much.
+    Delayed(A::Data, u64),
     /// Asset have finished loading, can now be inserted into storage and tracker notified
     Loaded(A),
+    /// The data contained several assets, e.g. the sprites of a sprite sheet, which have
+    /// finished loading and are inserted under the given handles at once. Assets of handles
+    /// which are already loaded are replaced, incrementing their version.
+    ///
+    /// One of the handles has to be the one the data has been loaded for, which the tracker
+    /// is notified for once the whole group has been inserted. The other handles can be
+    /// allocated with `AssetStorage::reserve_handle` when issuing the load.
+    Multiple(Vec<(Handle<A>, A)>),
+}
+
+/// Returned by the stages passed to `AssetStorage::process_stages`.
+pub enum StageState<A>
+where
+    A: Asset,
+{
+    /// The current stage is not done yet and will be run again next time.
+    Loading(A::Data),
+    /// The current stage is done, continue with the next stage.
+    Next(A::Data),
+    /// The asset has finished loading, no further stages are run.
+    Loaded(A),
+}
+
+/// A stage of the conversion from asset data to the asset, see `AssetStorage::process_stages`.
+pub type ProcessingStage<A> =
+    Box<dyn FnMut(<A as Asset>::Data) -> Result<StageState<A>, Error> + Send>;
+
+/// Converts asset data into assets, holding any state needed to do so.
+///
+/// This is an alternative to passing a closure to `AssetStorage::process`,
+/// which is useful if the conversion needs mutable access to some state (like
+/// a GPU device) that would be awkward to capture in a closure.
+pub trait AssetProcessor<A: Asset> {
+    /// Processes asset data into an asset.
+    fn process(&mut self, data: A::Data) -> Result<ProcessingState<A>, Error>;
 }
 
 impl<A: Asset> AssetStorage<A> {
@@ -73,8 +255,65 @@ impl<A: Asset> AssetStorage<A> {
         Default::default()
     }
 
-    /// Allocate a new handle.
+    /// Starts building a storage with given assets, for testing systems which read
+    /// a storage without loading anything.
+    pub fn mock() -> MockAssetStorage<A> {
+        MockAssetStorage {
+            handles: Vec::new(),
+            storage: AssetStorage::new(),
+        }
+    }
+
+    /// Allocate a new handle for an asset which will be added by `process`.
     pub(crate) fn allocate(&self) -> Handle<A> {
+        let handle = self.allocate_unused();
+        self.pending.lock().insert(handle.id());
+        handle
+    }
+
+    /// Makes `process` fail the load of `handle` if it didn't finish `timeout` after it has
+    /// been requested, returning the tracker to report the load to instead of `tracker`.
+    pub(crate) fn watch_deadline(
+        &self,
+        handle: &Handle<A>,
+        name: String,
+        requested: Instant,
+        timeout: Duration,
+        token: Option<LoadToken>,
+        tracker: Box<dyn Tracker>,
+    ) -> Box<dyn Tracker> {
+        let slot: TrackerSlot = Arc::new(Mutex::new(Some(tracker)));
+        self.deadlines.lock().insert(
+            handle.id(),
+            Deadline {
+                at: requested + timeout,
+                name,
+                requested,
+                token,
+                tracker: slot.clone(),
+            },
+        );
+
+        Box::new(SharedTracker(slot))
+    }
+
+    /// Makes the id of a removed asset available to new handles, see `RecyclePolicy`.
+    fn recycle(&mut self, id: u32) {
+        match self.recycle_policy {
+            RecyclePolicy::Immediate => {
+                // Can't reuse old handle here, because otherwise weak handles would still be valid.
+                self.unused_handles.push(Handle::new(id, self.storage_id));
+            }
+            RecyclePolicy::Never => {}
+            RecyclePolicy::DelayFrames(frames) => {
+                self.recycling
+                    .push((self.frame_number + u64::from(frames), id));
+            }
+        }
+    }
+
+    /// Allocate a new handle, reusing the ids of freed assets.
+    fn allocate_unused(&self) -> Handle<A> {
         self.unused_handles
             .pop()
             .unwrap_or_else(|_| self.allocate_new())
@@ -82,10 +321,16 @@ impl<A: Asset> AssetStorage<A> {
 
     fn allocate_new(&self) -> Handle<A> {
         let id = self.handle_alloc.next_id() as u32;
-        Handle {
-            id: Arc::new(id),
-            marker: PhantomData,
-        }
+        Handle::new(id, self.storage_id)
+    }
+
+    /// Panics in debug builds if `handle` has been created by another storage.
+    ///
+    /// Handles only carry the id of their asset, so a handle of another storage would
+    /// silently point at whichever asset has the same id in this one.
+    #[inline]
+    fn check_storage(&self, handle: &Handle<A>) {
+        check_storage(self.storage_id, handle);
     }
 
     /// Remove all data from asset storages, invalidating all associated handles.
@@ -93,6 +338,138 @@ impl<A: Asset> AssetStorage<A> {
     pub fn unload_all(&mut self) {
         unsafe { self.assets.clean(&self.bitset) }
         self.bitset.clear();
+        self.load_times.clear();
+        self.names.clear();
+        self.pinned.clear();
+        self.provenances.clear();
+        self.reload_failures.clear();
+        self.epoch.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Like `unload_all`, but removes the assets one by one and calls the `drop_fn`
+    /// closure for each of them, e.g. to release GPU resources.
+    ///
+    /// `unload_all` is faster for assets whose `Drop` implementation cleans up.
+    pub fn unload_all_with<D>(&mut self, mut drop_fn: D)
+    where
+        D: FnMut(A),
+    {
+        let ids: Vec<u32> = (&self.bitset).iter().collect();
+        for id in ids {
+            let (asset, _) = unsafe { self.assets.remove(id) };
+            drop_fn(asset);
+        }
+        self.bitset.clear();
+        self.load_times.clear();
+        self.names.clear();
+        self.pinned.clear();
+        self.provenances.clear();
+        self.reload_failures.clear();
+        self.epoch.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Remove the assets of the given handles from the storage.
+    ///
+    /// The handles (and all their clones) don't point to an asset anymore afterwards.
+    /// Their ids are only reused once all of them have been dropped, like the ids of
+    /// assets which are no longer used.
+    pub fn unload(&mut self, handles: &[Handle<A>]) {
+        self.unload_custom_drop(handles, |_| {});
+    }
+
+    /// Remove the assets of the given handles from the storage.
+    /// This calls the `drop_fn` closure for the removed assets.
+    ///
+    /// See `unload` for more information.
+    pub fn unload_custom_drop<D>(&mut self, handles: &[Handle<A>], mut drop_fn: D)
+    where
+        D: FnMut(A),
+    {
+        let mut unloaded = BitSet::new();
+        for handle in handles {
+            let id = handle.id();
+            if !self.bitset.remove(id) {
+                continue;
+            }
+            unloaded.add(id);
+            unsafe {
+                let (asset, _) = self.assets.remove(id);
+                drop_fn(asset);
+            }
+        }
+        self.forget(&unloaded);
+    }
+
+    /// Moves the asset of the given handle into another storage, returning its handle
+    /// in `dest`, or `None` if the handle doesn't point to an asset of this storage.
+    ///
+    /// Like with `unload`, the given handle doesn't point to an asset of this storage
+    /// anymore afterwards.
+    pub fn transfer_to(
+        &mut self,
+        handle: &Handle<A>,
+        dest: &mut AssetStorage<A>,
+    ) -> Option<Handle<A>> {
+        let id = handle.id();
+        if !self.bitset.remove(id) {
+            return None;
+        }
+        let (asset, _) = unsafe { self.assets.remove(id) };
+        let mut removed = BitSet::new();
+        removed.add(id);
+        self.forget(&removed);
+
+        Some(dest.insert(asset))
+    }
+
+    /// Moves the asset of `shadow` into the slot of `target` in one step, incrementing
+    /// the version of `target`, and returns the asset it replaced. Returns `None`, without
+    /// changing anything, unless both handles point to different assets of this storage.
+    ///
+    /// This allows double buffering: a new version of an asset is loaded into a separate
+    /// handle, and only swapped in once it has been loaded completely, so consumers of
+    /// `target` never see anything but a complete asset. Hot reloads already work like
+    /// that, the old asset is kept until the reloaded one has been processed completely.
+    ///
+    /// Like with `unload`, the `shadow` handle doesn't point to an asset anymore afterwards.
+    pub fn swap_in(&mut self, target: &Handle<A>, shadow: &Handle<A>) -> Option<A> {
+        let (target_id, shadow_id) = (target.id(), shadow.id());
+        if target_id == shadow_id
+            || !self.bitset.contains(target_id)
+            || !self.bitset.remove(shadow_id)
+        {
+            return None;
+        }
+        let (asset, _) = unsafe { self.assets.remove(shadow_id) };
+        let mut removed = BitSet::new();
+        removed.add(shadow_id);
+        self.forget(&removed);
+
+        Some(self.replace(target, asset))
+    }
+
+    /// Stops tracking the names and reload objects of removed assets.
+    ///
+    /// Their handles stay tracked, so `process` only recycles the ids once all clones
+    /// of the handles have been dropped.
+    fn forget(&mut self, removed: &BitSet) {
+        self.epoch.fetch_add(1, Ordering::Relaxed);
+        self.load_times.retain(|&id, _| !removed.contains(id));
+        self.reload_failures.retain(|&id, _| !removed.contains(id));
+        self.names.retain(|_, handle| {
+            handle
+                .upgrade()
+                .map(|handle| !removed.contains(handle.id()))
+                .unwrap_or(false)
+        });
+        self.pinned.retain(|&id, _| !removed.contains(id));
+        self.provenances.retain(|&id, _| !removed.contains(id));
+        self.reloads.retain(|&(ref handle, _)| {
+            handle
+                .upgrade()
+                .map(|handle| !removed.contains(handle.id()))
+                .unwrap_or(false)
+        });
     }
 
     /// When cloning an asset handle, you'll get another handle,
@@ -105,10 +482,11 @@ impl<A: Asset> AssetStorage<A> {
         A: Clone,
     {
         if let Some(asset) = self.get(handle).map(A::clone) {
-            let h = self.allocate();
+            let h = self.allocate_unused();
 
             let id = h.id();
             self.bitset.add(id);
+            self.epoch.fetch_add(1, Ordering::Relaxed);
             self.handles.push(h.clone());
 
             unsafe {
@@ -121,8 +499,63 @@ impl<A: Asset> AssetStorage<A> {
         }
     }
 
+    /// Creates a new storage holding clones of all assets of this one, with the same ids
+    /// and versions, e.g. to checkpoint the assets together with a snapshot of the world.
+    ///
+    /// The clone allocates new handles for the assets instead of sharing the ones of this
+    /// storage, so the assets of both storages are freed independently. Handles of this
+    /// storage can still be used with the clone, as the ids are kept, but they don't keep
+    /// the cloned assets alive. That's why all cloned assets are pinned (see `pin`); unpin
+    /// them once the handles have been turned into ones of the clone using `rebind`.
+    ///
+    /// Assets which are still loading, reload objects, callbacks and settings are not cloned.
+    pub fn deep_clone(&self) -> AssetStorage<A>
+    where
+        A: Clone,
+    {
+        let mut clone = AssetStorage::new();
+        clone.storage_id = self.storage_id;
+        clone.handle_alloc.store_count.store(
+            self.handle_alloc.store_count.load(Ordering::Relaxed),
+            Ordering::Relaxed,
+        );
+
+        for handle in &self.handles {
+            let id = handle.id();
+            if !self.bitset.contains(id) {
+                continue;
+            }
+            let (ref asset, version) = *unsafe { self.assets.get(id) };
+            let cloned = Handle::new(id, self.storage_id);
+            clone.bitset.add(id);
+            unsafe {
+                clone.assets.insert(id, (asset.clone(), version));
+            }
+            clone.handles.push(cloned.clone());
+            clone.pinned.insert(id, cloned);
+        }
+        for (name, handle) in &self.names {
+            let cloned = handle
+                .upgrade()
+                .and_then(|handle| clone.pinned.get(&handle.id()))
+                .map(Handle::downgrade);
+            if let Some(cloned) = cloned {
+                clone.names.insert(name.clone(), cloned);
+            }
+        }
+        clone.load_times = self.load_times.clone();
+        clone.provenances = self.provenances.clone();
+        clone.reload_failures = self.reload_failures.clone();
+        clone.epoch.store(self.epoch(), Ordering::Relaxed);
+
+        clone
+    }
+
     /// Get an asset from a given asset handle.
+    ///
+    /// In debug builds, this panics if the handle belongs to another storage.
     pub fn get(&self, handle: &Handle<A>) -> Option<&A> {
+        self.check_storage(handle);
         if self.bitset.contains(handle.id()) {
             Some(unsafe { &self.assets.get(handle.id()).0 })
         } else {
@@ -130,8 +563,62 @@ impl<A: Asset> AssetStorage<A> {
         }
     }
 
+    /// Like `get`, but tells why there is no asset for the handle instead of returning
+    /// `None`, e.g. to report "asset still loading" to a script.
+    ///
+    /// Handles of other storages are only told apart in debug builds; release builds
+    /// look up the asset with the same id in this storage, like `get`.
+    pub fn try_get(&self, handle: &Handle<A>) -> Result<&A, GetError> {
+        #[cfg(debug_assertions)]
+        {
+            if handle.storage != 0 && handle.storage != self.storage_id {
+                return Err(GetError::WrongStorage);
+            }
+        }
+        match self.status(handle) {
+            HandleStatus::Loaded => Ok(unsafe { &self.assets.get(handle.id()).0 }),
+            HandleStatus::Pending => Err(GetError::Pending),
+            HandleStatus::Absent => Err(GetError::Unloaded),
+        }
+    }
+
+    /// Get a clone of an asset from a given asset handle.
+    ///
+    /// Unlike `get`, the returned asset doesn't borrow the storage.
+    pub fn get_cloned(&self, handle: &Handle<A>) -> Option<A>
+    where
+        A: Clone,
+    {
+        self.get(handle).cloned()
+    }
+
+    /// Returns a number which increases whenever an asset is added to, replaced in or
+    /// removed from the storage, so comparing it to a previously returned value tells
+    /// whether anything changed in the meantime.
+    pub fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether the asset of the given handle is loaded, still loading or absent.
+    ///
+    /// `get` returns `None` both for assets which are still loading and those which
+    /// failed to load or have been removed; this tells them apart.
+    pub fn status(&self, handle: &Handle<A>) -> HandleStatus {
+        let id = handle.id();
+        if self.bitset.contains(id) {
+            HandleStatus::Loaded
+        } else if self.pending.lock().contains(&id) {
+            HandleStatus::Pending
+        } else {
+            HandleStatus::Absent
+        }
+    }
+
     /// Get an asset version from a given asset handle.
+    ///
+    /// In debug builds, this panics if the handle belongs to another storage.
     pub fn get_version(&self, handle: &Handle<A>) -> Option<u32> {
+        self.check_storage(handle);
         if self.bitset.contains(handle.id()) {
             Some(unsafe { self.assets.get(handle.id()).1 })
         } else {
@@ -140,7 +627,10 @@ impl<A: Asset> AssetStorage<A> {
     }
 
     /// Get an asset and it's version from a given asset handle.
+    ///
+    /// In debug builds, this panics if the handle belongs to another storage.
     pub fn get_with_version(&self, handle: &Handle<A>) -> Option<&(A, u32)> {
+        self.check_storage(handle);
         if self.bitset.contains(handle.id()) {
             Some(unsafe { self.assets.get(handle.id()) })
         } else {
@@ -148,6 +638,20 @@ impl<A: Asset> AssetStorage<A> {
         }
     }
 
+    /// Iterates over the assets whose version is greater than `baseline`, together with
+    /// their handles and versions.
+    ///
+    /// Versions start at `0` and are increased whenever an asset is replaced or hot
+    /// reloaded, so this yields the assets which changed more than `baseline` times.
+    /// Check `epoch` first to skip the iteration if nothing changed at all.
+    pub fn changed_since_iter(&self, baseline: u32) -> impl Iterator<Item = (&Handle<A>, &A, u32)> {
+        self.handles.iter().filter_map(move |handle| {
+            self.get_with_version(handle)
+                .filter(|&&(_, version)| version > baseline)
+                .map(|&(ref asset, version)| (handle, asset, version))
+        })
+    }
+
     /// Get an asset by it's handle id.
     pub fn get_by_id(&self, id: u32) -> Option<&A> {
         if self.bitset.contains(id) {
@@ -163,6 +667,7 @@ impl<A: Asset> AssetStorage<A> {
         if self.bitset.contains(handle.id()) {
             let data = unsafe { self.assets.get_mut(handle.id()) };
             data.1 += 1;
+            self.epoch.fetch_add(1, Ordering::Relaxed);
             std::mem::replace(&mut data.0, asset)
         } else {
             panic!("Trying to replace not loaded asset");
@@ -176,9 +681,10 @@ impl<A: Asset> AssetStorage<A> {
     /// Use this method only when you need to insert procedurally generated
     /// asset directly into storage, skipping intermediate Asset::Data form.
     pub fn insert(&mut self, asset: A) -> Handle<A> {
-        let handle = self.allocate();
+        let handle = self.allocate_unused();
         let id = handle.id();
         self.bitset.add(id);
+        self.epoch.fetch_add(1, Ordering::Relaxed);
         self.handles.push(handle.clone());
         unsafe {
             self.assets.insert(id, (asset, 0));
@@ -186,8 +692,322 @@ impl<A: Asset> AssetStorage<A> {
         handle
     }
 
+    /// Inserts many assets at once, like calling `insert` for each of them but
+    /// with less overhead.
+    ///
+    /// The returned handles are in the same order as the assets.
+    pub fn insert_batch<I>(&mut self, assets: I) -> Vec<Handle<A>>
+    where
+        I: IntoIterator<Item = A>,
+    {
+        let assets = assets.into_iter();
+        let (min, _) = assets.size_hint();
+        let mut inserted = Vec::with_capacity(min);
+        self.handles.reserve(min);
+
+        for asset in assets {
+            let handle = self.allocate_unused();
+            let id = handle.id();
+            self.bitset.add(id);
+            self.handles.push(handle.clone());
+            unsafe {
+                self.assets.insert(id, (asset, 0));
+            }
+            inserted.push(handle);
+        }
+        if !inserted.is_empty() {
+            self.epoch.fetch_add(1, Ordering::Relaxed);
+        }
+
+        inserted
+    }
+
+    /// Allocates a handle for an asset which is only added later, e.g. as one of the assets
+    /// of `ProcessingState::Multiple`. Until then, the status of the handle is `Pending`.
+    pub fn reserve_handle(&self) -> Handle<A> {
+        self.allocate()
+    }
+
+    /// Inserts an asset without needing mutable access to the storage, e.g. from a system
+    /// which only reads the storage.
+    ///
+    /// The handle is returned right away, but the asset is only added to the storage by
+    /// the next call to `process`; until then, `get` returns `None` for it.
+    pub fn insert_deferred(&self, asset: A) -> Handle<A> {
+        let handle = self.allocate();
+        self.processed.push(Processed::Inserted {
+            asset,
+            handle: handle.clone(),
+            sequence: self.processed.next_sequence(),
+        });
+
+        handle
+    }
+
+    /// Returns the handles of all assets currently stored.
+    ///
+    /// The handles of assets which are about to be freed, because no other handle
+    /// points to them anymore, are included until the next call to `process`.
+    /// Don't rely on every handle pointing to a valid asset, but check it with
+    /// `contains` before accessing it.
+    pub fn handles(&self) -> &[Handle<A>] {
+        &self.handles
+    }
+
+    /// Describes the state of the storage in a few lines, e.g. to log it while
+    /// investigating why an asset is missing.
+    ///
+    /// Lists the number of stored assets, of tracked handles (including the ones which
+    /// wait to be collected or whose asset has been unloaded), of assets waiting to be
+    /// processed, of ids waiting to be reused and of reload objects, the highest id
+    /// allocated so far and the ids of the first tracked handles with the versions of
+    /// their assets.
+    pub fn debug_dump(&self) -> String {
+        use std::fmt::Write;
+
+        const SAMPLE: usize = 16;
+
+        let allocated = self.handle_alloc.store_count.load(Ordering::Relaxed);
+        let mut dump = String::new();
+        let _ = writeln!(dump, "AssetStorage<{}>:", A::NAME);
+        let _ = writeln!(dump, "  live assets: {}", (&self.bitset).iter().count());
+        let _ = writeln!(
+            dump,
+            "  tracked handles: {} (dead: {})",
+            self.handles.len(),
+            self.handles
+                .iter()
+                .filter(|handle| handle.is_dead())
+                .count(),
+        );
+        let _ = writeln!(
+            dump,
+            "  pending: {} (queued: {}, held: {}, delayed: {})",
+            self.pending.lock().len(),
+            self.processed.len(),
+            self.held.len(),
+            self.delayed.len(),
+        );
+        let _ = writeln!(
+            dump,
+            "  recycled ids: {} (reusable: {}, delayed: {})",
+            self.unused_handles.len() + self.recycling.len(),
+            self.unused_handles.len(),
+            self.recycling.len(),
+        );
+        match allocated {
+            0 => dump.push_str("  highest allocated id: none\n"),
+            n => {
+                let _ = writeln!(dump, "  highest allocated id: {}", n - 1);
+            }
+        }
+        let _ = writeln!(dump, "  pinned: {}", self.pinned.len());
+        let _ = writeln!(
+            dump,
+            "  reload entries: {} (in flight: {})",
+            self.reloads.len(),
+            self.reloads_in_flight.load(Ordering::Relaxed),
+        );
+
+        let mut ids: Vec<u32> = self.handles.iter().map(Handle::id).collect();
+        ids.sort_unstable();
+        let sample: Vec<String> = ids
+            .iter()
+            .take(SAMPLE)
+            .map(|&id| {
+                if self.bitset.contains(id) {
+                    format!("{} (v{})", id, unsafe { self.assets.get(id).1 })
+                } else {
+                    format!("{} (missing)", id)
+                }
+            })
+            .collect();
+        let _ = write!(dump, "  handles: [{}", sample.join(", "));
+        if ids.len() > SAMPLE {
+            let _ = write!(dump, ", .. {} more", ids.len() - SAMPLE);
+        }
+        dump.push(']');
+
+        dump
+    }
+
+    /// Returns the handle this storage keeps for the asset with the id of `handle`, or
+    /// `None` if there is no such asset.
+    ///
+    /// This turns a handle created with `Handle::from_raw` into one sharing the reference
+    /// count of the other handles to the asset, so it keeps the asset alive again.
+    pub fn rebind(&self, handle: Handle<A>) -> Option<Handle<A>> {
+        let id = handle.id();
+        if !self.bitset.contains(id) {
+            return None;
+        }
+
+        self.handles.iter().find(|h| h.id() == id).cloned()
+    }
+
+    /// Returns a handle to the asset which has been loaded under the given name, or `None`
+    /// if there is no such asset (anymore).
+    ///
+    /// Names are recorded as the `Loader` passed them, or as returned by the normalizer
+    /// installed with `set_name_normalizer`; `name` itself is looked up as it is.
+    pub fn get_handle_by_name(&self, name: &str) -> Option<Handle<A>> {
+        self.names
+            .get(name)
+            .and_then(WeakHandle::upgrade)
+            .filter(|handle| self.bitset.contains(handle.id()))
+    }
+
+    /// Returns `name` as `process` records it for `get_handle_by_name`, see
+    /// `set_name_normalizer`.
+    pub(crate) fn normalize_name(&self, name: &str) -> String {
+        match self.name_normalizer {
+            Some(ref normalize) => normalize(name),
+            None => name.to_owned(),
+        }
+    }
+
+    /// Installs a function rewriting the names of loaded assets before `process` records
+    /// them for `get_handle_by_name`, e.g. to map paths to the logical names of a virtual
+    /// file system.
+    ///
+    /// Only assets loaded afterwards are affected. This replaces a previously installed
+    /// normalizer.
+    pub fn set_name_normalizer<F>(&mut self, normalizer: F)
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.name_normalizer = Some(Box::new(normalizer));
+    }
+
+    /// Hot reloads the asset of the given handle from bytes which are already in memory,
+    /// instead of its source.
+    ///
+    /// The bytes are imported with `format` on the pool set with `set_reload_pool`, or on
+    /// `pool` if there is none; the resulting data is then processed by `process` like
+    /// any other hot reload. Any reload object of the asset is kept as is.
+    ///
+    /// The format gets the name of the reload object of the asset, or else the name the
+    /// asset has been loaded with. Returns an error if the asset has neither.
+    pub fn reload_from_bytes<F>(
+        &mut self,
+        handle: &Handle<A>,
+        bytes: Vec<u8>,
+        format: F,
+        pool: &ThreadPool,
+    ) -> Result<(), Error>
+    where
+        F: Format<A::Data>,
+    {
+        self.check_storage(handle);
+        let name = self
+            .reload_info(handle)
+            .map(|info| info.name)
+            .or_else(|| {
+                self.names
+                    .iter()
+                    .find(|&(_, weak)| weak.upgrade().map_or(false, |h| h.id() == handle.id()))
+                    .map(|(name, _)| name.clone())
+            })
+            .ok_or_else(|| format_err!("Asset {:?} has no name to import bytes with", handle))?;
+
+        self.reloads_in_flight.fetch_add(1, Ordering::Relaxed);
+        let processed = self.processed.clone();
+        let sequence = processed.next_sequence();
+        let handle = handle.clone();
+        let job = move || {
+            let format_name = format.name();
+            let data = format
+                .import(name.clone(), Arc::new(BytesSource::new(bytes)), None)
+                .with_context(|_| error::Error::Format(format_name));
+            processed.push(Processed::HotReload {
+                data,
+                handle,
+                name,
+                old_reload: None,
+                sequence,
+            });
+        };
+        match self.reload_pool {
+            Some(ref reload_pool) => reload_pool.spawn(job),
+            None => pool.spawn(job),
+        }
+
+        Ok(())
+    }
+
+    /// Returns information about how the asset of the given handle will be hot reloaded,
+    /// or `None` if it has no reload object attached.
+    pub fn reload_info(&self, handle: &Handle<A>) -> Option<ReloadInfo> {
+        self.reload_of(handle).map(|rel| ReloadInfo {
+            name: rel.name(),
+            format: rel.format().to_owned(),
+        })
+    }
+
+    /// Returns `true` if the asset of the given handle has a reload object attached,
+    /// meaning it can be hot reloaded.
+    pub fn is_reloadable(&self, handle: &Handle<A>) -> bool {
+        self.reload_of(handle).is_some()
+    }
+
+    /// Reloads the asset of the given handle with the next call to `process`, regardless
+    /// of the `HotReloadStrategy` and whether its reload object thinks it changed.
+    ///
+    /// Returns `false` if the asset has no reload object attached, see `is_reloadable`.
+    /// Such assets can be imported again with `Loader::reload`.
+    pub fn request_reload(&mut self, handle: &Handle<A>) -> bool {
+        if !self.is_reloadable(handle) {
+            return false;
+        }
+        self.forced_reloads.insert(handle.id());
+
+        true
+    }
+
+    /// Reloads the assets read from the file `path` with the next call to `process`,
+    /// regardless of the `HotReloadStrategy`. This lets file watchers of your own drive
+    /// hot reloads instead of polling, see `FileWatcher` for the built-in one.
+    ///
+    /// `path` is compared to the names the assets have been loaded with, and to each of
+    /// the files of assets made of several files (see `Reload::files`). Their reload
+    /// objects are not asked whether the file changed, so the assets are reloaded even
+    /// if the modification time of the file stayed the same.
+    pub fn notify_file_changed(&mut self, path: &str) {
+        self.changed_files
+            .get_or_insert_with(Default::default)
+            .insert(path.to_owned());
+    }
+
+    fn reload_of(&self, handle: &Handle<A>) -> Option<&dyn Reload<A::Data>> {
+        self.reloads
+            .iter()
+            .find(|&&(ref weak, _)| {
+                weak.upgrade()
+                    .map(|h| h.id() == handle.id())
+                    .unwrap_or(false)
+            })
+            .map(|&(_, ref rel)| &**rel)
+    }
+
+    /// Turns this storage into a read-only `FrozenAssetStorage`.
+    ///
+    /// Only the assets and their handles are kept. Loads which are still in flight are
+    /// dropped, and so are the reloads and settings of this storage.
+    pub fn freeze(mut self) -> FrozenAssetStorage<A> {
+        FrozenAssetStorage {
+            assets: std::mem::replace(&mut self.assets, VecStorage::default()),
+            bitset: std::mem::replace(&mut self.bitset, BitSet::new()),
+            handles: std::mem::replace(&mut self.handles, Vec::new()),
+            next_id: self.handle_alloc.store_count.load(Ordering::Relaxed),
+            storage_id: self.storage_id,
+        }
+    }
+
     /// Check if given handle points to a valid asset in the storage.
+    ///
+    /// In debug builds, this panics if the handle belongs to another storage.
     pub fn contains(&self, handle: &Handle<A>) -> bool {
+        self.check_storage(handle);
         self.bitset.contains(handle.id())
     }
 
@@ -207,8 +1027,22 @@ impl<A: Asset> AssetStorage<A> {
         &self.assets.get(id).0
     }
 
+    /// Get an asset and it's version by it's handle id without checking the internal bitset.
+    /// Use `contains_id` to manually check it's status before access.
+    ///
+    /// # Safety
+    /// You must manually verify that given asset id is valid.
+    /// Failing to do so may result in dereferencing
+    /// uninitialized memory or out of bounds access.
+    pub unsafe fn get_by_id_unchecked_with_version(&self, id: u32) -> &(A, u32) {
+        self.assets.get(id)
+    }
+
     /// Get an asset mutably from a given asset handle.
+    ///
+    /// In debug builds, this panics if the handle belongs to another storage.
     pub fn get_mut(&mut self, handle: &Handle<A>) -> Option<&mut A> {
+        self.check_storage(handle);
         if self.bitset.contains(handle.id()) {
             Some(unsafe { &mut self.assets.get_mut(handle.id()).0 })
         } else {
@@ -216,81 +1050,590 @@ impl<A: Asset> AssetStorage<A> {
         }
     }
 
-    /// Process finished asset data and maintain the storage.
-    pub fn process<F>(
-        &mut self,
-        f: F,
-        frame_number: u64,
-        pool: &ThreadPool,
-        strategy: Option<&HotReloadStrategy>,
-    ) where
+    /// Get an asset mutably and its version from a given asset handle.
+    ///
+    /// This doesn't change the version, see `get_mut_bump` for that. In debug builds,
+    /// this panics if the handle belongs to another storage.
+    pub fn get_mut_with_version(&mut self, handle: &Handle<A>) -> Option<(&mut A, u32)> {
+        self.check_storage(handle);
+        if self.bitset.contains(handle.id()) {
+            let data = unsafe { self.assets.get_mut(handle.id()) };
+            Some((&mut data.0, data.1))
+        } else {
+            None
+        }
+    }
+
+    /// Get an asset mutably from a given asset handle, increasing its version like
+    /// `replace` does, so code comparing versions notices the mutation.
+    ///
+    /// In debug builds, this panics if the handle belongs to another storage.
+    pub fn get_mut_bump(&mut self, handle: &Handle<A>) -> Option<&mut A> {
+        self.check_storage(handle);
+        if self.bitset.contains(handle.id()) {
+            let data = unsafe { self.assets.get_mut(handle.id()) };
+            data.1 += 1;
+            self.epoch.fetch_add(1, Ordering::Relaxed);
+            Some(&mut data.0)
+        } else {
+            None
+        }
+    }
+
+    /// Replaces the asset of the given handle with the result of `f`, which is passed the
+    /// current asset by value, and increments the version like `replace` does.
+    ///
+    /// Returns `false`, without calling `f`, if the handle doesn't point to a loaded asset.
+    /// If `f` panics, the asset is removed from the storage.
+    pub fn map_asset<F>(&mut self, handle: &Handle<A>, f: F) -> bool
+    where
+        F: FnOnce(A) -> A,
+    {
+        self.check_storage(handle);
+        let id = handle.id();
+        // Removed from the bitset first, so a panic in `f` can't leave it pointing to nothing.
+        if !self.bitset.remove(id) {
+            return false;
+        }
+        let (asset, version) = unsafe { self.assets.remove(id) };
+        let asset = f(asset);
+        unsafe {
+            self.assets.insert(id, (asset, version + 1));
+        }
+        self.bitset.add(id);
+        self.epoch.fetch_add(1, Ordering::Relaxed);
+
+        true
+    }
+
+    /// Decides if `process` looks for assets whose handles have all been dropped on every
+    /// call, which is the default, or only if it processed asset data or a hot reload is due.
+    ///
+    /// The latter saves walking all handles while nothing is loading, but assets which are
+    /// no longer used then stay in the storage until the next load or reload.
+    pub fn set_idle_gc(&mut self, idle_gc: bool) {
+        self.idle_gc = idle_gc;
+    }
+
+    /// Keeps the asset of a weak handle alive, even if all other handles to it are dropped,
+    /// until it is unpinned again. Returns `false` if the asset is already gone.
+    ///
+    /// The storage holds a strong handle to pinned assets, so they are skipped when
+    /// `process` frees the assets which are no longer used.
+    pub fn pin(&mut self, handle: &WeakHandle<A>) -> bool {
+        match handle.upgrade() {
+            Some(handle) => {
+                self.check_storage(&handle);
+                self.pinned.insert(handle.id(), handle);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Releases an asset pinned with `pin`, which is freed by the next `process` if no
+    /// other handles to it are left. Returns `false` if the asset wasn't pinned.
+    pub fn unpin(&mut self, handle: &WeakHandle<A>) -> bool {
+        handle
+            .upgrade()
+            .and_then(|handle| self.pinned.remove(&handle.id()))
+            .is_some()
+    }
+
+    /// Returns `true` if the asset of the handle has been pinned with `pin`.
+    pub fn is_pinned(&self, handle: &Handle<A>) -> bool {
+        self.pinned.contains_key(&handle.id())
+    }
+
+    /// Pauses or resumes the processing of loaded asset data.
+    ///
+    /// While paused, `process` still maintains the storage, but finished asset data
+    /// is only held back instead of being converted and inserted. Once resumed, the
+    /// held back data is processed again in the order it arrived in.
+    ///
+    /// Data of loads with `LoadPriority::Immediate` is processed even while paused.
+    pub fn set_paused(&mut self, paused: bool) {
+        if self.paused && !paused {
+            while let Some(processed) = self.processed.pop() {
+                self.held.push(processed);
+            }
+            for processed in self.held.drain(..) {
+                self.processed.push(processed);
+            }
+        }
+        self.paused = paused;
+    }
+
+    /// Returns how long `process` took to turn the loaded data of the asset into the asset.
+    ///
+    /// This is measured from the first time `process` picked up the data, so for assets
+    /// which returned `ProcessingState::Loading`, it is the total time until they were loaded.
+    ///
+    /// Returns `None` if the asset has not been processed by this storage, e.g. because it
+    /// was inserted directly or converted by `Loader::load_from_data_async_processed`.
+    pub fn load_time(&self, handle: &Handle<A>) -> Option<Duration> {
+        self.load_times.get(&handle.id()).cloned()
+    }
+
+    /// Returns where the data of the asset was read from, e.g. to show it in tools.
+    ///
+    /// Returns `None` unless the asset has been loaded from a source by the `Loader`.
+    pub fn provenance(&self, handle: &Handle<A>) -> Option<&Provenance> {
+        self.provenances.get(&handle.id()).map(|p| &**p)
+    }
+
+    /// Returns `true` if processing is currently paused, see `set_paused`.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Installs a sink which receives an `AssetProgressEvent` whenever `process` finishes
+    /// (or fails, or cancels) loading an asset, in addition to notifying its tracker.
+    ///
+    /// This replaces a previously installed sink.
+    pub fn set_progress_sink<F>(&mut self, sink: F)
+    where
+        F: FnMut(AssetProgressEvent) + Send + Sync + 'static,
+    {
+        self.progress_sink = Some(Box::new(sink));
+    }
+
+    /// Removes the sink installed with `set_progress_sink`.
+    pub fn remove_progress_sink(&mut self) {
+        self.progress_sink = None;
+    }
+
+    /// Installs a callback which is called with the handle id of an asset whenever
+    /// `process` starts hot reloading it. It runs on the thread calling `process`,
+    /// before the reload is spawned.
+    ///
+    /// This replaces a previously installed callback.
+    pub fn on_reload_started<F>(&mut self, callback: F)
+    where
+        F: FnMut(u32) + Send + Sync + 'static,
+    {
+        self.reload_started = Some(Box::new(callback));
+    }
+
+    /// Installs a callback which is called with the handle id of an asset, and whether
+    /// the asset has been replaced, once a hot reload announced to `on_reload_started`
+    /// is done. Reloads which are skipped because nothing changed count as successful.
+    ///
+    /// This replaces a previously installed callback.
+    pub fn on_reload_finished<F>(&mut self, callback: F)
+    where
+        F: FnMut(u32, bool) + Send + Sync + 'static,
+    {
+        self.reload_finished = Some(Box::new(callback));
+    }
+
+    /// Returns the channel a `HotReloadEvent` is published to whenever `process` finished
+    /// a hot reload, including failed ones, creating it on first use. Register a reader
+    /// with it to be notified of reloads instead of comparing versions every frame.
+    ///
+    /// Until this has been called, no events are published.
+    pub fn reload_events_mut(&mut self) -> &mut EventChannel<HotReloadEvent<A>> {
+        self.reload_events.get_or_insert_with(EventChannel::new)
+    }
+
+    /// Returns the channel created by `reload_events_mut`, to read the published events
+    /// with, or `None` if it hasn't been created.
+    pub fn reload_events(&self) -> Option<&EventChannel<HotReloadEvent<A>>> {
+        self.reload_events.as_ref()
+    }
+
+    /// Returns the assets whose last hot reload failed, sorted by name, e.g. to show them
+    /// in a console. Such assets keep their previous version.
+    ///
+    /// An asset is removed from the list once it has been reloaded successfully, or once
+    /// it is removed from the storage.
+    pub fn reload_failures(&self) -> Vec<ReloadFailure> {
+        let mut failures: Vec<_> = self.reload_failures.values().cloned().collect();
+        failures.sort_by(|a, b| a.name.cmp(&b.name));
+
+        failures
+    }
+
+    /// Installs a callback which is called whenever an asset fails to load, before its
+    /// tracker is notified. Unlike the error passed to the tracker, the `ProcessingError`
+    /// tells why the load failed.
+    ///
+    /// This replaces a previously installed callback.
+    pub fn on_processing_error<F>(&mut self, callback: F)
+    where
+        F: FnMut(&ProcessingError) + Send + Sync + 'static,
+    {
+        self.processing_failed = Some(Box::new(callback));
+    }
+
+    /// If set to `true`, hot-reloaded assets are compared to the asset they would replace,
+    /// and only replaced (incrementing the version) if they differ.
+    pub fn set_skip_identical_reloads(&mut self, value: bool)
+    where
+        A: PartialEq,
+    {
+        self.skip_identical = if value {
+            Some(<A as PartialEq>::eq)
+        } else {
+            None
+        };
+    }
+
+    /// Reserves capacity for at least `additional` more reloadable assets, avoiding
+    /// repeated reallocations when loading many of them at once.
+    pub fn reserve_reloads(&mut self, additional: usize) {
+        self.reloads.reserve(additional);
+    }
+
+    /// Sets a dedicated thread pool to run hot reloads on, instead of the pool passed to
+    /// `process`. Passing `None` restores the default.
+    ///
+    /// Using a separate pool keeps big reloads from delaying other work on the shared
+    /// pool, at the cost of additional threads.
+    pub fn set_reload_pool(&mut self, pool: Option<Arc<ThreadPool>>) {
+        self.reload_pool = pool;
+    }
+
+    /// Sets when the ids of removed assets are reused for new handles, which defaults
+    /// to `RecyclePolicy::Immediate`.
+    pub fn set_recycle_policy(&mut self, policy: RecyclePolicy) {
+        self.recycle_policy = policy;
+    }
+
+    /// Limits the number of hot reloads running at the same time to `max`, so changing
+    /// many assets at once doesn't flood the pool with reads. Passing `None`, the default,
+    /// removes the limit.
+    ///
+    /// Assets which need a reload while the limit is reached are reloaded by later
+    /// calls to `process`, once earlier reloads are done.
+    pub fn set_max_concurrent_reloads(&mut self, max: Option<usize>) {
+        self.max_concurrent_reloads = max;
+    }
+
+    /// Sets the IO threads to run hot reloads on if no dedicated pool has been set using
+    /// `set_reload_pool`, so reading the changed files doesn't block the pool passed to
+    /// `process`. Passing `None` restores the default.
+    ///
+    /// `Processor` sets this to the `Loader`'s `io_pool` when it is set up.
+    pub fn set_io_pool(&mut self, pool: Option<Arc<IoPool>>) {
+        self.io_pool = pool;
+    }
+
+    /// If set to `true`, `process` handles finished asset data in the order the loads
+    /// were issued in, instead of the order the data happened to finish in.
+    ///
+    /// Data of loads with a higher `LoadPriority` is still handled first. Defaults to `false`.
+    pub fn set_ordered_processing(&mut self, value: bool) {
+        self.ordered = value;
+    }
+
+    /// If set to `true`, panics of the processing closure passed to `process` are caught
+    /// and reported as a failure of the asset being processed, instead of unwinding
+    /// through `process`. Defaults to `false`.
+    pub fn set_catch_processing_panics(&mut self, value: bool) {
+        self.catch_panics = value;
+    }
+
+    /// If set to `true`, assets whose handles have all been dropped by the time they are
+    /// loaded are reported as loaded successfully, for fire-and-forget loads which don't
+    /// keep their handle. Such assets are freed again by the next `process`.
+    ///
+    /// Defaults to `false`, which fails the load with `ProcessingError::UnusedHandle`, as
+    /// such a load is usually a mistake.
+    pub fn set_transient_loads(&mut self, value: bool) {
+        self.transient_loads = value;
+    }
+
+    /// Process finished asset data and maintain the storage.
+    ///
+    /// Hot reloads are run on `pool`, unless a dedicated pool has been set using
+    /// `set_reload_pool` or IO threads using `set_io_pool`.
+    ///
+    /// Returns `false` if there was nothing to do: no asset data was waiting to be
+    /// processed, no asset was freed and no hot reload was started.
+    pub fn process<F>(
+        &mut self,
+        f: F,
+        frame_number: u64,
+        pool: &ThreadPool,
+        strategy: Option<&HotReloadStrategy>,
+    ) -> bool
+    where
         F: FnMut(A::Data) -> Result<ProcessingState<A>, Error>,
     {
-        self.process_custom_drop(f, |_| {}, frame_number, pool, strategy);
+        self.process_custom_drop(f, |_| {}, frame_number, pool, strategy)
+    }
+
+    /// Process finished asset data using an `AssetProcessor` and maintain the storage.
+    ///
+    /// Works just like `process`, but the processor may also be a trait object.
+    pub fn process_with<P>(
+        &mut self,
+        processor: &mut P,
+        frame_number: u64,
+        pool: &ThreadPool,
+        strategy: Option<&HotReloadStrategy>,
+    ) -> bool
+    where
+        P: AssetProcessor<A> + ?Sized,
+    {
+        self.process(|data| processor.process(data), frame_number, pool, strategy)
+    }
+
+    /// Process finished asset data in multiple stages and maintain the storage.
+    ///
+    /// The stages are run in order for every asset. A stage returning `StageState::Loading`
+    /// is run again the next time, while `StageState::Next` immediately continues with the
+    /// following stage. The asset is loaded once a stage returns `StageState::Loaded`, which
+    /// the last stage has to do eventually.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stages` is empty.
+    pub fn process_stages(
+        &mut self,
+        stages: &mut [ProcessingStage<A>],
+        frame_number: u64,
+        pool: &ThreadPool,
+        strategy: Option<&HotReloadStrategy>,
+    ) -> bool {
+        assert!(
+            !stages.is_empty(),
+            "At least one processing stage is required"
+        );
+
+        self.process_staged(
+            |stage: &mut usize, mut data| loop {
+                // Re-queued assets may come back to fewer stages than they left.
+                let count = stages.len();
+                let current = stages.get_mut(*stage).ok_or_else(|| {
+                    format_err!(
+                        "Asset is at processing stage {}, but there are only {}",
+                        *stage,
+                        count
+                    )
+                })?;
+                match current(data)? {
+                    StageState::Loading(d) => return Ok(ProcessingState::Loading(d)),
+                    StageState::Next(d) => {
+                        *stage += 1;
+                        data = d;
+                    }
+                    StageState::Loaded(asset) => return Ok(ProcessingState::Loaded(asset)),
+                }
+            },
+            |_| {},
+            frame_number,
+            pool,
+            strategy,
+        )
     }
 
     /// Process finished asset data and maintain the storage.
     /// This calls the `drop_fn` closure for assets that were removed from the storage.
+    ///
+    /// Returns `false` if there was nothing to do, see `process`.
     pub fn process_custom_drop<F, D>(
         &mut self,
         mut f: F,
-        mut drop_fn: D,
+        drop_fn: D,
         frame_number: u64,
         pool: &ThreadPool,
         strategy: Option<&HotReloadStrategy>,
-    ) where
+    ) -> bool
+    where
         D: FnMut(A),
         F: FnMut(A::Data) -> Result<ProcessingState<A>, Error>,
     {
+        self.process_staged(
+            |_: &mut usize, data| f(data),
+            drop_fn,
+            frame_number,
+            pool,
+            strategy,
+        )
+    }
+
+    /// Like `process_custom_drop`, but passes the current processing stage of each asset
+    /// to `f`, which is kept while the asset is re-queued.
+    fn process_staged<F, D>(
+        &mut self,
+        mut f: F,
+        mut drop_fn: D,
+        frame_number: u64,
+        pool: &ThreadPool,
+        strategy: Option<&HotReloadStrategy>,
+    ) -> bool
+    where
+        D: FnMut(A),
+        F: FnMut(&mut usize, A::Data) -> Result<ProcessingState<A>, Error>,
+    {
+        let mut busy = false;
+        self.frame_number = frame_number;
+        if !self.recycling.is_empty() {
+            let unused_handles = &self.unused_handles;
+            let storage_id = self.storage_id;
+            self.recycling.retain(|&(frame, id)| {
+                if frame > frame_number {
+                    return true;
+                }
+                unused_handles.push(Handle::new(id, storage_id));
+                false
+            });
+        }
+
+        if !self.delayed.is_empty() {
+            let (due, delayed): (Vec<_>, Vec<_>) = self
+                .delayed
+                .drain(..)
+                .partition(|&(frame, _)| frame <= frame_number);
+            self.delayed = delayed;
+            for (_, processed) in due {
+                self.processed.push(processed);
+            }
+        }
+
+        if self.paused {
+            let mut immediate = Vec::new();
+            while let Some(processed) = self.processed.pop() {
+                if processed.priority() == LoadPriority::Immediate {
+                    immediate.push(processed);
+                } else {
+                    self.held.push(processed);
+                }
+                busy = true;
+            }
+            for processed in immediate {
+                self.processed.push(processed);
+            }
+        }
+
+        self.expire_loads(Instant::now());
+
         {
             let mut requeue = Vec::new();
-            while let Ok(processed) = self.processed.pop() {
+            let mut queued = Vec::new();
+            while let Some(processed) = self.processed.pop() {
+                queued.push(processed);
+            }
+            busy |= !queued.is_empty();
+            if self.ordered {
+                queued.sort_by(|a, b| {
+                    b.priority()
+                        .cmp(&a.priority())
+                        .then(a.sequence().cmp(&b.sequence()))
+                });
+            }
+
+            for processed in queued {
                 let assets = &mut self.assets;
                 let bitset = &mut self.bitset;
                 let handles = &mut self.handles;
                 let reloads = &mut self.reloads;
+                let sink = &mut self.progress_sink;
+                let load_started = &mut self.load_started;
+                let load_times = &mut self.load_times;
+                let provenances = &mut self.provenances;
+                let pending = self.pending.get_mut();
+                let pending_stages = &mut self.pending_stages;
+                let delays = &mut self.delays;
+                let failed = &mut self.processing_failed;
+                let epoch = &self.epoch;
+                let names = &mut self.names;
+                let normalizer = &self.name_normalizer;
+                let mut extra = Vec::new();
 
                 let f = &mut f;
+                let catch_panics = self.catch_panics;
+                let transient_loads = self.transient_loads;
                 let (reload_obj, handle) = match processed {
                     Processed::NewAsset {
                         data,
                         handle,
                         name,
                         tracker,
+                        token,
+                        priority,
+                        requested,
+                        sequence,
+                        provenance,
                     } => {
-                        let (asset, reload_obj) = match data
-                            .map(|FormatValue { data, reload }| (data, reload))
-                            .and_then(|(d, rel)| f(d).map(|a| (a, rel)))
-                            .with_context(|_| error::Error::Asset(name.clone()))
-                        {
+                        if self.timed_out.remove(&handle.id()) {
+                            debug!(
+                                "{:?}: Asset {:?} (handle id: {:?}) timed out, dropping it",
+                                A::NAME,
+                                name,
+                                handle,
+                            );
+                            pending_stages.remove(&handle.id());
+                            continue;
+                        }
+
+                        // Re-queued assets keep the time they have been dequeued first.
+                        let started = *load_started.entry(handle.id()).or_insert_with(Instant::now);
+                        if token.as_ref().map(LoadToken::is_cancelled).unwrap_or(false) {
+                            load_started.remove(&handle.id());
+                            pending.remove(&handle.id());
+                            pending_stages.remove(&handle.id());
+                            debug!(
+                                "{:?}: Asset {:?} (handle id: {:?}) has been cancelled",
+                                A::NAME,
+                                name,
+                                handle,
+                            );
+                            report::<A>(sink, handle.id(), &name, requested, || {
+                                AssetProgressOutcome::Cancelled
+                            });
+                            tracker.cancel(handle.id(), A::NAME, name);
+
+                            continue;
+                        }
+
+                        let result = match data {
+                            Ok(FormatValue { data, reload }) => process_stage(
+                                &mut *f,
+                                pending_stages,
+                                delays,
+                                &mut extra,
+                                handle.id(),
+                                data,
+                                catch_panics,
+                            )
+                            .map(|a| (a, reload))
+                            .map_err(|cause| ProcessingError::Processing {
+                                id: handle.id(),
+                                name: name.clone(),
+                                cause,
+                            }),
+                            Err(cause) => Err(ProcessingError::Format {
+                                id: handle.id(),
+                                name: name.clone(),
+                                cause,
+                            }),
+                        };
+                        let (asset, reload_obj) = match result {
                             Ok((ProcessingState::Loaded(x), r)) => {
-                                debug!(
-                                        "{:?}: Asset {:?} (handle id: {:?}) has been loaded successfully",
-                                        A::NAME,
-                                        name,
-                                        handle,
-                                    );
-                                // Add a warning if a handle is unique (i.e. asset does not
-                                // need to be loaded as it is not used by anything)
-                                // https://github.com/amethyst/amethyst/issues/628
-                                if handle.is_unique() {
-                                    warn!(
-                                        "Loading unnecessary asset. Handle {} is unique ",
-                                        handle.id()
-                                    );
-                                    tracker.fail(
-                                        handle.id(),
-                                        A::NAME,
-                                        name,
-                                        Error::from(error::Error::UnusedHandle),
-                                    );
-                                } else {
-                                    tracker.success();
+                                load_started.remove(&handle.id());
+                                pending.remove(&handle.id());
+                                load_times.insert(handle.id(), started.elapsed());
+                                if let Some(ref provenance) = provenance {
+                                    provenances.insert(handle.id(), provenance.clone());
                                 }
+                                record_name(names, normalizer, &name, &handle);
+                                notify_loaded(
+                                    &handle,
+                                    name,
+                                    tracker,
+                                    requested,
+                                    sink,
+                                    failed,
+                                    transient_loads,
+                                );
 
                                 (x, r)
                             }
-                            Ok((ProcessingState::Loading(x), r)) => {
+                            Ok((ProcessingState::Loading(x), r))
+                            | Ok((ProcessingState::Delayed(x, _), r)) => {
                                 debug!(
                                         "{:?}: Asset {:?} (handle id: {:?}) is not complete, readding to queue",
                                         A::NAME,
@@ -302,17 +1645,38 @@ impl<A: Asset> AssetStorage<A> {
                                     handle,
                                     name,
                                     tracker,
+                                    token,
+                                    priority,
+                                    requested,
+                                    sequence,
+                                    provenance,
                                 });
                                 continue;
                             }
                             Err(e) => {
+                                if let Some(ref mut failed) = *failed {
+                                    failed(&e);
+                                }
+                                let mut e = Error::from(e);
+                                if let Some(ref provenance) = provenance {
+                                    e = format_err!("Asset was read from {}", provenance)
+                                        .with_source(e);
+                                }
                                 error!(
-                                    "{:?}: Asset {:?} (handle id: {:?}) could not be loaded: {}",
+                                    "{:?}: Asset {} (handle id: {:?}) could not be loaded: {}",
                                     A::NAME,
-                                    name,
+                                    ErrorLocation::describe(ErrorLocation::find(&e), &name),
                                     handle,
-                                    e,
+                                    e.causes()
+                                        .map(|cause| cause.to_string())
+                                        .collect::<Vec<_>>()
+                                        .join(": "),
                                 );
+                                load_started.remove(&handle.id());
+                                pending.remove(&handle.id());
+                                report::<A>(sink, handle.id(), &name, requested, || {
+                                    AssetProgressOutcome::Failed(e.to_string())
+                                });
                                 tracker.fail(handle.id(), A::NAME, name, e);
 
                                 continue;
@@ -321,6 +1685,7 @@ impl<A: Asset> AssetStorage<A> {
 
                         let id = handle.id();
                         bitset.add(id);
+                        epoch.fetch_add(1, Ordering::Relaxed);
                         handles.push(handle.clone());
 
                         // NOTE: the loader has to ensure that a handle will be used
@@ -331,19 +1696,92 @@ impl<A: Asset> AssetStorage<A> {
 
                         (reload_obj, handle)
                     }
+                    Processed::Converted {
+                        asset,
+                        handle,
+                        name,
+                        tracker,
+                        requested,
+                        ..
+                    } => {
+                        record_name(names, normalizer, &name, &handle);
+                        notify_loaded(
+                            &handle,
+                            name,
+                            tracker,
+                            requested,
+                            sink,
+                            failed,
+                            transient_loads,
+                        );
+
+                        let id = handle.id();
+                        pending.remove(&id);
+                        bitset.add(id);
+                        epoch.fetch_add(1, Ordering::Relaxed);
+                        handles.push(handle.clone());
+
+                        unsafe {
+                            assets.insert(id, (asset, 0));
+                        }
+
+                        (None, handle)
+                    }
+                    Processed::Inserted { asset, handle, .. } => {
+                        let id = handle.id();
+                        pending.remove(&id);
+                        bitset.add(id);
+                        epoch.fetch_add(1, Ordering::Relaxed);
+                        handles.push(handle.clone());
+
+                        unsafe {
+                            assets.insert(id, (asset, 0));
+                        }
+
+                        (None, handle)
+                    }
                     Processed::HotReload {
                         data,
                         handle,
                         name,
                         old_reload,
+                        sequence,
                     } => {
+                        // The asset might have been unloaded while reloading. Its id can't
+                        // have been reused, as the reload holds on to the handle.
+                        if !bitset.contains(handle.id()) {
+                            debug!(
+                                "{:?}: Asset {:?} (handle id: {:?}) has been unloaded, dropping reload",
+                                A::NAME,
+                                name,
+                                handle,
+                            );
+                            reload_done(&mut self.reloads_in_flight);
+                            if let Some(ref mut finished) = self.reload_finished {
+                                finished(handle.id(), false);
+                            }
+                            continue;
+                        }
+
                         let (asset, reload_obj) = match data
                             .map(|FormatValue { data, reload }| (data, reload))
-                            .and_then(|(d, rel)| f(d).map(|a| (a, rel)))
+                            .and_then(|(d, rel)| {
+                                process_stage(
+                                    &mut *f,
+                                    pending_stages,
+                                    delays,
+                                    &mut extra,
+                                    handle.id(),
+                                    d,
+                                    catch_panics,
+                                )
+                                .map(|a| (a, rel))
+                            })
                             .with_context(|_| error::Error::Asset(name.clone()))
                         {
                             Ok((ProcessingState::Loaded(x), r)) => (x, r),
-                            Ok((ProcessingState::Loading(x), r)) => {
+                            Ok((ProcessingState::Loading(x), r))
+                            | Ok((ProcessingState::Delayed(x, _), r)) => {
                                 debug!(
                                     "{:?}: Asset {:?} (handle id: {:?}) is not complete, readding to queue",
                                     A::NAME,
@@ -355,6 +1793,7 @@ impl<A: Asset> AssetStorage<A> {
                                     handle,
                                     name,
                                     old_reload,
+                                    sequence,
                                 });
                                 continue;
                             }
@@ -368,7 +1807,37 @@ impl<A: Asset> AssetStorage<A> {
                                     e,
                                 );
 
-                                reloads.push((handle.downgrade(), old_reload));
+                                if let Some(old_reload) = old_reload {
+                                    reloads.push((handle.downgrade(), old_reload));
+                                }
+                                reload_done(&mut self.reloads_in_flight);
+                                if let Some(ref mut finished) = self.reload_finished {
+                                    finished(handle.id(), false);
+                                }
+                                let failure = self
+                                    .reload_failures
+                                    .entry(handle.id())
+                                    .or_insert_with(|| ReloadFailure {
+                                        name: name.clone(),
+                                        error: String::new(),
+                                        first_failed_at: SystemTime::now(),
+                                        attempts: 0,
+                                    });
+                                failure.error = e
+                                    .causes()
+                                    .map(|cause| cause.to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(": ");
+                                failure.attempts += 1;
+                                publish_reload(
+                                    &mut self.reload_events,
+                                    HotReloadEvent {
+                                        handle: handle.downgrade(),
+                                        name,
+                                        new_version: unsafe { self.assets.get(handle.id()).1 },
+                                        outcome: ReloadOutcome::Failed(e.to_string()),
+                                    },
+                                );
 
                                 continue;
                             }
@@ -381,8 +1850,47 @@ impl<A: Asset> AssetStorage<A> {
                             handle,
                         );
                         let data = unsafe { self.assets.get_mut(id) };
-                        data.1 += 1;
-                        drop_fn(std::mem::replace(&mut data.0, asset));
+                        let outcome = if self
+                            .skip_identical
+                            .map(|eq| eq(&data.0, &asset))
+                            .unwrap_or(false)
+                        {
+                            debug!(
+                                "{:?}: Asset {:?} (handle id: {:?}) did not change, skipping reload",
+                                A::NAME,
+                                name,
+                                handle,
+                            );
+                            drop_fn(asset);
+                            ReloadOutcome::Unchanged
+                        } else {
+                            data.1 += 1;
+                            epoch.fetch_add(1, Ordering::Relaxed);
+                            drop_fn(std::mem::replace(&mut data.0, asset));
+                            ReloadOutcome::Reloaded
+                        };
+                        let new_version = data.1;
+                        // Assets imported again by `Loader::reload` are watched by the new
+                        // reload object only.
+                        if old_reload.is_none() && reload_obj.is_some() {
+                            reloads.retain(|&(ref weak, _)| {
+                                weak.upgrade().map(|h| h.id() != id).unwrap_or(false)
+                            });
+                        }
+                        reload_done(&mut self.reloads_in_flight);
+                        self.reload_failures.remove(&id);
+                        if let Some(ref mut finished) = self.reload_finished {
+                            finished(id, true);
+                        }
+                        publish_reload(
+                            &mut self.reload_events,
+                            HotReloadEvent {
+                                handle: handle.downgrade(),
+                                name,
+                                new_version,
+                                outcome,
+                            },
+                        );
 
                         (reload_obj, handle)
                     }
@@ -392,56 +1900,259 @@ impl<A: Asset> AssetStorage<A> {
                 if let Some(reload_obj) = reload_obj {
                     reloads.push((handle.downgrade(), reload_obj));
                 }
+
+                // Add the other assets of `ProcessingState::Multiple`.
+                for (handle, asset) in extra {
+                    let id = handle.id();
+                    self.pending.get_mut().remove(&id);
+                    if self.bitset.contains(id) {
+                        let data = unsafe { self.assets.get_mut(id) };
+                        data.1 += 1;
+                        drop_fn(std::mem::replace(&mut data.0, asset));
+                    } else {
+                        self.bitset.add(id);
+                        self.handles.push(handle);
+                        unsafe {
+                            self.assets.insert(id, (asset, 0));
+                        }
+                    }
+                    self.epoch.fetch_add(1, Ordering::Relaxed);
+                }
             }
 
             for p in requeue.drain(..) {
-                self.processed.push(p);
+                match self.delays.remove(&p.handle_id()) {
+                    Some(frames) => self.delayed.push((frame_number + frames, p)),
+                    None => self.processed.push(p),
+                }
             }
         }
 
-        let mut count = 0;
-        let mut skip = 0;
-        while let Some(i) = self.handles.iter().skip(skip).position(Handle::is_unique) {
-            count += 1;
-            // Re-normalize index
-            let i = skip + i;
-            skip = i;
-            let handle = self.handles.swap_remove(i);
-            let id = handle.id();
-            unsafe {
-                let (asset, _) = self.assets.remove(id);
-                drop_fn(asset);
+        // Reloads skipped because the contents didn't change go back to being watched.
+        while let Ok((handle, rel)) = self.unchanged_reloads.pop() {
+            reload_done(&mut self.reloads_in_flight);
+            if let Some(handle) = handle.upgrade() {
+                self.reload_failures.remove(&handle.id());
+            }
+            if let (Some(finished), Some(handle)) =
+                (self.reload_finished.as_mut(), handle.upgrade())
+            {
+                finished(handle.id(), true);
             }
-            self.bitset.remove(id);
+            if let Some(new_version) = handle.upgrade().and_then(|h| self.get_version(&h)) {
+                publish_reload(
+                    &mut self.reload_events,
+                    HotReloadEvent {
+                        handle: handle.clone(),
+                        name: rel.name(),
+                        new_version,
+                        outcome: ReloadOutcome::Unchanged,
+                    },
+                );
+            }
+            self.reloads.push((handle, rel));
+        }
 
-            // Can't reuse old handle here, because otherwise weak handles would still be valid.
-            // TODO: maybe just store u32?
-            self.unused_handles.push(Handle {
-                id: Arc::new(id),
-                marker: PhantomData,
-            });
+        // Files reported by a `FileWatcher` are kept until their reloads could be started.
+        if let Some(changed) = strategy.and_then(|s| s.changed_files(frame_number)) {
+            self.changed_files
+                .get_or_insert_with(Default::default)
+                .extend(changed.iter().cloned());
+        }
+        let reload_due = self.reloads_deferred
+            || strategy
+                .map(|s| s.needs_reload(frame_number))
+                .unwrap_or(false);
+        // Reloads requested for specific assets don't need all the others to be polled.
+        let reload_requested = !self.forced_reloads.is_empty()
+            || self.changed_files.is_some()
+            || !self.quiet_since.is_empty();
+
+        let mut count = 0;
+        // Without idle GC, dropped assets are only looked for while the storage is busy.
+        if self.idle_gc || busy || reload_due {
+            let mut skip = 0;
+            while let Some(i) = self.handles.iter().skip(skip).position(Handle::is_unique) {
+                count += 1;
+                // Re-normalize index
+                let i = skip + i;
+                skip = i;
+                let handle = self.handles.swap_remove(i);
+                let id = handle.id();
+                // The asset may be gone already, e.g. after `unload_all`.
+                if self.bitset.remove(id) {
+                    let (asset, _) = unsafe { self.assets.remove(id) };
+                    drop_fn(asset);
+                }
+                self.load_times.remove(&id);
+                self.provenances.remove(&id);
+                self.reload_failures.remove(&id);
+                self.epoch.fetch_add(1, Ordering::Relaxed);
+
+                self.recycle(id);
+            }
         }
         if count != 0 {
             debug!("{:?}: Freed {} handle ids", A::NAME, count,);
+            self.names.retain(|_, handle| !handle.is_dead());
+            busy = true;
         }
 
-        if strategy
-            .map(|s| s.needs_reload(frame_number))
-            .unwrap_or(false)
-        {
+        if reload_due || reload_requested {
             trace!("{:?}: Testing for asset reloads..", A::NAME);
-            self.hot_reload(pool);
+            let compare = strategy.map(|s| s.compares_contents()).unwrap_or(false);
+            let quiet = strategy.and_then(HotReloadStrategy::quiet_period);
+            let now = Instant::now();
+            busy |= match (self.reload_pool.clone(), self.io_pool.clone()) {
+                (Some(reload_pool), _) => self.hot_reload(compare, quiet, reload_due, now, |job| {
+                    reload_pool.spawn(job)
+                }),
+                (None, Some(io_pool)) => self.hot_reload(compare, quiet, reload_due, now, |job| {
+                    io_pool.spawn(LoadPriority::Normal.into(), job)
+                }),
+                (None, None) => {
+                    self.hot_reload(compare, quiet, reload_due, now, |job| pool.spawn(job))
+                }
+            };
         }
+
+        busy
     }
 
-    fn hot_reload(&mut self, pool: &ThreadPool) {
-        self.reloads.retain(|&(ref handle, _)| !handle.is_dead());
-        while let Some(p) = self
-            .reloads
+    /// Fails the loads which didn't finish before their deadline by `now`, see
+    /// `watch_deadline`.
+    fn expire_loads(&mut self, now: Instant) {
+        let pending = self.pending.get_mut();
+        let deadlines = self.deadlines.get_mut();
+        deadlines.retain(|id, _| pending.contains(id));
+        if deadlines.is_empty() {
+            return;
+        }
+
+        let expired: Vec<u32> = deadlines
             .iter()
-            .position(|&(_, ref rel)| rel.needs_reload())
+            .filter(|&(_, deadline)| deadline.at <= now)
+            .map(|(&id, _)| id)
+            .collect();
+        for id in expired {
+            let deadline = deadlines.remove(&id).expect("Expired deadline is missing");
+            // Loads which started processing have been read already.
+            let stage = if self.load_started.remove(&id).is_some() {
+                LoadStage::Processing
+            } else {
+                LoadStage::Reading
+            };
+            let e = ProcessingError::Timeout {
+                id,
+                name: deadline.name.clone(),
+                elapsed: now.duration_since(deadline.requested),
+                stage,
+            };
+            if let Some(ref mut failed) = self.processing_failed {
+                failed(&e);
+            }
+            let e = Error::from(e);
+            error!(
+                "{:?}: Asset {:?} (handle id: {:?}) could not be loaded: {}",
+                A::NAME,
+                deadline.name,
+                id,
+                e,
+            );
+
+            if let Some(ref token) = deadline.token {
+                token.cancel();
+            }
+            pending.remove(&id);
+            self.timed_out.insert(id);
+            report::<A>(
+                &mut self.progress_sink,
+                id,
+                &deadline.name,
+                deadline.requested,
+                || AssetProgressOutcome::Failed(e.to_string()),
+            );
+            if let Some(tracker) = deadline.tracker.lock().take() {
+                tracker.fail(id, A::NAME, deadline.name, e);
+            }
+        }
+    }
+
+    /// Starts reloading the assets which need it, returning `true` if any reload started.
+    ///
+    /// If a `FileWatcher` reported changed files, only the assets read from them are
+    /// reloaded, without asking their reload objects. Otherwise, with a `quiet` period,
+    /// reloads only start once the files of the asset stopped changing for that long.
+    ///
+    /// Unless `poll` is `true`, only the reloads requested with `request_reload` or
+    /// `notify_file_changed` and the ones waiting for their files to settle are looked at.
+    /// Quiet periods are measured up to `now`.
+    fn hot_reload<S>(
+        &mut self,
+        compare: bool,
+        quiet: Option<Duration>,
+        poll: bool,
+        now: Instant,
+        mut spawn: S,
+    ) -> bool
+    where
+        S: FnMut(Box<dyn FnOnce() + Send>),
+    {
+        let mut started_any = false;
+        self.reloads
+            .retain(|&(ref handle, ref rel)| !handle.is_dead() && rel.is_available());
+        self.reloads_deferred = false;
+        let changed = self.changed_files.take();
+        let mut forced = std::mem::replace(&mut self.forced_reloads, FnvHashSet::default());
+        let mut started_files = Vec::new();
+        let mut quiet_since = std::mem::replace(&mut self.quiet_since, FnvHashMap::default());
+        let mut waiting = FnvHashMap::default();
+        while let Some(p) =
+            self.reloads
+                .iter()
+                .position(|&(ref handle, ref rel)| match (changed.as_ref(), quiet) {
+                    _ if is_forced(&forced, handle) => true,
+                    (Some(changed), _) => rel.files().iter().any(|file| changed.contains(file)),
+                    (None, None) => poll && rel.needs_reload(),
+                    (None, Some(quiet)) => {
+                        let id = match handle.upgrade() {
+                            Some(handle) => handle.id(),
+                            None => return false,
+                        };
+                        // Reloads waiting for their files to settle are known to be needed.
+                        !waiting.contains_key(&id)
+                            && (quiet_since.contains_key(&id) || poll && rel.needs_reload())
+                            && is_quiet(
+                                &mut quiet_since,
+                                &mut waiting,
+                                id,
+                                rel.last_modified(),
+                                quiet,
+                                now,
+                            )
+                    }
+                })
         {
+            if let Some(max) = self.max_concurrent_reloads {
+                if self.reloads_in_flight.load(Ordering::Relaxed) >= max {
+                    trace!(
+                        "{:?}: Too many reloads in flight, deferring the rest",
+                        A::NAME
+                    );
+                    self.reloads_deferred = true;
+                    break;
+                }
+            }
+
             let (handle, rel): (WeakHandle<_>, Box<dyn Reload<_>>) = self.reloads.swap_remove(p);
+            if changed.is_some() {
+                started_files.extend(rel.files());
+            }
+            // Forced reloads happen even if the contents are the same.
+            let compare = match handle.upgrade() {
+                Some(ref handle) if forced.remove(&handle.id()) => false,
+                _ => compare,
+            };
 
             let name = rel.name();
             let format = rel.format();
@@ -456,21 +2167,455 @@ impl<A: Asset> AssetStorage<A> {
             );
 
             if let Some(handle) = handle {
+                if let Some(ref mut started) = self.reload_started {
+                    started(handle.id());
+                }
+                self.reloads_in_flight.fetch_add(1, Ordering::Relaxed);
+                started_any = true;
+
                 let processed = self.processed.clone();
-                pool.spawn(move || {
-                    let old_reload = rel.clone();
-                    let data = rel.reload().with_context(|_| error::Error::Format(format));
+                let sequence = processed.next_sequence();
+                let unchanged = self.unchanged_reloads.clone();
+                spawn(Box::new(move || {
+                    let old_reload = Some(rel.clone());
+                    let data = if compare {
+                        match rel.reload_if_changed() {
+                            Ok(Reloaded::Changed(data)) => Ok(data),
+                            Ok(Reloaded::Unchanged(rel)) => {
+                                unchanged.push((handle.downgrade(), rel));
+                                return;
+                            }
+                            Err(e) => Err(e),
+                        }
+                    } else {
+                        rel.reload()
+                    };
+                    let data = data.with_context(|_| error::Error::Format(format));
 
                     let p = Processed::HotReload {
                         data,
                         name,
                         handle,
                         old_reload,
+                        sequence,
                     };
                     processed.push(p);
-                });
+                }));
+            }
+        }
+        // Reloads which weren't looked at keep waiting for their files to settle.
+        if self.reloads_deferred || changed.is_some() {
+            waiting.extend(quiet_since);
+        }
+        // Keep the files of deferred reloads, but don't reload the started ones again.
+        if self.reloads_deferred {
+            if let Some(mut changed) = changed {
+                for file in &started_files {
+                    changed.remove(file);
+                }
+                self.changed_files = Some(changed);
+            }
+            self.forced_reloads = forced;
+        }
+        // Reloads waiting for their files to settle are checked again by the next `process`,
+        // without polling the other assets.
+        self.quiet_since = waiting;
+
+        started_any
+    }
+}
+
+/// Returns `true` if a reload of the asset of `handle` has been requested with
+/// `AssetStorage::request_reload`.
+fn is_forced<A>(forced: &FnvHashSet<u32>, handle: &WeakHandle<A>) -> bool {
+    !forced.is_empty()
+        && handle
+            .upgrade()
+            .map(|handle| forced.contains(&handle.id()))
+            .unwrap_or(false)
+}
+
+/// A reload waiting for the files of its asset to stop changing, see
+/// `HotReloadStrategy::with_quiet_period`.
+struct QuietPeriod {
+    /// The modification time of the files when the last change was noticed.
+    modified: Option<u64>,
+    /// When the last change was noticed.
+    noticed: Instant,
+}
+
+/// Returns `true` if no change of the files of the asset with the given id has been
+/// noticed for `quiet`. Changes are noticed when `Reload::last_modified` differs from
+/// the last time it was checked. Otherwise, the reload is moved to `waiting`.
+fn is_quiet(
+    quiet_since: &mut FnvHashMap<u32, QuietPeriod>,
+    waiting: &mut FnvHashMap<u32, QuietPeriod>,
+    id: u32,
+    modified: Option<u64>,
+    quiet: Duration,
+    now: Instant,
+) -> bool {
+    let period = quiet_since
+        .remove(&id)
+        .filter(|period| period.modified == modified)
+        .unwrap_or(QuietPeriod {
+            modified,
+            noticed: now,
+        });
+    if now.duration_since(period.noticed) >= quiet {
+        return true;
+    }
+    waiting.insert(id, period);
+
+    false
+}
+
+/// A load which fails if it doesn't finish in time, see `AssetStorage::watch_deadline`.
+struct Deadline {
+    at: Instant,
+    name: String,
+    requested: Instant,
+    token: Option<LoadToken>,
+    tracker: TrackerSlot,
+}
+
+/// Tracker shared between a load and its `Deadline`, reporting only whichever finishes first.
+struct SharedTracker(TrackerSlot);
+
+impl Tracker for SharedTracker {
+    fn success(self: Box<Self>) {
+        if let Some(tracker) = self.0.lock().take() {
+            tracker.success();
+        }
+    }
+
+    fn fail(
+        self: Box<Self>,
+        handle_id: u32,
+        asset_type_name: &'static str,
+        asset_name: String,
+        error: Error,
+    ) {
+        if let Some(tracker) = self.0.lock().take() {
+            tracker.fail(handle_id, asset_type_name, asset_name, error);
+        }
+    }
+
+    fn cancel(self: Box<Self>, handle_id: u32, asset_type_name: &'static str, asset_name: String) {
+        if let Some(tracker) = self.0.lock().take() {
+            tracker.cancel(handle_id, asset_type_name, asset_name);
+        }
+    }
+}
+
+/// Publishes a `HotReloadEvent`, if the channel has been created by
+/// `AssetStorage::reload_events_mut`.
+fn publish_reload<A: Asset>(
+    channel: &mut Option<EventChannel<HotReloadEvent<A>>>,
+    event: HotReloadEvent<A>,
+) {
+    if let Some(channel) = channel {
+        channel.single_write(event);
+    }
+}
+
+/// Marks a hot reload as done, see `AssetStorage::set_max_concurrent_reloads`.
+fn reload_done(in_flight: &mut AtomicUsize) {
+    let in_flight = in_flight.get_mut();
+    *in_flight = in_flight.saturating_sub(1);
+}
+
+/// Builds an `AssetStorage` holding given assets, created with `AssetStorage::mock`.
+///
+/// The storage is a regular one, but no thread pool is needed as long as it's
+/// never processed.
+///
+/// ```
+/// # use amethyst_assets::{Asset, AssetStorage, Handle};
+/// # use amethyst_core::ecs::prelude::VecStorage;
+/// # struct Texture(u32);
+/// # impl Asset for Texture {
+/// #     const NAME: &'static str = "Texture";
+/// #     type Data = u32;
+/// #     type HandleStorage = VecStorage<Handle<Texture>>;
+/// # }
+/// let (storage, handles) = AssetStorage::mock()
+///     .with(Texture(1))
+///     .with(Texture(2))
+///     .pending()
+///     .build();
+/// assert_eq!(2, storage.get(&handles[1]).unwrap().0);
+/// assert!(storage.get(&handles[2]).is_none());
+/// ```
+pub struct MockAssetStorage<A: Asset> {
+    handles: Vec<Handle<A>>,
+    storage: AssetStorage<A>,
+}
+
+impl<A: Asset> MockAssetStorage<A> {
+    /// Adds an asset to the storage.
+    pub fn with(mut self, asset: A) -> Self {
+        let handle = self.storage.insert(asset);
+        self.handles.push(handle);
+        self
+    }
+
+    /// Adds a handle whose asset is still loading, and so never appears.
+    pub fn pending(mut self) -> Self {
+        let handle = self.storage.allocate();
+        self.handles.push(handle);
+        self
+    }
+
+    /// Returns the storage and the handles of the added assets, in the order they
+    /// were added.
+    pub fn build(self) -> (AssetStorage<A>, Vec<Handle<A>>) {
+        (self.storage, self.handles)
+    }
+}
+
+/// A read-only asset storage, created with `AssetStorage::freeze`.
+///
+/// As it can't be mutated, it can be shared between threads (e.g. using an `Arc`)
+/// without needing to synchronize access. Only the assets and their handles are kept,
+/// loads which are still in flight when the storage gets frozen are dropped.
+pub struct FrozenAssetStorage<A: Asset> {
+    assets: VecStorage<(A, u32)>,
+    bitset: BitSet,
+    handles: Vec<Handle<A>>,
+    /// The next id the frozen storage would have allocated.
+    next_id: usize,
+    /// The id of the frozen storage, to accept its handles.
+    storage_id: u64,
+}
+
+impl<A: Asset> FrozenAssetStorage<A> {
+    /// Turns this storage back into a mutable `AssetStorage`, with the default settings.
+    pub fn unfreeze(mut self) -> AssetStorage<A> {
+        let mut storage = AssetStorage::new();
+        storage.assets = std::mem::replace(&mut self.assets, VecStorage::default());
+        storage.bitset = std::mem::replace(&mut self.bitset, BitSet::new());
+        storage.handles = std::mem::replace(&mut self.handles, Vec::new());
+        // Handles of the dropped loads may still be around, so their ids are not reused.
+        storage.handle_alloc = Allocator {
+            store_count: AtomicUsize::new(self.next_id),
+        };
+        storage.storage_id = self.storage_id;
+
+        storage
+    }
+
+    /// Get an asset from a given asset handle.
+    pub fn get(&self, handle: &Handle<A>) -> Option<&A> {
+        check_storage(self.storage_id, handle);
+        self.get_by_id(handle.id())
+    }
+
+    /// Get an asset by it's handle id.
+    pub fn get_by_id(&self, id: u32) -> Option<&A> {
+        if self.bitset.contains(id) {
+            Some(unsafe { &self.assets.get(id).0 })
+        } else {
+            None
+        }
+    }
+
+    /// Check if given handle points to a valid asset in the storage.
+    pub fn contains(&self, handle: &Handle<A>) -> bool {
+        check_storage(self.storage_id, handle);
+        self.bitset.contains(handle.id())
+    }
+
+    /// Check if given asset id points to a valid asset in the storage.
+    pub fn contains_id(&self, id: u32) -> bool {
+        self.bitset.contains(id)
+    }
+
+    /// Iterates over all assets in the storage, together with their handles.
+    pub fn iter(&self) -> impl Iterator<Item = (&Handle<A>, &A)> {
+        self.handles
+            .iter()
+            .filter_map(move |handle| self.get(handle).map(|asset| (handle, asset)))
+    }
+}
+
+impl<A: Asset> Drop for FrozenAssetStorage<A> {
+    fn drop(&mut self) {
+        let bitset = &self.bitset;
+        unsafe { self.assets.clean(bitset) }
+    }
+}
+
+/// Panics in debug builds if `handle` hasn't been created by the storage `storage_id`.
+#[inline]
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+fn check_storage<A: Asset>(storage_id: u64, handle: &Handle<A>) {
+    #[cfg(debug_assertions)]
+    assert!(
+        handle.storage == 0 || handle.storage == storage_id,
+        "{:?}: Handle {:?} has been created by another storage",
+        A::NAME,
+        handle,
+    );
+}
+
+/// Calls the processing closure, turning a panic into an error if `catch_panics` is set.
+fn process_data<A, F>(
+    f: &mut F,
+    data: A::Data,
+    catch_panics: bool,
+) -> Result<ProcessingState<A>, Error>
+where
+    A: Asset,
+    F: FnMut(A::Data) -> Result<ProcessingState<A>, Error>,
+{
+    if !catch_panics {
+        return f(data);
+    }
+
+    panic::catch_unwind(AssertUnwindSafe(|| f(data))).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "<unknown>".to_string());
+
+        Err(format_err!("Processing panicked: {}", message))
+    })
+}
+
+/// Runs `f` with the current processing stage of the asset, remembering the
+/// stage if the asset has to be processed again.
+///
+/// `Delayed` is turned into `Loading`, remembering the delay in `delays`. `Multiple` is
+/// turned into `Loaded` with the asset of `id`, moving the other assets to `extra`.
+fn process_stage<A, F>(
+    f: &mut F,
+    pending_stages: &mut FnvHashMap<u32, usize>,
+    delays: &mut FnvHashMap<u32, u64>,
+    extra: &mut Vec<(Handle<A>, A)>,
+    id: u32,
+    data: A::Data,
+    catch_panics: bool,
+) -> Result<ProcessingState<A>, Error>
+where
+    A: Asset,
+    F: FnMut(&mut usize, A::Data) -> Result<ProcessingState<A>, Error>,
+{
+    let mut stage = pending_stages.remove(&id).unwrap_or(0);
+    let result = match process_data(&mut |data| f(&mut stage, data), data, catch_panics) {
+        Ok(ProcessingState::Delayed(data, frames)) => {
+            delays.insert(id, frames);
+            Ok(ProcessingState::Loading(data))
+        }
+        Ok(ProcessingState::Multiple(mut assets)) => {
+            match assets.iter().position(|&(ref handle, _)| handle.id() == id) {
+                Some(i) => {
+                    let (_, asset) = assets.swap_remove(i);
+                    *extra = assets;
+                    Ok(ProcessingState::Loaded(asset))
+                }
+                None => Err(format_err!(
+                    "None of the {} processed assets is the one of handle id {}",
+                    assets.len(),
+                    id
+                )),
             }
         }
+        result => result,
+    };
+    if let Ok(ProcessingState::Loading(_)) = result {
+        if stage != 0 {
+            pending_stages.insert(id, stage);
+        }
+    }
+
+    result
+}
+
+/// Records the name of a loaded asset for `AssetStorage::get_handle_by_name`.
+fn record_name<A: Asset>(
+    names: &mut FnvHashMap<String, WeakHandle<A>>,
+    normalizer: &Option<NameNormalizer>,
+    name: &str,
+    handle: &Handle<A>,
+) {
+    let name = match *normalizer {
+        Some(ref normalize) => normalize(name),
+        None => name.to_owned(),
+    };
+    names.insert(name, handle.downgrade());
+}
+
+/// Notifies the tracker of an asset which has been loaded successfully.
+fn notify_loaded<A: Asset>(
+    handle: &Handle<A>,
+    name: String,
+    tracker: Box<dyn Tracker>,
+    requested: Instant,
+    sink: &mut Option<ProgressSink>,
+    failed: &mut Option<ProcessingFailed>,
+    transient: bool,
+) {
+    debug!(
+        "{:?}: Asset {:?} (handle id: {:?}) has been loaded successfully",
+        A::NAME,
+        name,
+        handle,
+    );
+    // Add a warning if a handle is unique (i.e. asset does not
+    // need to be loaded as it is not used by anything)
+    // https://github.com/amethyst/amethyst/issues/628
+    if handle.is_unique() && !transient {
+        warn!(
+            "Loading unnecessary asset. Handle {} is unique ",
+            handle.id()
+        );
+        let e = ProcessingError::UnusedHandle {
+            id: handle.id(),
+            name: name.clone(),
+        };
+        if let Some(ref mut failed) = *failed {
+            failed(&e);
+        }
+        let e = Error::from(e);
+        report::<A>(sink, handle.id(), &name, requested, || {
+            AssetProgressOutcome::Failed(e.to_string())
+        });
+        tracker.fail(handle.id(), A::NAME, name, e);
+    } else {
+        if handle.is_unique() {
+            debug!(
+                "{:?}: Transient asset {:?} (handle id: {:?}) is no longer used",
+                A::NAME,
+                name,
+                handle,
+            );
+        }
+        report::<A>(sink, handle.id(), &name, requested, || {
+            AssetProgressOutcome::Loaded
+        });
+        tracker.success();
+    }
+}
+
+/// Passes an `AssetProgressEvent` to the sink, if there is one.
+fn report<A: Asset>(
+    sink: &mut Option<ProgressSink>,
+    handle_id: u32,
+    name: &str,
+    requested: Instant,
+    outcome: impl FnOnce() -> AssetProgressOutcome,
+) {
+    if let Some(sink) = sink {
+        sink(AssetProgressEvent {
+            handle_id,
+            asset_type_name: A::NAME,
+            asset_name: name.to_owned(),
+            duration: requested.elapsed(),
+            outcome: outcome(),
+            timestamp: SystemTime::now(),
+        });
     }
 }
 
@@ -479,15 +2624,78 @@ impl<A: Asset> Default for AssetStorage<A> {
         AssetStorage {
             assets: Default::default(),
             bitset: Default::default(),
+            catch_panics: false,
+            changed_files: None,
+            deadlines: Default::default(),
+            delayed: Default::default(),
+            delays: Default::default(),
+            epoch: AtomicU64::new(0),
+            forced_reloads: Default::default(),
+            frame_number: 0,
             handles: Default::default(),
             handle_alloc: Default::default(),
-            processed: Arc::new(SegQueue::new()),
+            paused: false,
+            held: Default::default(),
+            idle_gc: true,
+            io_pool: None,
+            load_started: Default::default(),
+            load_times: Default::default(),
+            max_concurrent_reloads: None,
+            name_normalizer: None,
+            names: Default::default(),
+            ordered: false,
+            pending: Default::default(),
+            pending_stages: Default::default(),
+            processing_failed: None,
+            pinned: Default::default(),
+            provenances: Default::default(),
+            processed: Arc::new(ProcessedQueue::new()),
+            progress_sink: None,
+            quiet_since: Default::default(),
+            recycle_policy: RecyclePolicy::default(),
+            recycling: Default::default(),
+            reload_events: None,
+            reload_failures: Default::default(),
+            reload_finished: None,
+            reload_pool: None,
+            reload_started: None,
             reloads: Default::default(),
+            reloads_deferred: false,
+            reloads_in_flight: AtomicUsize::new(0),
+            skip_identical: None,
+            storage_id: NEXT_STORAGE_ID.fetch_add(1, Ordering::Relaxed),
+            timed_out: Default::default(),
+            transient_loads: false,
+            unchanged_reloads: Arc::new(SegQueue::new()),
             unused_handles: SegQueue::new(),
         }
     }
 }
 
+#[cfg(test)]
+impl<A: Asset> AssetStorage<A> {
+    /// Processes the storage until `done` returns `true`, failing the test if that
+    /// doesn't happen within a few seconds.
+    pub(crate) fn process_until<F, C>(&mut self, mut f: F, pool: &ThreadPool, mut done: C)
+    where
+        F: FnMut(A::Data) -> Result<ProcessingState<A>, Error>,
+        C: FnMut(&Self) -> bool,
+    {
+        for frame_number in 0.. {
+            self.process(&mut f, frame_number, pool, None);
+            if done(self) {
+                return;
+            }
+            assert!(
+                frame_number < 5000,
+                "{:?}: Storage did not finish processing in time",
+                A::NAME
+            );
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
 impl<A: Asset> Drop for AssetStorage<A> {
     fn drop(&mut self) {
         let bitset = &self.bitset;
@@ -501,8 +2709,8 @@ impl<A: Asset> Drop for AssetStorage<A> {
 ///
 /// This system can only be used if the asset data implements
 /// `Into<Result<A, BoxedErr>>`.
-#[derive(Default)]
 pub struct Processor<A> {
+    idle_gc: Option<bool>,
     marker: PhantomData<A>,
 }
 
@@ -511,9 +2719,26 @@ impl<A> Processor<A> {
     /// assets of type `A`.
     pub fn new() -> Self {
         Processor {
+            idle_gc: None,
             marker: PhantomData,
         }
     }
+
+    /// If `false`, the storage only looks for assets whose handles have all been dropped
+    /// on frames where something was loaded or a hot reload is due, instead of every
+    /// frame. See `AssetStorage::set_idle_gc`.
+    ///
+    /// This is applied to the storage once, when the system is set up.
+    pub fn with_idle_gc(mut self, idle_gc: bool) -> Self {
+        self.idle_gc = Some(idle_gc);
+        self
+    }
+}
+
+impl<A> Default for Processor<A> {
+    fn default() -> Self {
+        Processor::new()
+    }
 }
 
 impl<'a, A> System<'a> for Processor<A>
@@ -525,21 +2750,110 @@ where
         ReadExpect<'a, Arc<ThreadPool>>,
         Read<'a, Time>,
         Option<Read<'a, HotReloadStrategy>>,
+        Option<Read<'a, HotReloadStrategyFor<A>>>,
     );
 
-    fn run(&mut self, (mut storage, pool, time, strategy): Self::SystemData) {
+    fn run(&mut self, (mut storage, pool, time, strategy, strategy_for): Self::SystemData) {
         #[cfg(feature = "profiler")]
         profile_scope!("processor_system");
 
         use std::ops::Deref;
-
         storage.process(
             ProcessableAsset::process,
             time.frame_number(),
             &**pool,
-            strategy.as_ref().map(Deref::deref),
+            HotReloadStrategyFor::select(
+                strategy_for.as_ref().map(Deref::deref),
+                strategy.as_ref().map(Deref::deref),
+            ),
+        );
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        use amethyst_core::ecs::prelude::SystemData;
+        Self::SystemData::setup(res);
+        let mut storage = res.fetch_mut::<AssetStorage<A>>();
+        if let Some(idle_gc) = self.idle_gc {
+            storage.set_idle_gc(idle_gc);
+        }
+        if let Some(loader) = res.try_fetch::<Loader>() {
+            storage.set_io_pool(loader.io_pool());
+        }
+    }
+}
+
+/// Like `Processor`, but calls a closure for every asset which is removed from the storage,
+/// e.g. to release resources the asset refers to.
+pub struct ProcessorWithDrop<A> {
+    drop_fn: Box<dyn FnMut(A) + Send>,
+    idle_gc: Option<bool>,
+}
+
+impl<A> ProcessorWithDrop<A> {
+    /// Creates a new asset processor for assets of type `A`,
+    /// calling `drop_fn` for removed assets.
+    pub fn new<D>(drop_fn: D) -> Self
+    where
+        D: FnMut(A) + Send + 'static,
+    {
+        ProcessorWithDrop {
+            drop_fn: Box::new(drop_fn),
+            idle_gc: None,
+        }
+    }
+
+    /// If `false`, the storage only looks for assets whose handles have all been dropped
+    /// on frames where something was loaded or a hot reload is due, instead of every
+    /// frame. See `AssetStorage::set_idle_gc`.
+    ///
+    /// This is applied to the storage once, when the system is set up.
+    pub fn with_idle_gc(mut self, idle_gc: bool) -> Self {
+        self.idle_gc = Some(idle_gc);
+        self
+    }
+}
+
+impl<'a, A> System<'a> for ProcessorWithDrop<A>
+where
+    A: Asset + ProcessableAsset,
+{
+    type SystemData = (
+        Write<'a, AssetStorage<A>>,
+        ReadExpect<'a, Arc<ThreadPool>>,
+        Read<'a, Time>,
+        Option<Read<'a, HotReloadStrategy>>,
+        Option<Read<'a, HotReloadStrategyFor<A>>>,
+    );
+
+    fn run(&mut self, (mut storage, pool, time, strategy, strategy_for): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("processor_with_drop_system");
+
+        use std::ops::Deref;
+
+        storage.process_custom_drop(
+            ProcessableAsset::process,
+            &mut self.drop_fn,
+            time.frame_number(),
+            &**pool,
+            HotReloadStrategyFor::select(
+                strategy_for.as_ref().map(Deref::deref),
+                strategy.as_ref().map(Deref::deref),
+            ),
         );
     }
+
+    fn setup(&mut self, res: &mut Resources) {
+        use amethyst_core::ecs::prelude::SystemData;
+        Self::SystemData::setup(res);
+        let mut storage = res.fetch_mut::<AssetStorage<A>>();
+        if let Some(idle_gc) = self.idle_gc {
+            storage.set_idle_gc(idle_gc);
+        }
+        if let Some(loader) = res.try_fetch::<Loader>() {
+            storage.set_io_pool(loader.io_pool());
+        }
+    }
 }
 
 /// A handle to an asset. This is usually what the
@@ -555,11 +2869,25 @@ where
 )]
 pub struct Handle<A: ?Sized> {
     id: Arc<u32>,
+    /// The id of the storage which created the handle, `0` if unknown.
+    #[cfg(debug_assertions)]
+    #[derivative(Hash = "ignore", PartialEq = "ignore", Debug = "ignore")]
+    storage: u64,
     #[derivative(Debug = "ignore")]
     marker: PhantomData<A>,
 }
 
 impl<A> Handle<A> {
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    fn new(id: u32, storage: u64) -> Self {
+        Handle {
+            id: Arc::new(id),
+            #[cfg(debug_assertions)]
+            storage,
+            marker: PhantomData,
+        }
+    }
+
     /// Return the 32 bit id of this handle.
     pub fn id(&self) -> u32 {
         *self.id.as_ref()
@@ -571,10 +2899,32 @@ impl<A> Handle<A> {
 
         WeakHandle {
             id,
+            #[cfg(debug_assertions)]
+            storage: self.storage,
             marker: PhantomData,
         }
     }
 
+    /// Creates a handle from a raw id, as returned by `id`, e.g. to restore handles from
+    /// a saved scene.
+    ///
+    /// **Be careful**, the returned handle is not connected to the other handles pointing
+    /// at the same asset:
+    ///
+    /// * It doesn't keep the asset alive. Once all other handles are dropped, the asset is
+    ///   freed and its id may be reused for a different asset, which this handle then
+    ///   silently points at.
+    /// * `WeakHandle`s created from it don't know about the asset; they can't be upgraded
+    ///   anymore as soon as this handle is dropped.
+    /// * There is no check that an asset with this id exists at all, or that it is of the
+    ///   type the id was saved for.
+    ///
+    /// Pass it to `AssetStorage::rebind` as soon as possible to get a proper handle.
+    #[cfg(feature = "raw_handles")]
+    pub fn from_raw(id: u32) -> Self {
+        Handle::new(id, 0)
+    }
+
     /// Returns `true` if this is the only handle to the asset its pointing at.
     fn is_unique(&self) -> bool {
         Arc::strong_count(&self.id) == 1
@@ -594,23 +2944,115 @@ pub(crate) enum Processed<A: Asset> {
         handle: Handle<A>,
         name: String,
         tracker: Box<dyn Tracker>,
+        token: Option<LoadToken>,
+        priority: LoadPriority,
+        requested: Instant,
+        sequence: u64,
+        provenance: Option<Arc<Provenance>>,
+    },
+    Converted {
+        asset: A,
+        handle: Handle<A>,
+        name: String,
+        tracker: Box<dyn Tracker>,
+        requested: Instant,
+        sequence: u64,
+    },
+    Inserted {
+        asset: A,
+        handle: Handle<A>,
+        sequence: u64,
     },
     HotReload {
         data: Result<FormatValue<A::Data>, Error>,
         handle: Handle<A>,
         name: String,
-        old_reload: Box<dyn Reload<A::Data>>,
+        old_reload: Option<Box<dyn Reload<A::Data>>>,
+        sequence: u64,
     },
 }
 
-/// A weak handle, which is useful if you don't directly need the asset
-/// like in caches. This way, the asset can still get dropped (if you want that).
-#[derive(Derivative)]
-#[derivative(Clone(bound = ""))]
-pub struct WeakHandle<A> {
-    id: Weak<u32>,
-    marker: PhantomData<A>,
-}
+impl<A: Asset> Processed<A> {
+    fn priority(&self) -> LoadPriority {
+        match *self {
+            Processed::NewAsset { priority, .. } => priority,
+            Processed::Converted { .. }
+            | Processed::Inserted { .. }
+            | Processed::HotReload { .. } => LoadPriority::Normal,
+        }
+    }
+
+    fn sequence(&self) -> u64 {
+        match *self {
+            Processed::NewAsset { sequence, .. }
+            | Processed::Converted { sequence, .. }
+            | Processed::Inserted { sequence, .. }
+            | Processed::HotReload { sequence, .. } => sequence,
+        }
+    }
+
+    fn handle_id(&self) -> u32 {
+        match *self {
+            Processed::NewAsset { ref handle, .. }
+            | Processed::Converted { ref handle, .. }
+            | Processed::Inserted { ref handle, .. }
+            | Processed::HotReload { ref handle, .. } => handle.id(),
+        }
+    }
+}
+
+/// Queue of finished asset data waiting to be processed,
+/// with one lane per `LoadPriority`.
+pub(crate) struct ProcessedQueue<A: Asset> {
+    lanes: [SegQueue<Processed<A>>; 4],
+    next_sequence: AtomicU64,
+}
+
+impl<A: Asset> ProcessedQueue<A> {
+    fn new() -> Self {
+        ProcessedQueue {
+            lanes: [
+                SegQueue::new(),
+                SegQueue::new(),
+                SegQueue::new(),
+                SegQueue::new(),
+            ],
+            next_sequence: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns a sequence number for a load which is being issued,
+    /// greater than the ones of all loads issued before.
+    pub(crate) fn next_sequence(&self) -> u64 {
+        self.next_sequence.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Pushes finished asset data into the lane of its priority.
+    pub(crate) fn push(&self, processed: Processed<A>) {
+        self.lanes[processed.priority() as usize].push(processed);
+    }
+
+    /// Pops finished asset data, taking it from the lane with the highest priority first.
+    pub(crate) fn pop(&self) -> Option<Processed<A>> {
+        self.lanes.iter().rev().find_map(|lane| lane.pop().ok())
+    }
+
+    /// Returns the number of queued entries in all lanes.
+    fn len(&self) -> usize {
+        self.lanes.iter().map(SegQueue::len).sum()
+    }
+}
+
+/// A weak handle, which is useful if you don't directly need the asset
+/// like in caches. This way, the asset can still get dropped (if you want that).
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct WeakHandle<A> {
+    id: Weak<u32>,
+    #[cfg(debug_assertions)]
+    storage: u64,
+    marker: PhantomData<A>,
+}
 
 impl<A> WeakHandle<A> {
     /// Tries to upgrade to a `Handle`.
@@ -618,6 +3060,8 @@ impl<A> WeakHandle<A> {
     pub fn upgrade(&self) -> Option<Handle<A>> {
         self.id.upgrade().map(|id| Handle {
             id,
+            #[cfg(debug_assertions)]
+            storage: self.storage,
             marker: PhantomData,
         })
     }
@@ -628,3 +3072,2082 @@ impl<A> WeakHandle<A> {
         self.upgrade().is_none()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::{self, AssertUnwindSafe},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
+
+    #[cfg(feature = "async_source")]
+    use futures::{
+        channel::oneshot,
+        future::{BoxFuture, FutureExt},
+    };
+    use parking_lot::{Mutex, RwLock};
+    use rayon::{ThreadPool, ThreadPoolBuilder};
+
+    use amethyst_core::{
+        ecs::{prelude::VecStorage, RunNow, World},
+        Time,
+    };
+    use amethyst_error::{format_err, Error};
+
+    use super::{
+        AssetProcessor, AssetStorage, Handle, HandleStatus, Processed, ProcessingError,
+        ProcessingStage, ProcessingState, ProcessorWithDrop, QuietPeriod, RecyclePolicy,
+        ReloadInfo, StageState,
+    };
+    #[cfg(feature = "async_source")]
+    use crate::AsyncSource;
+    use crate::{
+        Asset, AssetProgressOutcome, Format, FormatValue, GetError, HotReloadStrategy,
+        LoadPriority, Loader, ProcessableAsset, Progress, ProgressCounter, Reload, ReloadOutcome,
+        Source, StreamingFormat, Tracker,
+    };
+
+    #[derive(PartialEq)]
+    struct TestAsset(String);
+
+    impl Asset for TestAsset {
+        const NAME: &'static str = "test::TestAsset";
+        type Data = String;
+        type HandleStorage = VecStorage<Handle<TestAsset>>;
+    }
+
+    impl ProcessableAsset for TestAsset {
+        fn process(data: String) -> Result<ProcessingState<Self>, Error> {
+            Ok(ProcessingState::Loaded(TestAsset(data)))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestFormat;
+
+    impl Format<String> for TestFormat {
+        fn name(&self) -> &'static str {
+            "TEST"
+        }
+
+        fn import_simple(&self, bytes: Vec<u8>) -> Result<String, Error> {
+            Ok(String::from_utf8(bytes)?)
+        }
+    }
+
+    /// In-memory source counting how often it has been loaded from.
+    #[derive(Clone, Default)]
+    struct CountingSource {
+        loads: Arc<AtomicUsize>,
+        modified: Arc<AtomicUsize>,
+    }
+
+    impl Source for CountingSource {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(self.modified.load(Ordering::Relaxed) as u64)
+        }
+
+        fn load(&self, _: &str) -> Result<Vec<u8>, Error> {
+            self.loads.fetch_add(1, Ordering::Relaxed);
+            Ok(b"data".to_vec())
+        }
+    }
+
+    fn pool() -> Arc<ThreadPool> {
+        Arc::new(
+            ThreadPoolBuilder::new()
+                .num_threads(1)
+                .build()
+                .expect("Invalid config"),
+        )
+    }
+
+    /// Finished data of a load, as the loader pushes it to a storage. The data is the
+    /// name of the asset, unless set otherwise.
+    struct NewAsset {
+        data: Result<String, Error>,
+        handle: Handle<TestAsset>,
+        name: String,
+        priority: LoadPriority,
+        sequence: Option<u64>,
+        tracker: Box<dyn Tracker>,
+    }
+
+    fn new_asset(handle: &Handle<TestAsset>, name: &str) -> NewAsset {
+        NewAsset {
+            data: Ok(name.to_owned()),
+            handle: handle.clone(),
+            name: name.to_owned(),
+            priority: LoadPriority::Normal,
+            sequence: None,
+            tracker: Box::new(()),
+        }
+    }
+
+    impl NewAsset {
+        fn data(mut self, data: &str) -> Self {
+            self.data = Ok(data.to_owned());
+            self
+        }
+
+        fn failed(mut self, error: Error) -> Self {
+            self.data = Err(error);
+            self
+        }
+
+        fn priority(mut self, priority: LoadPriority) -> Self {
+            self.priority = priority;
+            self
+        }
+
+        fn sequence(mut self, sequence: u64) -> Self {
+            self.sequence = Some(sequence);
+            self
+        }
+
+        fn tracker(mut self, tracker: Box<dyn Tracker>) -> Self {
+            self.tracker = tracker;
+            self
+        }
+
+        fn push(self, storage: &AssetStorage<TestAsset>) {
+            storage.processed.push(Processed::NewAsset {
+                data: self.data.map(FormatValue::data),
+                handle: self.handle,
+                name: self.name,
+                tracker: self.tracker,
+                token: None,
+                priority: self.priority,
+                requested: Instant::now(),
+                sequence: self
+                    .sequence
+                    .unwrap_or_else(|| storage.processed.next_sequence()),
+                provenance: None,
+            });
+        }
+    }
+
+    #[test]
+    fn assets_of_removed_sources_are_not_hot_reloaded() {
+        let pool = pool();
+        let loader = Loader::new(".", pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let source = CountingSource::default();
+        source.modified.store(1, Ordering::Relaxed);
+        loader.add_source("memory", source.clone());
+
+        let handle = loader.load_from("asset", TestFormat, "memory", (), &storage);
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.contains(&handle),
+        );
+        assert_eq!("data", storage.get(&handle).unwrap().0);
+        assert_eq!(1, storage.reloads.len());
+
+        assert!(loader.remove_source("memory"));
+        assert!(!loader.remove_source("memory"));
+        source.modified.store(2, Ordering::Relaxed);
+        storage.hot_reload(false, None, true, Instant::now(), |job| pool.spawn(job));
+
+        assert!(storage.reloads.is_empty());
+        assert_eq!(1, source.loads.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn assets_are_reloaded_from_bytes_on_the_pool() {
+        let pool = pool();
+        let loader = Loader::new(".", pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+        loader.add_source("memory", CountingSource::default());
+
+        let handle = loader.load_from("asset", TestFormat, "memory", (), &storage);
+        let inserted = storage.insert(TestAsset("inserted".to_owned()));
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.contains(&handle),
+        );
+
+        storage
+            .reload_from_bytes(&handle, b"bytes".to_vec(), TestFormat, &pool)
+            .unwrap();
+        assert!(storage
+            .reload_from_bytes(&inserted, b"bytes".to_vec(), TestFormat, &pool)
+            .is_err());
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.reloads_in_flight.load(Ordering::Relaxed) == 0,
+        );
+        assert_eq!("bytes", storage.get(&handle).unwrap().0);
+        assert_eq!(Some(1), storage.get_version(&handle));
+        assert_eq!("inserted", storage.get(&inserted).unwrap().0);
+    }
+
+    #[test]
+    fn contents_are_only_compared_if_the_loader_hashed_them() {
+        let pool = pool();
+        let mut loader = Loader::new(".", pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let source = CountingSource::default();
+        source.modified.store(1, Ordering::Relaxed);
+        loader.add_source("memory", source.clone());
+
+        let unhashed = loader.load_from("unhashed", TestFormat, "memory", (), &storage);
+        loader.set_content_comparison(true);
+        let hashed = loader.load_from("hashed", TestFormat, "memory", (), &storage);
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.contains(&unhashed) && storage.contains(&hashed),
+        );
+
+        // Both files are touched without changing their contents.
+        source.modified.store(2, Ordering::Relaxed);
+        assert!(storage.hot_reload(true, None, true, Instant::now(), |job| pool.spawn(job)));
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.reloads_in_flight.load(Ordering::Relaxed) == 0,
+        );
+        assert_eq!(Some(1), storage.get_version(&unhashed));
+        assert_eq!(Some(0), storage.get_version(&hashed));
+    }
+
+    #[test]
+    fn only_assets_of_changed_files_are_reloaded() {
+        let pool = pool();
+        let loader = Loader::new(".", pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let source = CountingSource::default();
+        source.modified.store(1, Ordering::Relaxed);
+        loader.add_source("memory", source.clone());
+
+        let changed = loader.load_from("changed", TestFormat, "memory", (), &storage);
+        let unchanged = loader.load_from("unchanged", TestFormat, "memory", (), &storage);
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.contains(&changed) && storage.contains(&unchanged),
+        );
+        assert_eq!(2, source.loads.load(Ordering::Relaxed));
+
+        // The modification time didn't change, as if the file was saved twice in a second.
+        storage.changed_files = Some(vec!["changed".to_owned()].into_iter().collect());
+        assert!(storage.hot_reload(false, None, true, Instant::now(), |job| pool.spawn(job)));
+        assert!(storage.changed_files.is_none());
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.get_version(&changed) == Some(1),
+        );
+        assert_eq!(Some(0), storage.get_version(&unchanged));
+        assert_eq!(3, source.loads.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn notified_files_are_reloaded_by_next_process() {
+        let pool = pool();
+        let loader = Loader::new(".", pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let source = CountingSource::default();
+        source.modified.store(1, Ordering::Relaxed);
+        loader.add_source("memory", source.clone());
+
+        let handle = loader.load_from("asset", TestFormat, "memory", (), &storage);
+        let other = loader.load_from("other", TestFormat, "memory", (), &storage);
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.contains(&handle) && storage.contains(&other),
+        );
+
+        // No strategy is passed, so only the notification causes the reload.
+        storage.notify_file_changed("asset");
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.get_version(&handle) == Some(1),
+        );
+        assert_eq!(Some(0), storage.get_version(&other));
+        assert_eq!(3, source.loads.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn requested_reloads_ignore_modification_times() {
+        let pool = pool();
+        let mut loader = Loader::new(".", pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let source = CountingSource::default();
+        source.modified.store(1, Ordering::Relaxed);
+        loader.add_source("memory", source.clone());
+
+        let handle = loader.load_from("asset", TestFormat, "memory", (), &storage);
+        loader.set_hot_reload(false);
+        let fixed = loader.load_from("fixed", TestFormat, "memory", (), &storage);
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.contains(&handle) && storage.contains(&fixed),
+        );
+
+        assert!(storage.request_reload(&handle));
+        assert!(!storage.request_reload(&fixed));
+        assert!(loader.reload("fixed", TestFormat, "memory", &storage));
+        assert!(!loader.reload("missing", TestFormat, "memory", &storage));
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| {
+                storage.get_version(&handle) == Some(1) && storage.get_version(&fixed) == Some(1)
+            },
+        );
+        assert_eq!(4, source.loads.load(Ordering::Relaxed));
+        assert!(storage.is_reloadable(&handle));
+        assert!(!storage.is_reloadable(&fixed));
+    }
+
+    #[test]
+    fn reloads_wait_for_files_to_settle() {
+        let pool = pool();
+        let loader = Loader::new(".", pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let source = CountingSource::default();
+        source.modified.store(1, Ordering::Relaxed);
+        loader.add_source("memory", source.clone());
+
+        let handle = loader.load_from("asset", TestFormat, "memory", (), &storage);
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.contains(&handle),
+        );
+
+        let quiet = Some(Duration::from_millis(50));
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        source.modified.store(2, Ordering::Relaxed);
+        assert!(!storage.hot_reload(false, quiet, true, at(0), |job| pool.spawn(job)));
+        assert!(storage.quiet_since.contains_key(&handle.id()));
+
+        // Changing the file again restarts the quiet period. Waiting reloads are checked
+        // again without polling the other assets.
+        source.modified.store(3, Ordering::Relaxed);
+        assert!(!storage.hot_reload(false, quiet, false, at(30), |job| pool.spawn(job)));
+        assert!(!storage.hot_reload(false, quiet, false, at(60), |job| pool.spawn(job)));
+
+        assert!(storage.hot_reload(false, quiet, false, at(90), |job| pool.spawn(job)));
+        assert!(storage.quiet_since.is_empty());
+        assert!(!storage.reloads_deferred);
+    }
+
+    /// Source whose reads block until the test stops holding its gate, like a network or
+    /// a cold hard drive.
+    struct SlowSource {
+        gate: Arc<RwLock<()>>,
+    }
+
+    impl Source for SlowSource {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        fn load(&self, _: &str) -> Result<Vec<u8>, Error> {
+            let _open = self.gate.read();
+            Ok(b"slow".to_vec())
+        }
+    }
+
+    #[test]
+    fn cancelled_loads_are_not_inserted() {
+        let pool = pool();
+        let gate = Arc::new(RwLock::new(()));
+        let closed = gate.write();
+        let loader = Loader::with_default_source(SlowSource { gate: gate.clone() }, pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let mut counter = ProgressCounter::new();
+
+        let (handle, token) =
+            loader.load_cancellable("asset", TestFormat, "", &mut counter, &storage);
+        token.cancel();
+        drop(closed);
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |_| counter.num_loading() == 0,
+        );
+
+        assert_eq!(1, counter.num_cancelled());
+        assert_eq!(0, counter.num_failed());
+        assert_eq!(0, counter.num_finished());
+        assert!(!storage.contains(&handle));
+        assert_eq!(HandleStatus::Absent, storage.status(&handle));
+    }
+
+    #[test]
+    fn slow_reads_do_not_block_the_thread_pool() {
+        let pool = pool();
+        let gate = Arc::new(RwLock::new(()));
+        let closed = gate.write();
+        let loader = Loader::with_default_source(SlowSource { gate: gate.clone() }, pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let handles: Vec<_> = (0..2)
+            .map(|i| loader.load(format!("asset{}", i), TestFormat, (), &storage))
+            .collect();
+
+        // The reads can't finish yet, so they must not occupy the only thread of the pool.
+        let (sender, receiver) = std::sync::mpsc::channel();
+        for i in 0..10 {
+            let sender = sender.clone();
+            pool.spawn(move || sender.send(i).unwrap());
+        }
+        for _ in 0..10 {
+            receiver
+                .recv_timeout(Duration::from_secs(5))
+                .expect("Thread pool is blocked");
+        }
+        storage.process(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            0,
+            &pool,
+            None,
+        );
+        assert!(handles.iter().all(|handle| !storage.contains(handle)));
+
+        drop(closed);
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| handles.iter().all(|handle| storage.contains(handle)),
+        );
+        assert_eq!("slow", storage.get(&handles[1]).unwrap().0);
+    }
+
+    /// Source of a huge file consisting only of holes, which can't be loaded at once.
+    struct SparseSource {
+        len: u64,
+    }
+
+    impl Source for SparseSource {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        fn load(&self, _: &str) -> Result<Vec<u8>, Error> {
+            panic!("Tried to buffer {} bytes", self.len)
+        }
+
+        fn open(&self, _: &str) -> Result<Box<dyn std::io::Read + Send>, Error> {
+            Ok(Box::new(SparseReader {
+                remaining: self.len,
+            }))
+        }
+    }
+
+    struct SparseReader {
+        remaining: u64,
+    }
+
+    impl std::io::Read for SparseReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            // The holes read as zeros; `buf` is left untouched to keep the test fast.
+            let n = (buf.len() as u64).min(self.remaining);
+            self.remaining -= n;
+            Ok(n as usize)
+        }
+    }
+
+    /// Streaming format which only counts the bytes, using a fixed size buffer.
+    struct CountingFormat;
+
+    impl StreamingFormat<String> for CountingFormat {
+        fn name(&self) -> &'static str {
+            "COUNTING"
+        }
+
+        fn import(&self, mut reader: Box<dyn std::io::Read + Send>) -> Result<String, Error> {
+            let mut buf = vec![0; 64 * 1024];
+            let mut len = 0u64;
+            loop {
+                match reader.read(&mut buf)? {
+                    0 => return Ok(len.to_string()),
+                    n => len += n as u64,
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_loads_do_not_buffer_the_asset() {
+        let pool = pool();
+        let len = 4 * 1024 * 1024 * 1024;
+        let loader = Loader::with_default_source(SparseSource { len }, pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let handle = loader.load_streaming("huge", CountingFormat, "", (), &storage);
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.contains(&handle),
+        );
+        assert_eq!(len.to_string(), storage.get(&handle).unwrap().0);
+    }
+
+    /// Asynchronous source whose loads only complete once the test says so.
+    #[cfg(feature = "async_source")]
+    #[derive(Clone, Default)]
+    struct ManualSource {
+        pending: Arc<Mutex<Vec<(String, oneshot::Sender<Result<Vec<u8>, Error>>)>>>,
+    }
+
+    #[cfg(feature = "async_source")]
+    impl AsyncSource for ManualSource {
+        fn load(&self, path: &str) -> BoxFuture<'static, Result<Vec<u8>, Error>> {
+            let (sender, receiver) = oneshot::channel();
+            self.pending.lock().push((path.to_owned(), sender));
+            receiver
+                .map(|result| result.expect("Load was dropped"))
+                .boxed()
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "async_source")]
+    fn async_loads_are_not_limited_by_threads() {
+        let pool = pool();
+        let loader = Loader::new(".", pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let source = ManualSource::default();
+        loader.add_async_source("manual", source.clone());
+        loader.add_source("memory", CountingSource::default());
+
+        let handles: Vec<_> = (0..200)
+            .map(|i| {
+                loader.load_from_async_source(format!("{}", i), TestFormat, "manual", (), &storage)
+            })
+            .collect();
+        let blocking = loader.load_from_async_source("asset", TestFormat, "memory", (), &storage);
+
+        let pending = source.pending.lock().drain(..).collect::<Vec<_>>();
+        assert_eq!(200, pending.len());
+        for (path, sender) in pending {
+            sender.send(Ok(path.into_bytes())).unwrap();
+        }
+
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| {
+                handles
+                    .iter()
+                    .chain(Some(&blocking))
+                    .all(|h| storage.contains(h))
+            },
+        );
+        assert_eq!("199", storage.get(&handles[199]).unwrap().0);
+        assert_eq!("data", storage.get(&blocking).unwrap().0);
+    }
+
+    #[test]
+    fn loaded_assets_remember_their_provenance() {
+        use crate::MemorySource;
+
+        let pool = pool();
+        let loader = Loader::new(".", pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let mut source = MemorySource::new();
+        source.insert("asset", &b"data"[..]);
+        loader.add_source("memory", source);
+
+        let handle = loader.load_from("asset", TestFormat, "memory", (), &storage);
+        let inserted = storage.insert(TestAsset("inserted".to_owned()));
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| storage.contains(&handle),
+        );
+
+        let provenance = storage.provenance(&handle).expect("Provenance is missing");
+        assert_eq!("memory", provenance.source);
+        assert_eq!("asset", provenance.path);
+        assert_eq!(Some(4), provenance.len);
+        assert!(storage.provenance(&inserted).is_none());
+    }
+
+    #[test]
+    fn stages_run_in_order_and_resume_after_loading() {
+        let pool = pool();
+        let loader = Loader::new(".", pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let parsed = Arc::new(AtomicUsize::new(0));
+        let mut stages: Vec<ProcessingStage<TestAsset>> = vec![
+            Box::new({
+                let parsed = parsed.clone();
+                move |data: String| {
+                    parsed.fetch_add(1, Ordering::Relaxed);
+                    Ok(StageState::Next(data + " parsed"))
+                }
+            }),
+            Box::new(|data: String| {
+                if data.ends_with("uploading") {
+                    Ok(StageState::Loaded(TestAsset(data)))
+                } else {
+                    Ok(StageState::Loading(data + " uploading"))
+                }
+            }),
+        ];
+
+        let handle = loader.load_from_data("data".to_owned(), (), &storage);
+        storage.process_stages(&mut stages, 0, &pool, None);
+        assert!(!storage.contains(&handle));
+        storage.process_stages(&mut stages, 1, &pool, None);
+
+        assert_eq!("data parsed uploading", storage.get(&handle).unwrap().0);
+        assert_eq!(1, parsed.load(Ordering::Relaxed));
+        assert!(storage.pending_stages.is_empty());
+    }
+
+    #[test]
+    fn multiple_assets_are_inserted_together() {
+        let pool = pool();
+        let loader = Loader::new(".", pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let mut counter = ProgressCounter::new();
+
+        let sprites: Vec<_> = (0..2).map(|_| storage.reserve_handle()).collect();
+        assert_eq!(HandleStatus::Pending, storage.status(&sprites[0]));
+        let sheet = loader.load_from_data("sheet".to_owned(), &mut counter, &storage);
+        let process = |data: String| {
+            let mut assets = vec![(sheet.clone(), TestAsset(data.clone()))];
+            for (i, sprite) in sprites.iter().enumerate() {
+                assets.push((sprite.clone(), TestAsset(format!("{} {}", data, i))));
+            }
+            Ok(ProcessingState::Multiple(assets))
+        };
+        storage.process(process, 0, &pool, None);
+
+        assert_eq!("sheet", storage.get(&sheet).unwrap().0);
+        assert_eq!("sheet 1", storage.get(&sprites[1]).unwrap().0);
+        assert_eq!(1, counter.num_finished());
+        assert_eq!(0, counter.num_failed());
+
+        let orphan = loader.load_from_data("orphan".to_owned(), &mut counter, &storage);
+        let sprite = sprites[0].clone();
+        storage.process(
+            move |data| {
+                Ok(ProcessingState::Multiple(vec![(
+                    sprite.clone(),
+                    TestAsset(data),
+                )]))
+            },
+            1,
+            &pool,
+            None,
+        );
+        assert_eq!(HandleStatus::Absent, storage.status(&orphan));
+        assert_eq!(1, counter.num_failed());
+        assert_eq!("sheet 0", storage.get(&sprites[0]).unwrap().0);
+    }
+
+    #[test]
+    fn ordered_processing_follows_issue_order() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        storage.set_ordered_processing(true);
+
+        let first = storage.processed.next_sequence();
+        let second = storage.processed.next_sequence();
+        let handles: Vec<_> = (0..2).map(|_| storage.allocate()).collect();
+        for &(sequence, ref name) in &[(second, "second"), (first, "first")] {
+            new_asset(&handles[sequence as usize], name)
+                .sequence(sequence)
+                .push(&storage);
+        }
+
+        let mut order = Vec::new();
+        storage.process(
+            |data| {
+                order.push(data.clone());
+                Ok(ProcessingState::Loaded(TestAsset(data)))
+            },
+            0,
+            &pool,
+            None,
+        );
+        assert_eq!(vec!["first", "second"], order);
+    }
+
+    #[test]
+    fn immediate_loads_are_processed_while_paused() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        storage.set_paused(true);
+
+        let handles: Vec<_> = (0..2).map(|_| storage.allocate()).collect();
+        for (handle, &priority) in handles
+            .iter()
+            .zip(&[LoadPriority::High, LoadPriority::Immediate])
+        {
+            new_asset(&handle, &format!("{:?}", priority))
+                .priority(priority)
+                .push(&storage);
+        }
+
+        storage.process(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            0,
+            &pool,
+            None,
+        );
+        assert!(!storage.contains(&handles[0]));
+        assert_eq!("Immediate", storage.get(&handles[1]).unwrap().0);
+    }
+
+    #[test]
+    fn paused_data_is_processed_once_resumed() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        storage.set_paused(true);
+
+        let handles: Vec<_> = (0..3).map(|_| storage.allocate()).collect();
+        for (handle, name) in handles.iter().zip(&["a", "b", "c"]) {
+            new_asset(&handle, name).push(&storage);
+        }
+
+        let mut processed = Vec::new();
+        for frame_number in 0..2 {
+            storage.process(
+                |data: String| {
+                    processed.push(data.clone());
+                    Ok(ProcessingState::Loaded(TestAsset(data)))
+                },
+                frame_number,
+                &pool,
+                None,
+            );
+        }
+        assert!(processed.is_empty());
+        for handle in &handles {
+            assert_eq!(HandleStatus::Pending, storage.status(handle));
+        }
+
+        storage.set_paused(false);
+        assert!(!storage.is_paused());
+        storage.process(
+            |data: String| {
+                processed.push(data.clone());
+                Ok(ProcessingState::Loaded(TestAsset(data)))
+            },
+            2,
+            &pool,
+            None,
+        );
+        assert_eq!(vec!["a", "b", "c"], processed);
+        for (handle, name) in handles.iter().zip(&["a", "b", "c"]) {
+            assert_eq!(*name, storage.get(handle).unwrap().0);
+        }
+    }
+
+    #[test]
+    fn shadow_assets_are_swapped_in() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let target = storage.insert(TestAsset("old".to_owned()));
+
+        let shadow = storage.allocate();
+        new_asset(&shadow, "shadow").data("new").push(&storage);
+        // The shadow asset takes two frames to load, the target stays untouched meanwhile.
+        for (frame_number, loaded) in vec![(0, false), (1, true)] {
+            storage.process(
+                |data| {
+                    Ok(if loaded {
+                        ProcessingState::Loaded(TestAsset(data))
+                    } else {
+                        ProcessingState::Loading(data)
+                    })
+                },
+                frame_number,
+                &pool,
+                None,
+            );
+            assert_eq!("old", storage.get(&target).unwrap().0);
+            assert_eq!(loaded, storage.get(&shadow).is_some());
+        }
+        assert!(storage.swap_in(&target, &target).is_none());
+
+        let old = storage.swap_in(&target, &shadow).expect("Shadow is loaded");
+        assert_eq!("old", old.0);
+        assert_eq!("new", storage.get(&target).unwrap().0);
+        assert_eq!(Some(1), storage.get_version(&target));
+        assert_eq!(HandleStatus::Absent, storage.status(&shadow));
+
+        // The id of the shadow isn't reused while the handle is alive.
+        storage.process(|_| unreachable!(), 2, &pool, None);
+        let other = storage.insert(TestAsset("other".to_owned()));
+        assert_ne!(shadow.id(), other.id());
+        assert!(storage.get(&shadow).is_none());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "has been created by another storage")]
+    fn handles_of_other_storages_are_rejected() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let mut other = AssetStorage::<TestAsset>::new();
+        let handle = storage.insert(TestAsset("a".to_owned()));
+        other.insert(TestAsset("b".to_owned()));
+
+        assert!(storage.contains(&handle.downgrade().upgrade().unwrap()));
+        other.get(&handle);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn versioned_accessors_reject_handles_of_other_storages() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let mut other = AssetStorage::<TestAsset>::new();
+        let handle = storage.insert(TestAsset("a".to_owned()));
+        other.insert(TestAsset("b".to_owned()));
+
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| {
+            other.get_version(&handle).is_some()
+        }))
+        .is_err());
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| {
+            other.get_with_version(&handle).is_some()
+        }))
+        .is_err());
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| {
+            other.get_mut_with_version(&handle).is_some()
+        }))
+        .is_err());
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| {
+            other.get_mut_bump(&handle).is_some()
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn try_get_tells_why_assets_are_missing() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let loaded = storage.insert(TestAsset("a".to_owned()));
+        let pending = storage.allocate();
+        let removed = storage.insert(TestAsset("b".to_owned()));
+        storage.unload(&[removed.clone()]);
+
+        assert_eq!("a", storage.try_get(&loaded).unwrap().0);
+        assert_eq!(Some(GetError::Pending), storage.try_get(&pending).err());
+        assert_eq!(Some(GetError::Unloaded), storage.try_get(&removed).err());
+
+        #[cfg(debug_assertions)]
+        {
+            let other = AssetStorage::<TestAsset>::new();
+            assert_eq!(Some(GetError::WrongStorage), other.try_get(&loaded).err());
+        }
+    }
+
+    #[test]
+    fn transferred_assets_move_to_the_other_storage() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let mut dest = AssetStorage::<TestAsset>::new();
+        let handle = storage.insert(TestAsset("a".to_owned()));
+
+        let moved = storage
+            .transfer_to(&handle, &mut dest)
+            .expect("Asset is loaded");
+        assert_eq!("a", dest.get(&moved).unwrap().0);
+        assert!(storage.get(&handle).is_none());
+        assert!(storage.transfer_to(&handle, &mut dest).is_none());
+
+        // The id isn't reused while the old handle is alive.
+        let other = storage.insert(TestAsset("b".to_owned()));
+        storage.process(|_| unreachable!(), 0, &pool, None);
+        assert_ne!(handle.id(), other.id());
+        assert!(storage.get(&handle).is_none());
+    }
+
+    #[test]
+    fn ids_of_unloaded_assets_are_reused_once_all_handles_are_dropped() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let removed = storage.insert(TestAsset("a".to_owned()));
+        let kept = removed.clone();
+        storage.unload(&[removed]);
+
+        let loaded = storage.insert(TestAsset("b".to_owned()));
+        assert_ne!(kept.id(), loaded.id());
+        assert!(storage.get(&kept).is_none());
+        storage.process(|_| unreachable!(), 0, &pool, None);
+        assert!(storage.get(&kept).is_none());
+
+        let id = kept.id();
+        drop(kept);
+        storage.process(|_| unreachable!(), 1, &pool, None);
+        assert_eq!(id, storage.insert(TestAsset("c".to_owned())).id());
+        assert_eq!("b", storage.get(&loaded).unwrap().0);
+    }
+
+    #[test]
+    fn reloads_of_unloaded_assets_are_dropped() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let stale = storage.insert(TestAsset("old".to_owned()));
+        storage.processed.push(Processed::HotReload {
+            data: Ok(FormatValue::data("reloaded".to_owned())),
+            handle: stale.clone(),
+            name: "asset".to_owned(),
+            old_reload: None,
+            sequence: storage.processed.next_sequence(),
+        });
+        storage.unload(&[stale.clone()]);
+        let reused = storage.insert(TestAsset("new".to_owned()));
+        assert_ne!(stale.id(), reused.id());
+
+        storage.process(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            0,
+            &pool,
+            None,
+        );
+        assert_eq!("new", storage.get(&reused).unwrap().0);
+        assert_eq!(Some(0), storage.get_version(&reused));
+    }
+
+    #[test]
+    fn identical_reloads_can_be_skipped() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let handle = storage.insert(TestAsset("same".to_owned()));
+        let reload = |storage: &mut AssetStorage<TestAsset>, data: &str| {
+            storage.processed.push(Processed::HotReload {
+                data: Ok(FormatValue::data(data.to_owned())),
+                handle: handle.clone(),
+                name: "asset".to_owned(),
+                old_reload: None,
+                sequence: storage.processed.next_sequence(),
+            });
+            storage.process(
+                |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+                0,
+                &pool,
+                None,
+            );
+            storage.get_version(&handle)
+        };
+
+        assert_eq!(Some(1), reload(&mut storage, "same"));
+        storage.set_skip_identical_reloads(true);
+        assert_eq!(Some(1), reload(&mut storage, "same"));
+        assert_eq!(Some(2), reload(&mut storage, "changed"));
+        assert_eq!("changed", storage.get(&handle).unwrap().0);
+        storage.set_skip_identical_reloads(false);
+        assert_eq!(Some(3), reload(&mut storage, "changed"));
+    }
+
+    /// Reload object which always wants to reload.
+    #[derive(Clone)]
+    struct AlwaysReload;
+
+    impl Reload<String> for AlwaysReload {
+        fn needs_reload(&self) -> bool {
+            true
+        }
+
+        fn name(&self) -> String {
+            "always".to_owned()
+        }
+
+        fn format(&self) -> &'static str {
+            "TEST"
+        }
+
+        fn reload(self: Box<Self>) -> Result<FormatValue<String>, Error> {
+            Ok(FormatValue::data("reloaded".to_owned()))
+        }
+    }
+
+    #[test]
+    fn concurrent_reloads_are_limited() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        storage.set_max_concurrent_reloads(Some(1));
+        let handles: Vec<_> = (0..3)
+            .map(|_| storage.insert(TestAsset("old".to_owned())))
+            .collect();
+        for handle in &handles {
+            let reload: Box<dyn Reload<String>> = Box::new(AlwaysReload);
+            storage.reloads.push((handle.downgrade(), reload));
+        }
+
+        let mut jobs = Vec::new();
+        storage.hot_reload(false, None, true, Instant::now(), |job| jobs.push(job));
+        assert_eq!(1, jobs.len());
+        assert!(storage.reloads_deferred);
+
+        storage.hot_reload(false, None, true, Instant::now(), |job| jobs.push(job));
+        assert_eq!(1, jobs.len());
+
+        jobs.pop().unwrap()();
+        let pool = pool();
+        storage.process(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            0,
+            &pool,
+            None,
+        );
+        // The finished reload freed its slot for one of the deferred ones.
+        assert_eq!(1, storage.reloads_in_flight.load(Ordering::Relaxed));
+    }
+
+    /// Reload object counting how often it has been polled.
+    #[derive(Clone)]
+    struct PolledReload(Arc<AtomicUsize>);
+
+    impl Reload<String> for PolledReload {
+        fn needs_reload(&self) -> bool {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            false
+        }
+
+        fn name(&self) -> String {
+            "polled".to_owned()
+        }
+
+        fn format(&self) -> &'static str {
+            "TEST"
+        }
+
+        fn reload(self: Box<Self>) -> Result<FormatValue<String>, Error> {
+            Ok(FormatValue::data("reloaded".to_owned()))
+        }
+    }
+
+    #[test]
+    fn requested_reloads_do_not_poll_other_assets() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let polls = Arc::new(AtomicUsize::new(0));
+        let requested = storage.insert(TestAsset("old".to_owned()));
+        let other = storage.insert(TestAsset("old".to_owned()));
+        let reload: Box<dyn Reload<String>> = Box::new(AlwaysReload);
+        storage.reloads.push((requested.downgrade(), reload));
+        let reload: Box<dyn Reload<String>> = Box::new(PolledReload(polls.clone()));
+        storage.reloads.push((other.downgrade(), reload));
+
+        assert!(storage.request_reload(&requested));
+        storage.process(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            0,
+            &pool,
+            Some(&HotReloadStrategy::never()),
+        );
+        assert_eq!(1, storage.reloads_in_flight.load(Ordering::Relaxed));
+        assert_eq!(0, polls.load(Ordering::Relaxed));
+        assert!(!storage.reloads_deferred);
+    }
+
+    #[test]
+    fn waiting_reloads_do_not_poll_other_assets() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let polls = Arc::new(AtomicUsize::new(0));
+        let waiting = storage.insert(TestAsset("old".to_owned()));
+        let other = storage.insert(TestAsset("old".to_owned()));
+        let reload: Box<dyn Reload<String>> = Box::new(AlwaysReload);
+        storage.reloads.push((waiting.downgrade(), reload));
+        let reload: Box<dyn Reload<String>> = Box::new(PolledReload(polls.clone()));
+        storage.reloads.push((other.downgrade(), reload));
+        storage.quiet_since.insert(
+            waiting.id(),
+            QuietPeriod {
+                modified: None,
+                noticed: Instant::now(),
+            },
+        );
+
+        let strategy = HotReloadStrategy::never().with_quiet_period(Duration::from_secs(60));
+        for frame_number in 0..3 {
+            storage.process(
+                |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+                frame_number,
+                &pool,
+                Some(&strategy),
+            );
+        }
+        assert!(storage.quiet_since.contains_key(&waiting.id()));
+        assert_eq!(0, storage.reloads_in_flight.load(Ordering::Relaxed));
+        assert_eq!(0, polls.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn reload_info_describes_attached_reloads() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let reloadable = storage.insert(TestAsset("old".to_owned()));
+        let other = storage.insert(TestAsset("old".to_owned()));
+        let reload: Box<dyn Reload<String>> = Box::new(AlwaysReload);
+        storage.reloads.push((reloadable.downgrade(), reload));
+
+        assert_eq!(
+            Some(ReloadInfo {
+                name: "always".to_owned(),
+                format: "TEST".to_owned(),
+            }),
+            storage.reload_info(&reloadable)
+        );
+        assert_eq!(None, storage.reload_info(&other));
+    }
+
+    /// Reload object remembering the name of the thread it ran on.
+    #[derive(Clone)]
+    struct ThreadReload(Arc<Mutex<Option<String>>>);
+
+    impl Reload<String> for ThreadReload {
+        fn needs_reload(&self) -> bool {
+            true
+        }
+
+        fn name(&self) -> String {
+            "thread".to_owned()
+        }
+
+        fn format(&self) -> &'static str {
+            "TEST"
+        }
+
+        fn reload(self: Box<Self>) -> Result<FormatValue<String>, Error> {
+            *self.0.lock() = std::thread::current().name().map(str::to_owned);
+            Ok(FormatValue::data("reloaded".to_owned()))
+        }
+    }
+
+    #[test]
+    fn reloads_run_on_the_reload_pool() {
+        let pool = pool();
+        let reload_pool = ThreadPoolBuilder::new()
+            .num_threads(1)
+            .thread_name(|_| "reload".to_owned())
+            .build()
+            .expect("Invalid config");
+        let mut storage = AssetStorage::<TestAsset>::new();
+        storage.set_reload_pool(Some(Arc::new(reload_pool)));
+
+        let thread = Arc::new(Mutex::new(None));
+        let handle = storage.insert(TestAsset("old".to_owned()));
+        let reload: Box<dyn Reload<String>> = Box::new(ThreadReload(thread.clone()));
+        storage.reloads.push((handle.downgrade(), reload));
+
+        assert!(storage.request_reload(&handle));
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            &pool,
+            |storage| {
+                storage
+                    .get(&handle)
+                    .map_or(false, |asset| asset.0 == "reloaded")
+            },
+        );
+        assert_eq!(Some("reload"), thread.lock().as_ref().map(String::as_str));
+    }
+
+    #[test]
+    fn started_reloads_are_reported_once() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let started = Arc::new(Mutex::new(Vec::new()));
+        storage.on_reload_started({
+            let started = started.clone();
+            move |id| started.lock().push(id)
+        });
+
+        let first = storage.insert(TestAsset("old".to_owned()));
+        let second = storage.insert(TestAsset("old".to_owned()));
+        let other = storage.insert(TestAsset("old".to_owned()));
+        for handle in &[&first, &second, &other] {
+            let reload: Box<dyn Reload<String>> = Box::new(AlwaysReload);
+            storage.reloads.push((handle.downgrade(), reload));
+        }
+
+        assert!(storage.request_reload(&first));
+        assert!(storage.request_reload(&second));
+        for frame_number in 0..3 {
+            storage.process(
+                |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+                frame_number,
+                &pool,
+                Some(&HotReloadStrategy::never()),
+            );
+        }
+        let mut started = started.lock().clone();
+        started.sort();
+        assert_eq!(vec![first.id(), second.id()], started);
+    }
+
+    #[test]
+    fn finished_reloads_are_reported() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let finished = Arc::new(Mutex::new(Vec::new()));
+        storage.on_reload_finished({
+            let finished = finished.clone();
+            move |id, success| finished.lock().push((id, success))
+        });
+
+        let ok = storage.insert(TestAsset("old".to_owned()));
+        let failed = storage.insert(TestAsset("old".to_owned()));
+        storage.processed.push(Processed::HotReload {
+            data: Ok(FormatValue::data("reloaded".to_owned())),
+            handle: ok.clone(),
+            name: "ok".to_owned(),
+            old_reload: None,
+            sequence: storage.processed.next_sequence(),
+        });
+        storage.processed.push(Processed::HotReload {
+            data: Err(format_err!("Invalid asset")),
+            handle: failed.clone(),
+            name: "failed".to_owned(),
+            old_reload: None,
+            sequence: storage.processed.next_sequence(),
+        });
+
+        storage.process(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            0,
+            &pool,
+            None,
+        );
+        let mut finished = finished.lock().clone();
+        finished.sort();
+        assert_eq!(vec![(ok.id(), true), (failed.id(), false)], finished);
+    }
+
+    #[test]
+    fn finished_reloads_are_published() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        assert!(storage.reload_events().is_none());
+        let mut reader = storage.reload_events_mut().register_reader();
+
+        let ok = storage.insert(TestAsset("old".to_owned()));
+        let failed = storage.insert(TestAsset("old".to_owned()));
+        storage.processed.push(Processed::HotReload {
+            data: Ok(FormatValue::data("reloaded".to_owned())),
+            handle: ok.clone(),
+            name: "ok".to_owned(),
+            old_reload: None,
+            sequence: storage.processed.next_sequence(),
+        });
+        storage.processed.push(Processed::HotReload {
+            data: Err(format_err!("Invalid asset")),
+            handle: failed.clone(),
+            name: "failed".to_owned(),
+            old_reload: None,
+            sequence: storage.processed.next_sequence(),
+        });
+
+        storage.process(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            0,
+            &pool,
+            None,
+        );
+        let mut events: Vec<_> = storage
+            .reload_events()
+            .unwrap()
+            .read(&mut reader)
+            .map(|e| {
+                (
+                    e.handle.upgrade(),
+                    e.name.clone(),
+                    e.new_version,
+                    e.outcome.clone(),
+                )
+            })
+            .collect();
+        events.sort_by(|a, b| a.1.cmp(&b.1));
+        assert_eq!(2, events.len());
+        let (ref handle, ref name, version, ref outcome) = events[0];
+        assert_eq!(
+            (Some(&failed), "failed", 0),
+            (handle.as_ref(), name.as_str(), version)
+        );
+        match *outcome {
+            ReloadOutcome::Failed(ref e) => assert!(e.contains("failed"), "{}", e),
+            ref other => panic!("Expected failure, got {:?}", other),
+        }
+        assert_eq!(
+            (Some(ok), "ok".to_owned(), 1, ReloadOutcome::Reloaded),
+            events[1]
+        );
+    }
+
+    #[test]
+    fn reload_failures_are_counted_until_a_reload_succeeds() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let handle = storage.insert(TestAsset("old".to_owned()));
+        let reload = |storage: &AssetStorage<TestAsset>, data: Result<String, Error>| {
+            storage.processed.push(Processed::HotReload {
+                data: data.map(FormatValue::data),
+                handle: handle.clone(),
+                name: "asset".to_owned(),
+                old_reload: None,
+                sequence: storage.processed.next_sequence(),
+            });
+        };
+        let process = |storage: &mut AssetStorage<TestAsset>| {
+            storage.process(
+                |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+                0,
+                &pool,
+                None,
+            );
+        };
+
+        reload(&storage, Err(format_err!("Invalid asset")));
+        process(&mut storage);
+        let first = storage.reload_failures();
+        assert_eq!(1, first.len());
+        assert_eq!(("asset", 1), (first[0].name.as_str(), first[0].attempts));
+
+        reload(&storage, Err(format_err!("Still invalid")));
+        process(&mut storage);
+        let second = storage.reload_failures();
+        assert_eq!(2, second[0].attempts);
+        assert_eq!(first[0].first_failed_at, second[0].first_failed_at);
+        assert!(
+            second[0].error.contains("Still invalid"),
+            "{}",
+            second[0].error
+        );
+
+        reload(&storage, Ok("fixed".to_owned()));
+        process(&mut storage);
+        assert!(storage.reload_failures().is_empty());
+        assert_eq!(Some(1), storage.get_version(&handle));
+    }
+
+    #[test]
+    fn transient_loads_succeed() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        storage.set_transient_loads(true);
+        let mut counter = ProgressCounter::new();
+        let tracker = {
+            let mut progress = &mut counter;
+            progress.add_assets(1);
+            Box::new(progress.create_tracker()) as Box<dyn Tracker>
+        };
+
+        let handle = storage.allocate();
+        let id = handle.id();
+        new_asset(&handle, "asset")
+            .data("transient")
+            .tracker(tracker)
+            .push(&storage);
+        // Only the queued data holds on to the handle.
+        drop(handle);
+        let load = |data| Ok(ProcessingState::Loaded(TestAsset(data)));
+        storage.process(load, 0, &pool, None);
+        assert_eq!(0, counter.num_failed());
+        assert_eq!(1, counter.num_finished());
+
+        storage.process(load, 1, &pool, None);
+        assert!(!storage.contains_id(id));
+    }
+
+    #[test]
+    fn stuck_loads_time_out() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let mut counter = ProgressCounter::new();
+        let tracker = {
+            let mut progress = &mut counter;
+            progress.add_assets(1);
+            Box::new(progress.create_tracker()) as Box<dyn Tracker>
+        };
+
+        let handle = storage.allocate();
+        let requested = Instant::now();
+        let tracker = storage.watch_deadline(
+            &handle,
+            "asset".to_owned(),
+            requested,
+            Duration::from_millis(10),
+            None,
+            tracker,
+        );
+        storage.expire_loads(requested + Duration::from_millis(5));
+        assert_eq!(0, counter.num_failed());
+        storage.expire_loads(requested + Duration::from_millis(20));
+        assert_eq!(1, counter.num_failed());
+        let errors = counter.errors();
+        let cause = errors[0].error.source().expect("Timeout is missing");
+        assert!(cause.to_string().contains("reading from the source"));
+        assert_eq!(HandleStatus::Absent, storage.status(&handle));
+
+        // Data arriving after the timeout is dropped.
+        new_asset(&handle, "asset")
+            .data("late")
+            .tracker(tracker)
+            .push(&storage);
+        storage.process(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            0,
+            &pool,
+            None,
+        );
+        assert!(storage.get(&handle).is_none());
+        assert_eq!(1, counter.num_failed());
+    }
+
+    #[test]
+    fn processing_errors_are_categorized() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        storage.on_processing_error({
+            let errors = errors.clone();
+            move |e| {
+                let format = match *e {
+                    ProcessingError::Format { .. } => true,
+                    ProcessingError::Processing { .. } => false,
+                    _ => panic!("Unexpected error: {}", e),
+                };
+                errors.lock().push((e.id(), e.name().to_owned(), format));
+            }
+        });
+        let mut counter = ProgressCounter::new();
+
+        let mut handles = Vec::new();
+        for (name, data) in vec![
+            ("unreadable", Err(format_err!("Invalid bytes"))),
+            ("invalid", Ok("invalid".to_owned())),
+        ] {
+            let handle = storage.allocate();
+            let mut progress = &mut counter;
+            progress.add_assets(1);
+            NewAsset {
+                data,
+                ..new_asset(&handle, name)
+            }
+            .tracker(Box::new(progress.create_tracker()))
+            .push(&storage);
+            handles.push(handle);
+        }
+        storage.process(
+            |data| Err(format_err!("Cannot process {:?}", data)),
+            0,
+            &pool,
+            None,
+        );
+
+        assert_eq!(
+            vec![
+                (handles[0].id(), "unreadable".to_owned(), true),
+                (handles[1].id(), "invalid".to_owned(), false),
+            ],
+            *errors.lock()
+        );
+        // Trackers still get the errors they used to.
+        assert_eq!(2, counter.num_failed());
+        assert!(counter.errors()[0]
+            .error
+            .to_string()
+            .contains("Failed to load asset with name \"unreadable\""));
+    }
+
+    #[test]
+    fn delayed_assets_are_held_back() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let handle = storage.allocate();
+        new_asset(&handle, "asset").data("data").push(&storage);
+
+        let mut calls = 0;
+        for frame_number in 0..3 {
+            storage.process(
+                |data| {
+                    calls += 1;
+                    if calls == 1 {
+                        Ok(ProcessingState::Delayed(data, 2))
+                    } else {
+                        Ok(ProcessingState::Loaded(TestAsset(data)))
+                    }
+                },
+                frame_number,
+                &pool,
+                None,
+            );
+            assert_eq!(frame_number == 2, storage.contains(&handle));
+        }
+        assert_eq!(2, calls);
+    }
+
+    #[test]
+    fn changed_assets_are_iterated() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let _unchanged = storage.insert(TestAsset("a".to_owned()));
+        let changed = storage.insert(TestAsset("b".to_owned()));
+        storage.replace(&changed, TestAsset("c".to_owned()));
+
+        let found: Vec<_> = storage
+            .changed_since_iter(0)
+            .map(|(handle, asset, version)| (handle.id(), asset.0.clone(), version))
+            .collect();
+        assert_eq!(vec![(changed.id(), "c".to_owned(), 1)], found);
+        assert_eq!(0, storage.changed_since_iter(1).count());
+    }
+
+    #[test]
+    fn recycling_ids_can_be_delayed() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        storage.set_recycle_policy(RecyclePolicy::DelayFrames(2));
+
+        let removed = storage.insert(TestAsset("a".to_owned()));
+        let removed_id = removed.id();
+        storage.unload(&[removed]);
+        for frame_number in 0..2 {
+            storage.process(
+                |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+                frame_number,
+                &pool,
+                None,
+            );
+            let handle = storage.insert(TestAsset("b".to_owned()));
+            assert_ne!(removed_id, handle.id());
+        }
+
+        storage.process(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            2,
+            &pool,
+            None,
+        );
+        let handle = storage.insert(TestAsset("c".to_owned()));
+        assert_eq!(removed_id, handle.id());
+    }
+
+    #[test]
+    fn debug_dump_describes_the_storage() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let handle = storage.insert(TestAsset("a".to_owned()));
+        storage.replace(&handle, TestAsset("b".to_owned()));
+        let unloaded = storage.insert(TestAsset("c".to_owned()));
+        storage.unload(&[unloaded.clone()]);
+        drop(storage.insert(TestAsset("d".to_owned())));
+        let _pending = storage.allocate();
+
+        let dump = storage.debug_dump();
+        assert!(dump.contains("live assets: 2"), "{}", dump);
+        assert!(dump.contains("tracked handles: 3 (dead: 1)"), "{}", dump);
+        assert!(dump.contains("pending: 1"), "{}", dump);
+        assert!(dump.contains("highest allocated id: 3"), "{}", dump);
+        assert!(
+            dump.contains("handles: [0 (v1), 1 (missing), 2 (v0)]"),
+            "{}",
+            dump
+        );
+    }
+
+    #[test]
+    fn idle_gc_can_be_skipped() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        storage.set_idle_gc(false);
+        let load = |data| Ok(ProcessingState::Loaded(TestAsset(data)));
+
+        let dropped = storage.insert(TestAsset("a".to_owned())).id();
+        assert!(!storage.process(load, 0, &pool, None));
+        assert!(storage.contains_id(dropped));
+
+        storage.insert_deferred(TestAsset("b".to_owned()));
+        assert!(storage.process(load, 1, &pool, None));
+        assert!(!storage.contains_id(dropped));
+    }
+
+    #[test]
+    fn processor_with_drop_drops_collected_assets() {
+        let mut world = World::new();
+        world.add_resource(pool());
+        world.add_resource(Time::default());
+        let dropped = Arc::new(Mutex::new(Vec::new()));
+        let mut system = ProcessorWithDrop::new({
+            let dropped = dropped.clone();
+            move |asset: TestAsset| dropped.lock().push(asset.0)
+        })
+        .with_idle_gc(false);
+        RunNow::setup(&mut system, &mut world.res);
+
+        let (collected, kept) = {
+            let mut storage = world.write_resource::<AssetStorage<TestAsset>>();
+            (
+                storage.insert(TestAsset("collected".to_owned())),
+                storage.insert(TestAsset("kept".to_owned())),
+            )
+        };
+        drop(collected);
+        system.run_now(&world.res);
+        assert!(dropped.lock().is_empty());
+
+        // Without idle collection, the asset is only dropped along with the next load.
+        world
+            .write_resource::<AssetStorage<TestAsset>>()
+            .insert_deferred(TestAsset("loaded".to_owned()));
+        system.run_now(&world.res);
+        assert_eq!(vec!["collected".to_owned()], *dropped.lock());
+        assert!(world
+            .read_resource::<AssetStorage<TestAsset>>()
+            .contains(&kept));
+    }
+
+    #[test]
+    fn unload_all_with_drops_every_asset() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let a = storage.insert(TestAsset("a".to_owned()));
+        let b = storage.insert(TestAsset("b".to_owned()));
+
+        let mut dropped = Vec::new();
+        storage.unload_all_with(|asset| dropped.push(asset.0));
+        dropped.sort();
+        assert_eq!(vec!["a".to_owned(), "b".to_owned()], dropped);
+        assert!(storage.get(&a).is_none());
+        assert!(storage.get(&b).is_none());
+    }
+
+    #[test]
+    fn handles_of_unloaded_assets_are_collected_without_dropping_again() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let handle = storage.insert(TestAsset("a".to_owned()));
+        let id = handle.id();
+
+        let mut drops = 0;
+        storage.unload_all_with(|_| drops += 1);
+        drop(handle);
+        storage.process_custom_drop(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            |_| drops += 1,
+            0,
+            &pool,
+            None,
+        );
+        assert_eq!(1, drops);
+        // The id has been freed for new assets.
+        assert_eq!(id, storage.insert(TestAsset("b".to_owned())).id());
+    }
+
+    #[test]
+    fn pinned_assets_are_kept() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let load = |data| Ok(ProcessingState::Loaded(TestAsset(data)));
+
+        let handle = storage.insert(TestAsset("a".to_owned()));
+        let id = handle.id();
+        let weak = handle.downgrade();
+        assert!(storage.pin(&weak));
+        assert!(storage.is_pinned(&handle));
+        drop(handle);
+        storage.process(load, 0, &pool, None);
+        assert!(storage.contains_id(id));
+
+        assert!(storage.unpin(&weak));
+        storage.process(load, 1, &pool, None);
+        assert!(!storage.contains_id(id));
+        assert!(!storage.pin(&weak));
+    }
+
+    #[test]
+    fn process_reports_whether_it_did_work() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let load = |data| Ok(ProcessingState::Loaded(TestAsset(data)));
+        assert!(!storage.process(load, 0, &pool, None));
+
+        let handle = storage.insert_deferred(TestAsset("a".to_owned()));
+        assert!(storage.process(load, 1, &pool, None));
+        assert!(!storage.process(load, 2, &pool, None));
+
+        drop(handle);
+        assert!(storage.process(load, 3, &pool, None));
+        assert!(!storage.process(load, 4, &pool, None));
+    }
+
+    /// Processor upper-casing the data, counting the assets it processed.
+    struct CountingProcessor(usize);
+
+    impl AssetProcessor<TestAsset> for CountingProcessor {
+        fn process(&mut self, data: String) -> Result<ProcessingState<TestAsset>, Error> {
+            self.0 += 1;
+            Ok(ProcessingState::Loaded(TestAsset(data.to_uppercase())))
+        }
+    }
+
+    #[test]
+    fn processors_convert_the_data() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let first = storage.allocate();
+        let second = storage.allocate();
+        new_asset(&first, "first").push(&storage);
+        new_asset(&second, "second").push(&storage);
+
+        let mut processor = CountingProcessor(0);
+        assert!(storage.process_with(
+            &mut processor as &mut dyn AssetProcessor<TestAsset>,
+            0,
+            &pool,
+            None,
+        ));
+        assert_eq!(2, processor.0);
+        assert_eq!("FIRST", storage.get(&first).unwrap().0);
+        assert_eq!("SECOND", storage.get(&second).unwrap().0);
+        assert!(!storage.process_with(&mut processor, 1, &pool, None));
+    }
+
+    #[test]
+    fn bumping_mutable_access_changes_the_version() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let handle = storage.insert(TestAsset("a".to_owned()));
+
+        let (asset, version) = storage.get_mut_with_version(&handle).unwrap();
+        asset.0.push('b');
+        assert_eq!(0, version);
+        assert_eq!(Some(0), storage.get_version(&handle));
+
+        storage.get_mut_bump(&handle).unwrap().0.push('c');
+        let &(ref asset, version) = storage.get_with_version(&handle).unwrap();
+        assert_eq!(("abc", 1), (asset.0.as_str(), version));
+    }
+
+    #[test]
+    fn assets_are_found_by_normalized_name() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        storage.set_name_normalizer(|name| format!("vfs://{}", name.trim_start_matches("mods/")));
+
+        let handle = storage.allocate();
+        new_asset(&handle, "mods/texture.png")
+            .data("data")
+            .push(&storage);
+        storage.process(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            0,
+            &pool,
+            None,
+        );
+
+        let found = storage.get_handle_by_name("vfs://texture.png").unwrap();
+        assert_eq!(handle, found);
+        assert!(storage.get_handle_by_name("mods/texture.png").is_none());
+
+        drop((handle, found));
+        storage.process(|_| unreachable!(), 1, &pool, None);
+        assert!(storage.get_handle_by_name("vfs://texture.png").is_none());
+        assert!(storage.names.is_empty());
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct CloneAsset(String);
+
+    impl Asset for CloneAsset {
+        const NAME: &'static str = "test::CloneAsset";
+        type Data = String;
+        type HandleStorage = VecStorage<Handle<CloneAsset>>;
+    }
+
+    #[test]
+    fn cloned_assets_do_not_borrow_the_storage() {
+        let mut storage = AssetStorage::<CloneAsset>::new();
+        let handle = storage.insert(CloneAsset("old".to_owned()));
+        let pending = storage.allocate();
+
+        let cloned = storage.get_cloned(&handle);
+        storage.get_mut(&handle).unwrap().0 = "new".to_owned();
+        assert_eq!(Some(CloneAsset("old".to_owned())), cloned);
+        assert_eq!(
+            Some(CloneAsset("new".to_owned())),
+            storage.get_cloned(&handle)
+        );
+        assert_eq!(None, storage.get_cloned(&pending));
+    }
+
+    #[test]
+    fn deep_clones_have_their_own_handles() {
+        let pool = pool();
+        let mut storage = AssetStorage::<CloneAsset>::new();
+        let kept = storage.insert(CloneAsset("kept".to_owned()));
+        let changed = storage.insert(CloneAsset("old".to_owned()));
+        let removed = storage.insert(CloneAsset("removed".to_owned()));
+        storage.unload(&[removed]);
+
+        let mut clone = storage.deep_clone();
+        storage.replace(&changed, CloneAsset("new".to_owned()));
+        assert_eq!(2, clone.handles().len());
+        assert_eq!(
+            Some(&(CloneAsset("old".to_owned()), 0)),
+            clone.get_with_version(&changed)
+        );
+        assert_eq!(Some(1), storage.get_version(&changed));
+
+        let rebound = clone.rebind(kept.clone()).unwrap();
+        assert!(!Arc::ptr_eq(&kept.id, &rebound.id));
+        drop(kept);
+        storage.process(|_| unreachable!(), 0, &pool, None);
+        clone.process(|_| unreachable!(), 0, &pool, None);
+        assert_eq!(1, storage.handles().len());
+        assert_eq!("kept", clone.get(&rebound).unwrap().0);
+    }
+
+    #[test]
+    fn mapped_assets_are_replaced_and_bumped() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let handle = storage.insert(TestAsset("a".to_owned()));
+        let epoch = storage.epoch();
+
+        assert!(storage.map_asset(&handle, |asset| TestAsset(asset.0 + "b")));
+        let &(ref asset, version) = storage.get_with_version(&handle).unwrap();
+        assert_eq!(("ab", 1), (asset.0.as_str(), version));
+        assert!(storage.epoch() > epoch);
+
+        let pending = storage.insert_deferred(TestAsset("c".to_owned()));
+        assert!(!storage.map_asset(&pending, |_| unreachable!()));
+    }
+
+    #[test]
+    fn assets_of_panicking_maps_are_removed_once() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let handle = storage.insert(TestAsset("a".to_owned()));
+
+        let mapped = panic::catch_unwind(AssertUnwindSafe(|| {
+            storage.map_asset(&handle, |_| panic!("Failed to map asset"))
+        }));
+        assert!(mapped.is_err());
+        assert_eq!(HandleStatus::Absent, storage.status(&handle));
+
+        let mut drops = 0;
+        drop(handle);
+        storage.process_custom_drop(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            |_| drops += 1,
+            0,
+            &pool,
+            None,
+        );
+        // The asset has been dropped by the panicking closure already.
+        assert_eq!(0, drops);
+        assert!(storage.handles.is_empty());
+    }
+
+    #[test]
+    fn processing_panics_fail_only_their_asset() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        storage.set_catch_processing_panics(true);
+        let mut counter = ProgressCounter::new();
+
+        let handles: Vec<_> = (0..3).map(|_| storage.allocate()).collect();
+        for (handle, name) in handles.iter().zip(&["a", "panic", "c"]) {
+            let mut progress = &mut counter;
+            progress.add_assets(1);
+            new_asset(handle, name)
+                .tracker(Box::new(progress.create_tracker()))
+                .push(&storage);
+        }
+        storage.process(
+            |data| {
+                if data == "panic" {
+                    panic!("Failed to process asset");
+                }
+                Ok(ProcessingState::Loaded(TestAsset(data)))
+            },
+            0,
+            &pool,
+            None,
+        );
+
+        assert_eq!(1, counter.num_failed());
+        assert_eq!(2, counter.num_finished());
+        let errors = counter.errors();
+        assert_eq!("panic", errors[0].asset_name);
+        assert!(errors[0]
+            .error
+            .causes()
+            .any(|cause| cause.to_string().contains("Failed to process asset")));
+        assert!(storage.get(&handles[1]).is_none());
+        assert_eq!(HandleStatus::Absent, storage.status(&handles[1]));
+        assert_eq!("a", storage.get(&handles[0]).unwrap().0);
+        assert_eq!("c", storage.get(&handles[2]).unwrap().0);
+    }
+
+    #[test]
+    fn frozen_storages_keep_only_the_assets() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let a = storage.insert(TestAsset("a".to_owned()));
+        let b = storage.insert(TestAsset("b".to_owned()));
+        let pending = storage.allocate();
+        new_asset(&pending, "pending").push(&storage);
+
+        let frozen = Arc::new(storage.freeze());
+        let reader = {
+            let frozen = frozen.clone();
+            let a = a.clone();
+            std::thread::spawn(move || frozen.get(&a).map(|asset| asset.0.clone()))
+        };
+        assert_eq!(Some("a".to_owned()), reader.join().unwrap());
+        let mut names: Vec<_> = frozen.iter().map(|(_, asset)| &asset.0[..]).collect();
+        names.sort();
+        assert_eq!(vec!["a", "b"], names);
+        assert!(!frozen.contains(&pending));
+
+        let mut storage = Arc::try_unwrap(frozen).ok().unwrap().unfreeze();
+        storage.process(
+            |data| Ok(ProcessingState::Loaded(TestAsset(data))),
+            0,
+            &pool,
+            None,
+        );
+        // The data of the load was dropped with the queue.
+        assert_eq!(HandleStatus::Absent, storage.status(&pending));
+        assert_eq!("b", storage.get(&b).unwrap().0);
+
+        let c = storage.insert(TestAsset("c".to_owned()));
+        assert!(![a.id(), b.id(), pending.id()].contains(&c.id()));
+        assert_eq!("a", storage.get(&a).unwrap().0);
+    }
+
+    #[test]
+    fn epoch_advances_on_changes() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let start = storage.epoch();
+
+        let handle = storage.insert(TestAsset("a".to_owned()));
+        let inserted = storage.epoch();
+        assert!(inserted > start);
+
+        assert!(storage.get(&handle).is_some());
+        assert_eq!(inserted, storage.epoch());
+
+        storage.replace(&handle, TestAsset("b".to_owned()));
+        let replaced = storage.epoch();
+        assert!(replaced > inserted);
+
+        storage.unload(&[handle]);
+        assert!(storage.epoch() > replaced);
+    }
+
+    #[cfg(feature = "raw_handles")]
+    #[test]
+    fn raw_handles_are_rebound_to_live_assets() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let handle = storage.insert(TestAsset("a".to_owned()));
+
+        let raw = Handle::from_raw(handle.id());
+        let rebound = storage.rebind(raw).expect("Asset is alive");
+        assert_eq!("a", storage.get(&rebound).unwrap().0);
+        assert!(Arc::ptr_eq(&handle.id, &rebound.id));
+
+        storage.unload(&[handle]);
+        assert!(storage.rebind(Handle::from_raw(rebound.id())).is_none());
+    }
+
+    #[test]
+    fn status_tells_pending_from_absent_assets() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let loaded = storage.insert(TestAsset("a".to_owned()));
+        let pending = storage.insert_deferred(TestAsset("b".to_owned()));
+        let failed = storage.allocate();
+        new_asset(&failed, "failed")
+            .failed(format_err!("Broken"))
+            .push(&storage);
+        assert_eq!(HandleStatus::Loaded, storage.status(&loaded));
+        assert_eq!(HandleStatus::Pending, storage.status(&pending));
+        assert_eq!(HandleStatus::Pending, storage.status(&failed));
+
+        storage.process(|_| Err(format_err!("Broken")), 0, &pool, None);
+        assert_eq!(HandleStatus::Loaded, storage.status(&pending));
+        assert_eq!(HandleStatus::Absent, storage.status(&failed));
+
+        storage.unload(&[loaded.clone()]);
+        assert_eq!(HandleStatus::Absent, storage.status(&loaded));
+    }
+
+    #[test]
+    fn batch_inserts_keep_order() {
+        let mut storage = AssetStorage::<TestAsset>::new();
+        let handles = storage.insert_batch((0..100).map(|i| TestAsset(i.to_string())));
+
+        assert_eq!(100, handles.len());
+        for (i, handle) in handles.iter().enumerate() {
+            assert_eq!(i.to_string(), storage.get(handle).unwrap().0);
+        }
+    }
+
+    #[test]
+    fn deferred_inserts_are_added_by_process() {
+        let pool = pool();
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let handle = storage.insert_deferred(TestAsset("generated".to_owned()));
+        assert!(storage.get(&handle).is_none());
+
+        storage.process(|_| unreachable!(), 0, &pool, None);
+        assert_eq!("generated", storage.get(&handle).unwrap().0);
+        assert_eq!(Some(0), storage.get_version(&handle));
+    }
+
+    #[test]
+    fn progress_sink_receives_outcomes() {
+        let pool = pool();
+        let loader = Loader::new(".", pool.clone());
+        let mut storage = AssetStorage::<TestAsset>::new();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        storage.set_progress_sink({
+            let events = events.clone();
+            move |event| events.lock().push(event)
+        });
+
+        let loaded = loader.load_from_data("ok".to_owned(), (), &storage);
+        let _failed = loader.load_from_data("fail".to_owned(), (), &storage);
+        storage.process(
+            |data| match data.as_str() {
+                "ok" => Ok(ProcessingState::Loaded(TestAsset(data))),
+                _ => Err(format_err!("Bad data")),
+            },
+            0,
+            &pool,
+            None,
+        );
+        assert!(storage.contains(&loaded));
+
+        let events = events.lock();
+        assert_eq!(2, events.len());
+        assert_eq!(AssetProgressOutcome::Loaded, events[0].outcome);
+        assert_eq!(TestAsset::NAME, events[0].asset_type_name);
+        match events[1].outcome {
+            AssetProgressOutcome::Failed(_) => {}
+            ref other => panic!("Expected failure, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! A generic asset for lists of rows, like balance data maintained in spreadsheets.
+
+use std::{ops::Deref, slice};
+
+use amethyst_core::ecs::storage::DenseVecStorage;
+use amethyst_error::Error;
+
+use crate::{Asset, Handle, ProcessableAsset, ProcessingState};
+
+/// An asset holding a list of rows of type `R`, usually loaded from a CSV file with
+/// `CsvFormat`. Tables dereference to a slice of their rows.
+///
+/// ```rust,ignore
+/// let handle: Handle<Table<Weapon>> =
+///     loader.load("tables/weapons.csv", CsvFormat::new(), (), &storage);
+/// ```
+///
+/// Register `Processor::<Table<Weapon>>::new()` to turn loaded rows into tables; hot
+/// reloading then replaces the whole table when the file changes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table<R> {
+    rows: Vec<R>,
+}
+
+impl<R> Table<R> {
+    /// Creates a table of the given rows.
+    pub fn new(rows: Vec<R>) -> Self {
+        Table { rows }
+    }
+
+    /// Returns the rows of the table.
+    pub fn rows(&self) -> &[R] {
+        &self.rows
+    }
+
+    /// Consumes the table, returning its rows.
+    pub fn into_rows(self) -> Vec<R> {
+        self.rows
+    }
+}
+
+impl<R> Deref for Table<R> {
+    type Target = [R];
+
+    fn deref(&self) -> &[R] {
+        &self.rows
+    }
+}
+
+impl<'a, R> IntoIterator for &'a Table<R> {
+    type Item = &'a R;
+    type IntoIter = slice::Iter<'a, R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows.iter()
+    }
+}
+
+impl<R> Asset for Table<R>
+where
+    R: Send + Sync + 'static,
+{
+    const NAME: &'static str = "amethyst_assets::Table";
+    type Data = Vec<R>;
+    type HandleStorage = DenseVecStorage<Handle<Self>>;
+}
+
+impl<R> ProcessableAsset for Table<R>
+where
+    R: Send + Sync + 'static,
+{
+    fn process(data: Vec<R>) -> Result<ProcessingState<Self>, Error> {
+        Ok(ProcessingState::Loaded(Table::new(data)))
+    }
+}
@@ -0,0 +1,232 @@
+//! Defines the `FileWatcher`, which drives hot reloads using file system notifications.
+
+use std::{
+    fs::canonicalize,
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver},
+    time::Duration,
+};
+
+use amethyst_error::{format_err, Error, ResultExt};
+use fnv::FnvHashSet;
+use log::{debug, warn};
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::Loader;
+
+/// Watches directories for changed files using the file system notifications of the OS,
+/// as an alternative to polling the modification time of every reloadable asset.
+///
+/// The watcher runs on a thread of its own. Pass it to `HotReloadBundle::with_watcher`
+/// together with `HotReloadStrategy::when_files_change`, so assets are only reloaded
+/// once their files actually changed. The bundle watches the directories of all
+/// `Directory` sources the `Loader` has by then (see `watch_loader`):
+///
+/// ```rust,ignore
+/// let game_data = GameDataBuilder::default().with_bundle(
+///     HotReloadBundle::new(HotReloadStrategy::when_files_change())
+///         .with_watcher(FileWatcher::new()?),
+/// )?;
+/// ```
+///
+/// Directories are watched recursively, so files which are saved by writing a temporary
+/// file and renaming it over the original one are reported under their final path.
+pub struct FileWatcher {
+    events: Receiver<DebouncedEvent>,
+    roots: Vec<PathBuf>,
+    watcher: RecommendedWatcher,
+}
+
+impl FileWatcher {
+    /// Creates a watcher which doesn't watch any directory yet.
+    pub fn new() -> Result<Self, Error> {
+        Self::with_delay(Duration::from_millis(50))
+    }
+
+    /// Creates a watcher which reports changes once no further events arrived for
+    /// `delay`, so writing a file in several steps only reports it once.
+    pub fn with_delay(delay: Duration) -> Result<Self, Error> {
+        let (sender, events) = channel();
+        let watcher = notify::watcher(sender, delay)
+            .with_context(|_| format_err!("Failed to create file watcher"))?;
+
+        Ok(FileWatcher {
+            events,
+            roots: Vec::new(),
+            watcher,
+        })
+    }
+
+    /// Watches the directory `root` recursively, usually the one backing a `Directory`
+    /// source (see `Directory::root`).
+    ///
+    /// Changed files are reported relative to `root`, matching the names assets are
+    /// loaded with from such a source.
+    pub fn watch<P>(&mut self, root: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let root = root.as_ref();
+        if self.roots.iter().any(|watched| watched == root) {
+            return Ok(());
+        }
+        self.watcher
+            .watch(root, RecursiveMode::Recursive)
+            .with_context(|_| format_err!("Failed to watch directory {:?}", root))?;
+        self.roots.push(root.to_owned());
+        // Events carry canonical paths on some platforms.
+        if let Ok(canonical) = canonicalize(root) {
+            if canonical != root {
+                self.roots.push(canonical);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Watches the directories of all `Directory` sources of `loader`, see
+    /// `Loader::directories`. Directories which are watched already are skipped.
+    pub fn watch_loader(&mut self, loader: &Loader) -> Result<(), Error> {
+        for root in loader.directories() {
+            self.watch(root)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the paths of the files which changed since the last call, relative to the
+    /// watched directory they are in and using `/` as separator.
+    pub fn changed_files(&mut self) -> FnvHashSet<String> {
+        let mut changed = FnvHashSet::default();
+        while let Ok(event) = self.events.try_recv() {
+            let path = match event {
+                DebouncedEvent::Create(path) | DebouncedEvent::Write(path) => path,
+                // Editors saving through a temporary file rename it to the final path.
+                DebouncedEvent::Rename(_, path) => path,
+                DebouncedEvent::Error(e, path) => {
+                    warn!("Error while watching {:?} for changes: {}", path, e);
+                    continue;
+                }
+                _ => continue,
+            };
+            match self.relative(&path) {
+                Some(name) => {
+                    debug!("{:?} changed", name);
+                    changed.insert(name);
+                }
+                None => debug!(
+                    "Ignoring change of {:?} outside of watched directories",
+                    path
+                ),
+            }
+        }
+
+        changed
+    }
+
+    /// Turns an absolute path into the name of an asset, relative to its watched directory.
+    fn relative(&self, path: &Path) -> Option<String> {
+        let relative = self
+            .roots
+            .iter()
+            .find_map(|root| path.strip_prefix(root).ok())?;
+        let components = relative
+            .components()
+            .map(|c| c.as_os_str().to_str())
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(components.join("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        fs::{self, create_dir_all, remove_dir_all},
+        process,
+        sync::Arc,
+        thread::sleep,
+        time::Duration,
+    };
+
+    use amethyst_core::ecs::prelude::VecStorage;
+    use amethyst_error::Error;
+    use rayon::ThreadPoolBuilder;
+
+    use super::FileWatcher;
+    use crate::{Asset, AssetStorage, Format, Handle, Loader, ProcessingState};
+
+    struct Text(String);
+
+    impl Asset for Text {
+        const NAME: &'static str = "test::Text";
+        type Data = String;
+        type HandleStorage = VecStorage<Handle<Text>>;
+    }
+
+    #[derive(Clone, Debug)]
+    struct TextFormat;
+
+    impl Format<String> for TextFormat {
+        fn name(&self) -> &'static str {
+            "TEXT"
+        }
+
+        fn import_simple(&self, bytes: Vec<u8>) -> Result<String, Error> {
+            Ok(String::from_utf8(bytes)?)
+        }
+    }
+
+    #[test]
+    fn only_changed_files_are_reloaded() {
+        let root = env::temp_dir().join(format!("amethyst_assets_watcher_{}", process::id()));
+        create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("kept.txt"), "kept").unwrap();
+        fs::write(root.join("sub").join("changed.txt"), "old").unwrap();
+
+        let pool = Arc::new(
+            ThreadPoolBuilder::new()
+                .num_threads(1)
+                .build()
+                .expect("Invalid config"),
+        );
+        let loader = Loader::new(&root, pool.clone());
+        let mut storage = AssetStorage::<Text>::new();
+        let mut watcher = FileWatcher::with_delay(Duration::from_millis(10)).unwrap();
+        watcher.watch_loader(&loader).unwrap();
+
+        let kept = loader.load("kept.txt", TextFormat, (), &storage);
+        let changed = loader.load("sub/changed.txt", TextFormat, (), &storage);
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(Text(data))),
+            &pool,
+            |storage| storage.contains(&kept) && storage.contains(&changed),
+        );
+
+        fs::write(root.join("sub").join("changed.txt"), "new").unwrap();
+        let mut files = watcher.changed_files();
+        for _ in 0..5000 {
+            if !files.is_empty() {
+                break;
+            }
+            sleep(Duration::from_millis(1));
+            files = watcher.changed_files();
+        }
+        let files: Vec<_> = files.iter().map(String::as_str).collect();
+        assert_eq!(vec!["sub/changed.txt"], files);
+
+        for file in &files {
+            storage.notify_file_changed(file);
+        }
+        storage.process_until(
+            |data| Ok(ProcessingState::Loaded(Text(data))),
+            &pool,
+            |storage| storage.get_version(&changed) == Some(1),
+        );
+        assert_eq!("new", storage.get(&changed).unwrap().0);
+        assert_eq!(Some(0), storage.get_version(&kept));
+
+        remove_dir_all(&root).unwrap();
+    }
+}
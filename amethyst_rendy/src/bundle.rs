@@ -1,6 +1,7 @@
 //! A home of [RenderingBundle] with it's rendering plugins system and all types directly related to it.
 
 use crate::{
+    formats::image_metadata::ImageMetadata,
     mtl::Material,
     rendy::{
         factory::Factory,
@@ -67,6 +68,11 @@ impl<'a, 'b, B: Backend> SystemBundle<'a, 'b> for RenderingBundle<B> {
         builder.add(MeshProcessor::<B>::default(), "mesh_processor", &[]);
         builder.add(TextureProcessor::<B>::default(), "texture_processor", &[]);
         builder.add(Processor::<Material>::new(), "material_processor", &[]);
+        builder.add(
+            Processor::<ImageMetadata>::new(),
+            "image_metadata_processor",
+            &[],
+        );
         builder.add(
             Processor::<SpriteSheet>::new(),
             "sprite_sheet_processor",
@@ -0,0 +1,409 @@
+//! Reading the size of images from their headers, without decoding the pixels.
+use std::{
+    fmt,
+    io::{self, Read},
+    path::Path,
+    sync::Arc,
+};
+
+use amethyst_assets::{Asset, Format, FormatValue, Handle, Reload, SingleFile, Source};
+use amethyst_core::ecs::DenseVecStorage;
+use amethyst_error::{format_err, Error, ResultExt};
+use serde::{Deserialize, Serialize};
+
+use super::texture::IMAGE_EXTENSIONS;
+
+/// The container formats `ImageMetadataFormat` can read.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum ImageContainer {
+    /// Portable Network Graphics
+    Png,
+    /// JPEG File Interchange Format
+    Jpeg,
+    /// Truevision TGA
+    Tga,
+    /// Windows Bitmap
+    Bmp,
+}
+
+impl ImageContainer {
+    fn from_extension(name: &str) -> Option<Self> {
+        let extension = Path::new(name).extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Some(ImageContainer::Png),
+            "jpg" | "jpeg" => Some(ImageContainer::Jpeg),
+            "tga" => Some(ImageContainer::Tga),
+            "bmp" => Some(ImageContainer::Bmp),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ImageContainer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match *self {
+            ImageContainer::Png => "PNG",
+            ImageContainer::Jpeg => "JPEG",
+            ImageContainer::Tga => "TGA",
+            ImageContainer::Bmp => "BMP",
+        })
+    }
+}
+
+/// The channels stored for each pixel of an image.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum ImageColorType {
+    /// Luminance only
+    Gray,
+    /// Luminance and alpha
+    GrayAlpha,
+    /// Red, green and blue
+    Rgb,
+    /// Red, green, blue and alpha
+    Rgba,
+    /// Indices into a palette
+    Indexed,
+    /// Cyan, magenta, yellow and black
+    Cmyk,
+}
+
+/// The size and layout of an image, read from its header by `ImageMetadataFormat`.
+///
+/// This is an asset of its own, so UI code can reserve space for an image long before
+/// the `Texture` itself has been loaded.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ImageMetadata {
+    /// The width of the image in pixels.
+    pub width: u32,
+    /// The height of the image in pixels.
+    pub height: u32,
+    /// The channels stored for each pixel.
+    pub color_type: ImageColorType,
+    /// The container format the image is stored in.
+    pub container: ImageContainer,
+}
+
+impl Asset for ImageMetadata {
+    const NAME: &'static str = "ImageMetadata";
+    type Data = Self;
+    type HandleStorage = DenseVecStorage<Handle<Self>>;
+}
+
+/// Format reading `ImageMetadata` from PNG, JPEG, TGA and BMP images.
+///
+/// Only the header of the image is read, the pixels aren't decoded. The format uses the
+/// same extensions as `ImageFormat`, so an image can be loaded both as `ImageMetadata` and
+/// as `Texture` under the same name.
+///
+/// TGA files have no signature, so they are only recognized by their extension.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct ImageMetadataFormat;
+
+impl Format<ImageMetadata> for ImageMetadataFormat {
+    fn name(&self) -> &'static str {
+        "IMAGE_METADATA"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        IMAGE_EXTENSIONS
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<ImageMetadata, Error> {
+        read_metadata(&mut &bytes[..], None)
+    }
+
+    fn import(
+        &self,
+        name: String,
+        source: Arc<dyn Source>,
+        create_reload: Option<Box<dyn Format<ImageMetadata>>>,
+    ) -> Result<FormatValue<ImageMetadata>, Error> {
+        let modified = match create_reload {
+            Some(_) => Some(source.modified(&name)?),
+            None => None,
+        };
+        let mut reader = source.open(&name)?;
+        let data = read_metadata(&mut reader, ImageContainer::from_extension(&name))
+            .with_context(|_| format_err!("Failed reading the header of image {:?}", name))?;
+        let reload: Option<Box<dyn Reload<ImageMetadata>>> = match (create_reload, modified) {
+            (Some(format), Some(modified)) => {
+                Some(Box::new(SingleFile::new(format, modified, name, source)))
+            }
+            _ => None,
+        };
+
+        Ok(FormatValue { data, reload })
+    }
+}
+
+/// Detects the container of an image from its signature, falling back to the extension
+/// of its name, and reads the header.
+fn read_metadata(
+    reader: &mut dyn Read,
+    extension: Option<ImageContainer>,
+) -> Result<ImageMetadata, Error> {
+    let mut magic = [0; 2];
+    let detected = match reader.read_exact(&mut magic) {
+        Ok(()) => match magic {
+            [0x89, b'P'] => Some(ImageContainer::Png),
+            [0xFF, 0xD8] => Some(ImageContainer::Jpeg),
+            [b'B', b'M'] => Some(ImageContainer::Bmp),
+            _ => None,
+        },
+        Err(_) => None,
+    };
+    let container = detected
+        .or(extension)
+        .ok_or_else(|| format_err!("Unrecognized image container"))?;
+    if detected.is_none() && container != ImageContainer::Tga {
+        return Err(corrupted(container, "missing signature"));
+    }
+
+    let header = Header {
+        container,
+        magic,
+        reader,
+    };
+    let (width, height, color_type) = match container {
+        ImageContainer::Png => header.png()?,
+        ImageContainer::Jpeg => header.jpeg()?,
+        ImageContainer::Tga => header.tga()?,
+        ImageContainer::Bmp => header.bmp()?,
+    };
+    if width == 0 || height == 0 {
+        return Err(corrupted(container, "image is empty"));
+    }
+
+    Ok(ImageMetadata {
+        width,
+        height,
+        color_type,
+        container,
+    })
+}
+
+fn corrupted(container: ImageContainer, reason: &str) -> Error {
+    format_err!("Corrupted {} header: {}", container, reason)
+}
+
+struct Header<'a> {
+    container: ImageContainer,
+    magic: [u8; 2],
+    reader: &'a mut dyn Read,
+}
+
+impl Header<'_> {
+    /// Reads the first `len` bytes of the image, including the already read signature.
+    fn read(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+        let mut bytes = vec![0; len];
+        bytes[..2].copy_from_slice(&self.magic);
+        self.read_exact(&mut bytes[2..])?;
+        Ok(bytes)
+    }
+
+    fn read_exact(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+        let container = self.container;
+        self.reader
+            .read_exact(bytes)
+            .map_err(|e| corrupted(container, "unexpected end of data").with_source(e))
+    }
+
+    fn png(mut self) -> Result<(u32, u32, ImageColorType), Error> {
+        let h = self.read(26)?;
+        if h[..8] != b"\x89PNG\r\n\x1a\n"[..] {
+            return Err(corrupted(self.container, "invalid signature"));
+        }
+        if &h[12..16] != b"IHDR" {
+            return Err(corrupted(self.container, "missing IHDR chunk"));
+        }
+        let color_type = match h[25] {
+            0 => ImageColorType::Gray,
+            2 => ImageColorType::Rgb,
+            3 => ImageColorType::Indexed,
+            4 => ImageColorType::GrayAlpha,
+            6 => ImageColorType::Rgba,
+            _ => return Err(corrupted(self.container, "unknown color type")),
+        };
+
+        Ok((be_u32(&h[16..]), be_u32(&h[20..]), color_type))
+    }
+
+    fn jpeg(mut self) -> Result<(u32, u32, ImageColorType), Error> {
+        let container = self.container;
+        // Walks the segments until the frame header, skipping metadata like EXIF.
+        loop {
+            let mut marker = [0; 2];
+            self.read_exact(&mut marker)?;
+            if marker[0] != 0xFF {
+                return Err(corrupted(self.container, "invalid segment marker"));
+            }
+            while marker[1] == 0xFF {
+                self.read_exact(&mut marker[1..])?;
+            }
+            match marker[1] {
+                0x01 | 0xD0..=0xD7 => continue,
+                0xD9 | 0xDA => {
+                    return Err(corrupted(self.container, "missing frame header"));
+                }
+                _ => {}
+            }
+
+            let mut len = [0; 2];
+            self.read_exact(&mut len)?;
+            let len = u64::from(u16::from_be_bytes(len));
+            if len < 2 {
+                return Err(corrupted(self.container, "invalid segment length"));
+            }
+            match marker[1] {
+                0xC4 | 0xC8 | 0xCC => {}
+                0xC0..=0xCF => {
+                    let mut frame = [0; 6];
+                    self.read_exact(&mut frame)?;
+                    let color_type = match frame[5] {
+                        1 => ImageColorType::Gray,
+                        3 => ImageColorType::Rgb,
+                        4 => ImageColorType::Cmyk,
+                        _ => return Err(corrupted(self.container, "unknown component count")),
+                    };
+                    let height = u16::from_be_bytes([frame[1], frame[2]]);
+                    let width = u16::from_be_bytes([frame[3], frame[4]]);
+                    return Ok((u32::from(width), u32::from(height), color_type));
+                }
+                _ => {}
+            }
+            let skipped = io::copy(&mut (&mut self.reader).take(len - 2), &mut io::sink())
+                .map_err(|e| corrupted(container, "unexpected end of data").with_source(e))?;
+            if skipped != len - 2 {
+                return Err(corrupted(container, "unexpected end of data"));
+            }
+        }
+    }
+
+    fn tga(mut self) -> Result<(u32, u32, ImageColorType), Error> {
+        let h = self.read(18)?;
+        let (depth, alpha_bits) = (h[16], h[17] & 0x0F);
+        let color_type = match h[2] {
+            1 | 9 => ImageColorType::Indexed,
+            2 | 10 if depth == 32 || alpha_bits != 0 => ImageColorType::Rgba,
+            2 | 10 => ImageColorType::Rgb,
+            3 | 11 if depth == 16 || alpha_bits != 0 => ImageColorType::GrayAlpha,
+            3 | 11 => ImageColorType::Gray,
+            _ => return Err(corrupted(self.container, "unknown image type")),
+        };
+
+        Ok((le_u16(&h[12..]), le_u16(&h[14..]), color_type))
+    }
+
+    fn bmp(mut self) -> Result<(u32, u32, ImageColorType), Error> {
+        let mut h = self.read(18)?;
+        let info_len = le_u32(&h[14..]) as usize;
+        let (width, height, depth) = match info_len {
+            // `BITMAPCOREHEADER`
+            12 => {
+                h.resize(26, 0);
+                self.read_exact(&mut h[18..])?;
+                (le_u16(&h[18..]), le_u16(&h[20..]), le_u16(&h[24..]))
+            }
+            len if len >= 40 => {
+                h.resize(30, 0);
+                self.read_exact(&mut h[18..])?;
+                // Negative heights mark images stored top-down.
+                let width = le_u32(&h[18..]) as i32;
+                let height = le_u32(&h[22..]) as i32;
+                if width < 0 {
+                    return Err(corrupted(self.container, "negative width"));
+                }
+                (width as u32, height.wrapping_abs() as u32, le_u16(&h[28..]))
+            }
+            _ => return Err(corrupted(self.container, "unknown info header")),
+        };
+        let color_type = match depth {
+            1 | 4 | 8 => ImageColorType::Indexed,
+            16 | 24 => ImageColorType::Rgb,
+            32 => ImageColorType::Rgba,
+            _ => return Err(corrupted(self.container, "unknown bit depth")),
+        };
+
+        Ok((width, height, color_type))
+    }
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn le_u16(bytes: &[u8]) -> u32 {
+    u32::from(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_headers() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 1, 0, 0, 0, 0, 64, 8, 6, 0, 0, 0]);
+        let meta = read_metadata(&mut &png[..], None).unwrap();
+        assert_eq!((256, 64), (meta.width, meta.height));
+        assert_eq!(ImageColorType::Rgba, meta.color_type);
+
+        // An APP0 segment followed by a baseline frame header.
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xE0, 0, 4, 0, 0, 0xFF, 0xC0, 0, 11, 8, 0, 48, 0, 32, 3,
+        ];
+        let meta = read_metadata(&mut &jpeg[..], None).unwrap();
+        assert_eq!((32, 48), (meta.width, meta.height));
+        assert_eq!(ImageContainer::Jpeg, meta.container);
+
+        let mut tga = vec![0; 18];
+        tga[2] = 2;
+        tga[12] = 7;
+        tga[14] = 5;
+        tga[16] = 24;
+        let meta = read_metadata(&mut &tga[..], Some(ImageContainer::Tga)).unwrap();
+        assert_eq!((7, 5), (meta.width, meta.height));
+        assert_eq!(ImageColorType::Rgb, meta.color_type);
+
+        // An OS/2 style `BITMAPCOREHEADER` with 16 bit dimensions.
+        let mut bmp = b"BM".to_vec();
+        bmp.resize(26, 0);
+        bmp[14] = 12;
+        bmp[18] = 3;
+        bmp[20] = 2;
+        bmp[24] = 8;
+        let meta = read_metadata(&mut &bmp[..], None).unwrap();
+        assert_eq!((3, 2), (meta.width, meta.height));
+        assert_eq!(ImageColorType::Indexed, meta.color_type);
+        assert_eq!(ImageContainer::Bmp, meta.container);
+
+        // A `BITMAPINFOHEADER` of an image stored top-down.
+        let mut bmp = b"BM".to_vec();
+        bmp.resize(30, 0);
+        bmp[14] = 40;
+        bmp[18..22].copy_from_slice(&5i32.to_le_bytes());
+        bmp[22..26].copy_from_slice(&(-4i32).to_le_bytes());
+        bmp[28] = 32;
+        let meta = read_metadata(&mut &bmp[..], None).unwrap();
+        assert_eq!((5, 4), (meta.width, meta.height));
+        assert_eq!(ImageColorType::Rgba, meta.color_type);
+
+        bmp[18..22].copy_from_slice(&(-5i32).to_le_bytes());
+        let e = read_metadata(&mut &bmp[..], None).unwrap_err();
+        assert!(e.to_string().contains("negative width"), "{}", e);
+    }
+
+    #[test]
+    fn corrupted_headers_name_the_container() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0".to_vec();
+        let e = read_metadata(&mut &png[..], None).unwrap_err();
+        assert!(e.to_string().contains("Corrupted PNG header"), "{}", e);
+
+        let e = read_metadata(&mut &b"GIF89a"[..], Some(ImageContainer::Bmp)).unwrap_err();
+        assert!(e.to_string().contains("Corrupted BMP header"), "{}", e);
+    }
+}
@@ -84,6 +84,10 @@ impl Default for ImageFormat {
     }
 }
 
+/// The extensions of the image files loaded with `ImageFormat`, which are shared with
+/// `ImageMetadataFormat`.
+pub(crate) const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "tga", "bmp"];
+
 amethyst_assets::register_format_type!(TextureData);
 
 amethyst_assets::register_format!("IMAGE", ImageFormat as TextureData);
@@ -92,6 +96,10 @@ impl Format<TextureData> for ImageFormat {
         "IMAGE"
     }
 
+    fn extensions(&self) -> &[&'static str] {
+        IMAGE_EXTENSIONS
+    }
+
     fn import_simple(&self, bytes: Vec<u8>) -> Result<TextureData, Error> {
         load_from_image(std::io::Cursor::new(&bytes), self.0.clone())
             .map(|builder| builder.into())
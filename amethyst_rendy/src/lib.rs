@@ -94,6 +94,7 @@ pub use crate::{
     bundle::{RenderPlugin, RenderingBundle},
     camera::{ActiveCamera, Camera},
     formats::{
+        image_metadata::{ImageColorType, ImageContainer, ImageMetadata, ImageMetadataFormat},
         mesh::MeshPrefab,
         texture::{ImageFormat, TexturePrefab},
     },
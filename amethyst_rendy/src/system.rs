@@ -11,7 +11,9 @@ use crate::{
     types::{Backend, Mesh, Texture},
     visibility::Visibility,
 };
-use amethyst_assets::{AssetStorage, Handle, HotReloadStrategy, ProcessingState, ThreadPool};
+use amethyst_assets::{
+    AssetStorage, Handle, HotReloadStrategy, HotReloadStrategyFor, ProcessingState, ThreadPool,
+};
 use amethyst_core::{
     components::Transform,
     ecs::{
@@ -195,12 +197,13 @@ impl<'a, B: Backend> System<'a> for MeshProcessor<B> {
         Read<'a, Time>,
         ReadExpect<'a, Arc<ThreadPool>>,
         Option<Read<'a, HotReloadStrategy>>,
+        Option<Read<'a, HotReloadStrategyFor<Mesh>>>,
         ReadExpect<'a, Factory<B>>,
     );
 
     fn run(
         &mut self,
-        (mut mesh_storage, queue_id, time, pool, strategy, factory): Self::SystemData,
+        (mut mesh_storage, queue_id, time, pool, strategy, strategy_for, factory): Self::SystemData,
     ) {
         #[cfg(feature = "profiler")]
         profile_scope!("mesh_processor");
@@ -218,7 +221,10 @@ impl<'a, B: Backend> System<'a> for MeshProcessor<B> {
             },
             time.frame_number(),
             &**pool,
-            strategy.as_ref().map(Deref::deref),
+            HotReloadStrategyFor::select(
+                strategy_for.as_ref().map(Deref::deref),
+                strategy.as_ref().map(Deref::deref),
+            ),
         );
     }
 }
@@ -234,12 +240,13 @@ impl<'a, B: Backend> System<'a> for TextureProcessor<B> {
         Read<'a, Time>,
         ReadExpect<'a, Arc<ThreadPool>>,
         Option<Read<'a, HotReloadStrategy>>,
+        Option<Read<'a, HotReloadStrategyFor<Texture>>>,
         WriteExpect<'a, Factory<B>>,
     );
 
     fn run(
         &mut self,
-        (mut texture_storage, queue_id, time, pool, strategy, mut factory): Self::SystemData,
+        (mut texture_storage, queue_id, time, pool, strategy, strategy_for, mut factory): Self::SystemData,
     ) {
         #[cfg(feature = "profiler")]
         profile_scope!("texture_processor");
@@ -266,7 +273,10 @@ impl<'a, B: Backend> System<'a> for TextureProcessor<B> {
             },
             time.frame_number(),
             &**pool,
-            strategy.as_ref().map(Deref::deref),
+            HotReloadStrategyFor::select(
+                strategy_for.as_ref().map(Deref::deref),
+                strategy.as_ref().map(Deref::deref),
+            ),
         );
     }
 }
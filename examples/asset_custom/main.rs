@@ -83,10 +83,9 @@ impl Source for CodeSource {
 
 impl SimpleState for LoadingState {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
-        {
-            let mut loader = data.world.write_resource::<Loader>();
-            loader.add_source("code_source", CodeSource);
-        }
+        data.world
+            .read_resource::<Loader>()
+            .add_source("code_source", CodeSource);
 
         let loader = &data.world.read_resource::<Loader>();
 
@@ -724,7 +724,7 @@ where
         O: Source,
     {
         {
-            let mut loader = self.world.write_resource::<Loader>();
+            let loader = self.world.read_resource::<Loader>();
             loader.add_source(name, store);
         }
         self
@@ -776,7 +776,7 @@ where
         O: Source,
     {
         {
-            let mut loader = self.world.write_resource::<Loader>();
+            let loader = self.world.read_resource::<Loader>();
             loader.set_default_source(store);
         }
         self